@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+use vivalaakam_seattle_collection::{CollectionField, Decimal, FieldType, Pointer, TimeStamp};
+
+pub fn timestamp_value(value: DateTime<Utc>) -> Value {
+    TimeStamp(value).to_value()
+}
+
+pub fn coerce_value(field: &CollectionField, value: &Value) -> Value {
+    match &field.field_type {
+        FieldType::String => value
+            .as_str()
+            .map(|v| Value::String(v.to_string()))
+            .unwrap_or(Value::Null),
+        FieldType::Number => value.as_f64().map(|v| json!(v)).unwrap_or(Value::Null),
+        FieldType::Integer => FieldType::integer_value(value)
+            .filter(|v| i32::try_from(*v).is_ok())
+            .map(|v| json!(v))
+            .unwrap_or(Value::Null),
+        FieldType::BigInt => FieldType::integer_value(value)
+            .map(|v| json!(v))
+            .unwrap_or(Value::Null),
+        FieldType::Decimal { scale, .. } => Decimal::from_value(value)
+            .map(|v| v.round(*scale).to_value())
+            .unwrap_or(Value::Null),
+        FieldType::Boolean => value.as_bool().map(Value::Bool).unwrap_or(Value::Null),
+        FieldType::Array | FieldType::Object => value.clone(),
+        FieldType::TimeStamp => TimeStamp::from_value(value)
+            .map(|v| v.to_value())
+            .unwrap_or(Value::Null),
+        // queries may compare pointers with a plain object id
+        FieldType::Pointer { target } => Pointer::from_value(value)
+            .map(|pointer| pointer.object_id)
+            .or_else(|| value.as_str().map(|v| v.to_string()))
+            .map(|object_id| Pointer::new(target, &object_id).to_value())
+            .unwrap_or(Value::Null),
+        // rows keep the related ids, queries compare against a single one
+        FieldType::Relation { .. } => match value {
+            Value::Array(_) => value.clone(),
+            _ => Pointer::from_value(value)
+                .map(|pointer| pointer.object_id)
+                .or_else(|| value.as_str().map(|v| v.to_string()))
+                .map(Value::String)
+                .unwrap_or(Value::Null),
+        },
+    }
+}
@@ -0,0 +1,952 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, SubsecRound, Utc};
+use serde_json::{Map, Value};
+use tracing::{debug, info};
+
+use vivalaakam_seattle_collection::{
+    make_id, Collection, CollectionField, FieldOperation, FieldType, OnDelete, OrderDirection,
+    Pointer, Precondition, Projection, QueryOptions, SchemaPolicy, Storage, StorageError, Where,
+    WhereQuery,
+};
+
+use crate::coerce_value::{coerce_value, timestamp_value};
+use crate::match_where::{compare_values, match_where, resolve_field};
+
+type Row = Map<String, Value>;
+
+#[derive(Clone, Default)]
+struct MemoryState {
+    schemas: HashMap<String, Collection>,
+    tables: HashMap<String, Vec<Row>>,
+}
+
+fn row_id(row: &Row) -> &str {
+    row.get(ID_FIELD)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+}
+
+impl MemoryState {
+    // applies what `changed` did to `base` on top of this state, so writes made here since
+    // `base` was taken survive unless `changed` touched the same row
+    fn merge(&mut self, base: &MemoryState, changed: &MemoryState) {
+        for (name, schema) in &changed.schemas {
+            if base.schemas.get(name) != Some(schema) {
+                self.schemas.insert(name.to_string(), schema.clone());
+            }
+        }
+
+        for name in base.schemas.keys() {
+            if !changed.schemas.contains_key(name) {
+                self.schemas.remove(name);
+            }
+        }
+
+        for (name, rows) in &changed.tables {
+            let base_rows: HashMap<&str, &Row> = base
+                .tables
+                .get(name)
+                .map(|rows| rows.iter().map(|row| (row_id(row), row)).collect())
+                .unwrap_or_default();
+
+            let table = self.tables.entry(name.to_string()).or_default();
+
+            for row in rows {
+                if base_rows.get(row_id(row)) == Some(&row) {
+                    continue;
+                }
+
+                match table.iter_mut().find(|r| row_id(r) == row_id(row)) {
+                    Some(current) => *current = row.clone(),
+                    None => table.push(row.clone()),
+                }
+            }
+
+            let ids = rows.iter().map(row_id).collect::<HashSet<_>>();
+
+            table.retain(|row| !base_rows.contains_key(row_id(row)) || ids.contains(&row_id(row)));
+        }
+
+        for name in base.tables.keys() {
+            if !changed.tables.contains_key(name) {
+                self.tables.remove(name);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct StoreMemory {
+    state: Arc<Mutex<MemoryState>>,
+    // set for transactions, which work on a snapshot of the parent state
+    parent: Option<Arc<Mutex<MemoryState>>>,
+    // the parent state the snapshot was taken from
+    base: Arc<MemoryState>,
+}
+
+const ID_FIELD: &str = "id";
+const CREATED_AT_FIELD: &str = "created_at";
+const UPDATED_AT_FIELD: &str = "updated_at";
+
+impl StoreMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn matches_precondition(
+        collection: &Collection,
+        row: &Row,
+        precondition: &Precondition,
+    ) -> bool {
+        precondition.matches_etag(row) && match_where(collection, row, &precondition.query)
+    }
+
+    fn new_row(collection: &Collection, data: &Value) -> (String, Row) {
+        let collection_id = match data.get(ID_FIELD) {
+            Some(v) => v.as_str().unwrap_or(&make_id(10)).to_string(),
+            None => make_id(10),
+        };
+
+        let mut row = Row::new();
+        row.insert(
+            ID_FIELD.to_string(),
+            Value::String(collection_id.to_string()),
+        );
+
+        for field in &collection.fields {
+            match field.name.as_str() {
+                ID_FIELD | CREATED_AT_FIELD | UPDATED_AT_FIELD => {
+                    continue;
+                }
+                _ => {
+                    if let Some(v) = data.get(field.name.as_str()) {
+                        let v = Self::apply_operation(v, &Value::Null);
+                        row.insert(field.name.to_string(), coerce_value(field, &v));
+                    }
+                }
+            }
+        }
+
+        let now = timestamp_value(Self::now());
+        row.insert(CREATED_AT_FIELD.to_string(), now.clone());
+        row.insert(UPDATED_AT_FIELD.to_string(), now);
+
+        (collection_id, row)
+    }
+
+    // id and unique fields hold a value at most once, nulls don't count
+    fn check_unique(collection: &Collection, rows: &[Row]) -> Result<(), StorageError> {
+        let fields = collection
+            .fields
+            .iter()
+            .filter(|field| field.name == ID_FIELD || field.unique.unwrap_or_default());
+
+        for field in fields {
+            let mut values = HashSet::new();
+
+            for value in rows
+                .iter()
+                .filter_map(|row| row.get(&field.name))
+                .filter(|value| !value.is_null())
+            {
+                if !values.insert(value.to_string()) {
+                    return Err(StorageError::DBErr {
+                        collection: collection.name.to_string(),
+                        err: format!("duplicate key value: {} = {value}", field.name),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // writes to a copy of the table, which replaces it only if the unique fields still hold
+    fn write_rows<R>(
+        state: &mut MemoryState,
+        collection: &Collection,
+        write: impl FnOnce(&mut Vec<Row>) -> Result<R, StorageError>,
+    ) -> Result<R, StorageError> {
+        let rows = state
+            .tables
+            .get_mut(&collection.name)
+            .ok_or_else(|| StorageError::DBErr {
+                collection: collection.name.to_string(),
+                err: format!(r#"relation "{}" does not exist"#, collection.name),
+            })?;
+
+        let mut next = rows.clone();
+        let result = write(&mut next)?;
+
+        Self::check_unique(collection, &next)?;
+        *rows = next;
+
+        Ok(result)
+    }
+
+    // removes matching rows and returns their ids
+    fn remove_rows(rows: &mut Vec<Row>, predicate: impl Fn(&Row) -> bool) -> Vec<String> {
+        let (removed, kept): (Vec<Row>, Vec<Row>) =
+            std::mem::take(rows).into_iter().partition(predicate);
+        *rows = kept;
+
+        removed
+            .iter()
+            .filter_map(|row| row.get(ID_FIELD).and_then(|v| v.as_str()))
+            .map(|id| id.to_string())
+            .collect()
+    }
+
+    // copies of the tables a delete from `collection_name` can reach through pointers and relations
+    fn delete_scope(state: &MemoryState, collection_name: &str) -> HashMap<String, Vec<Row>> {
+        let mut names = vec![collection_name.to_string()];
+        let mut index = 0;
+
+        while let Some(name) = names.get(index).cloned() {
+            index += 1;
+
+            for schema in state.schemas.values() {
+                let references = schema.fields.iter().any(|field| match &field.field_type {
+                    FieldType::Pointer { target } | FieldType::Relation { target } => {
+                        *target == name
+                    }
+                    _ => false,
+                });
+
+                if references && !names.contains(&schema.name) {
+                    names.push(schema.name.to_string());
+                }
+            }
+        }
+
+        names
+            .into_iter()
+            .filter_map(|name| state.tables.get(&name).map(|rows| (name, rows.clone())))
+            .collect()
+    }
+
+    // follows the on_delete rules of pointers to deleted rows, as foreign keys do in postgres.
+    // callers apply it to the copies from `delete_scope` and keep them only on success
+    fn delete_references(
+        schemas: &HashMap<String, Collection>,
+        tables: &mut HashMap<String, Vec<Row>>,
+        collection_name: &str,
+        ids: &[String],
+    ) -> anyhow::Result<(), StorageError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let relations = schemas
+            .values()
+            .flat_map(|schema| {
+                schema
+                    .fields
+                    .iter()
+                    .filter(|field| {
+                        matches!(&field.field_type, FieldType::Relation { target } if target == collection_name)
+                    })
+                    .map(|field| (schema.name.to_string(), field.name.to_string()))
+            })
+            .collect::<Vec<_>>();
+
+        // relations pointing at deleted rows go with them
+        for (name, field) in relations {
+            for row in tables.get_mut(&name).into_iter().flatten() {
+                if let Some(Value::Array(related)) = row.get_mut(&field) {
+                    related.retain(|id| !id.as_str().is_some_and(|id| ids.iter().any(|v| v == id)));
+                }
+            }
+        }
+
+        let references = schemas
+            .values()
+            .flat_map(|schema| {
+                schema
+                    .fields
+                    .iter()
+                    .filter_map(|field| match &field.field_type {
+                        FieldType::Pointer { target } if target == collection_name => {
+                            field.on_delete.map(|on_delete| {
+                                (schema.name.to_string(), field.name.to_string(), on_delete)
+                            })
+                        }
+                        _ => None,
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        for (name, field, on_delete) in references {
+            let Some(rows) = tables.get_mut(&name) else {
+                continue;
+            };
+
+            let referenced = |row: &Row| {
+                row.get(&field)
+                    .and_then(Pointer::from_value)
+                    .filter(|pointer| ids.contains(&pointer.object_id))
+                    .map(|pointer| pointer.object_id)
+            };
+
+            match on_delete {
+                OnDelete::Restrict => {
+                    if let Some(id) = rows.iter().find_map(referenced) {
+                        return Err(StorageError::Referenced {
+                            collection: collection_name.to_string(),
+                            id,
+                        });
+                    }
+                }
+                OnDelete::SetNull => {
+                    for row in rows.iter_mut().filter(|row| referenced(row).is_some()) {
+                        row.insert(field.to_string(), Value::Null);
+                    }
+                }
+                OnDelete::Cascade => {
+                    let deleted = Self::remove_rows(rows, |row| referenced(row).is_some());
+                    Self::delete_references(schemas, tables, &name, &deleted)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // only relation operations reach the storage on insert
+    fn apply_operation(value: &Value, current: &Value) -> Value {
+        match FieldOperation::from_value(value) {
+            Some(Ok(operation)) => operation.apply(current),
+            _ => value.clone(),
+        }
+    }
+
+    // `$relatedTo` needs the owning row, so it becomes an id filter before matching
+    fn resolve_related_to(state: &MemoryState, query: WhereQuery) -> WhereQuery {
+        let resolve = |queries: Vec<WhereQuery>| {
+            queries
+                .into_iter()
+                .map(|query| Self::resolve_related_to(state, query))
+                .collect::<Vec<_>>()
+        };
+
+        let mut query = WhereQuery {
+            and: resolve(query.and),
+            or: resolve(query.or),
+            nor: resolve(query.nor),
+            ..query
+        };
+
+        if let Some(related_to) = query.related_to.take() {
+            let ids = state
+                .tables
+                .get(&related_to.object.class_name)
+                .and_then(|rows| {
+                    rows.iter().find(|row| {
+                        row.get(ID_FIELD).and_then(|v| v.as_str())
+                            == Some(&related_to.object.object_id)
+                    })
+                })
+                .and_then(|row| row.get(&related_to.key))
+                .and_then(|ids| ids.as_array())
+                .cloned()
+                .unwrap_or_default();
+
+            query.and.push(WhereQuery {
+                fields: HashMap::from([(
+                    ID_FIELD.to_string(),
+                    Where {
+                        in_: Some(ids),
+                        ..Where::default()
+                    },
+                )]),
+                ..WhereQuery::default()
+            });
+        }
+
+        query
+    }
+
+    fn update_values<'a>(
+        collection: &'a Collection,
+        data: &Value,
+    ) -> (Row, Vec<(&'a CollectionField, FieldOperation)>) {
+        let mut update_fields = Row::new();
+        let mut operations = vec![];
+
+        for field in &collection.fields {
+            match field.name.as_str() {
+                CREATED_AT_FIELD | UPDATED_AT_FIELD => {
+                    continue;
+                }
+                ID_FIELD => {
+                    if let Some(id) = data.get(field.name.as_str()).and_then(|v| v.as_str()) {
+                        update_fields.insert(field.name.to_string(), Value::String(id.to_string()));
+                    }
+                }
+                _ => {
+                    if let Some(v) = data.get(field.name.as_str()) {
+                        match FieldOperation::from_value(v) {
+                            Some(Ok(operation)) => operations.push((field, operation)),
+                            _ => {
+                                update_fields
+                                    .insert(field.name.to_string(), coerce_value(field, v));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        (update_fields, operations)
+    }
+
+    // operations read the current value, callers hold the state lock
+    fn apply_update(
+        row: &mut Row,
+        mut update_fields: Row,
+        operations: &[(&CollectionField, FieldOperation)],
+    ) {
+        for (field, operation) in operations {
+            let value = operation.apply(row.get(&field.name).unwrap_or(&Value::Null));
+            update_fields.insert(field.name.to_string(), coerce_value(field, &value));
+        }
+
+        update_fields.insert(UPDATED_AT_FIELD.to_string(), timestamp_value(Self::now()));
+        row.extend(update_fields);
+    }
+
+    fn now() -> DateTime<Utc> {
+        // postgres keeps timestamps with microsecond precision
+        Utc::now().trunc_subsecs(6)
+    }
+
+    fn serialize_row(collection: &Collection, row: &Row) -> Value {
+        let map = collection.fields.iter().map(|field| {
+            let value = match &field.field_type {
+                FieldType::Relation { target } => Pointer::relation_value(target),
+                _ => row.get(field.name.as_str()).cloned().unwrap_or(Value::Null),
+            };
+
+            (field.name.to_string(), value)
+        });
+
+        Value::Object(Map::from_iter(map))
+    }
+
+    fn compare_rows(
+        collection: &Collection,
+        options: &QueryOptions,
+        left: &Row,
+        right: &Row,
+    ) -> Ordering {
+        for order in &options.order {
+            let Some((field, left)) = resolve_field(collection, left, &order.field, None) else {
+                continue;
+            };
+
+            let right = resolve_field(collection, right, &order.field, None)
+                .map(|(_, right)| right)
+                .unwrap_or(&Value::Null);
+
+            // nulls go last in ascending order, as postgres does
+            let ordering = match (left.is_null(), right.is_null()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => compare_values(&field, left, right).unwrap_or(Ordering::Equal),
+            };
+
+            let ordering = match order.direction {
+                OrderDirection::Asc => ordering,
+                OrderDirection::Desc => ordering.reverse(),
+            };
+
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+
+        Ordering::Equal
+    }
+}
+
+#[async_trait]
+impl Storage for StoreMemory {
+    async fn begin(&self) -> anyhow::Result<Self, StorageError> {
+        let state = self.state.lock().unwrap().clone();
+
+        Ok(Self {
+            state: Arc::new(Mutex::new(state.clone())),
+            parent: Some(self.state.clone()),
+            base: Arc::new(state),
+        })
+    }
+
+    async fn commit(&self) -> anyhow::Result<(), StorageError> {
+        if let Some(parent) = &self.parent {
+            let state = self.state.lock().unwrap();
+            parent.lock().unwrap().merge(&self.base, &state);
+        }
+
+        Ok(())
+    }
+
+    async fn rollback(&self) -> anyhow::Result<(), StorageError> {
+        Ok(())
+    }
+
+    async fn get_collections(&self) -> anyhow::Result<Vec<Collection>, StorageError> {
+        let state = self.state.lock().unwrap();
+
+        Ok(state.schemas.values().cloned().collect())
+    }
+
+    async fn get_collection(
+        &self,
+        collection_name: String,
+    ) -> anyhow::Result<Collection, StorageError> {
+        let state = self.state.lock().unwrap();
+
+        state
+            .schemas
+            .get(&collection_name)
+            .cloned()
+            .ok_or(StorageError::CollectionNotFound {
+                collection: collection_name,
+            })
+    }
+
+    async fn create_collection(
+        &self,
+        collection_name: String,
+        collection_fields: Vec<CollectionField>,
+    ) -> anyhow::Result<Collection, StorageError> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.schemas.contains_key(&collection_name) {
+            return Err(StorageError::CollectionCreate {
+                collection: collection_name,
+            });
+        }
+
+        let mut fields = collection_fields.clone();
+
+        for (name, field_type) in [
+            (ID_FIELD, FieldType::String),
+            (CREATED_AT_FIELD, FieldType::TimeStamp),
+            (UPDATED_AT_FIELD, FieldType::TimeStamp),
+        ] {
+            if !fields.iter().any(|field| field.name == name) {
+                fields.push(CollectionField {
+                    name: name.to_string(),
+                    default: None,
+                    required: None,
+                    unique: None,
+                    on_delete: None,
+                    field_type,
+                });
+            }
+        }
+
+        debug!("create collection: {collection_name}");
+
+        let now = Self::now();
+
+        let collection = Collection {
+            name: collection_name.to_string(),
+            fields,
+            created_at: now,
+            updated_at: now,
+            policy: None,
+        };
+
+        state.tables.entry(collection_name.to_string()).or_default();
+        state
+            .schemas
+            .insert(collection_name.to_string(), collection.clone());
+
+        Ok(collection)
+    }
+
+    async fn remove_collection(&self, collection: &Collection) -> Result<(), StorageError> {
+        let mut state = self.state.lock().unwrap();
+
+        state.tables.remove(&collection.name);
+        state.schemas.remove(&collection.name);
+
+        Ok(())
+    }
+
+    async fn set_collection_policy(
+        &self,
+        collection: &Collection,
+        policy: Option<SchemaPolicy>,
+    ) -> anyhow::Result<Collection, StorageError> {
+        let mut state = self.state.lock().unwrap();
+
+        let schema =
+            state
+                .schemas
+                .get_mut(&collection.name)
+                .ok_or(StorageError::CollectionNotFound {
+                    collection: collection.name.to_string(),
+                })?;
+
+        schema.policy = policy;
+        schema.updated_at = Self::now();
+
+        Ok(schema.clone())
+    }
+
+    async fn insert_field_to_collection(
+        &self,
+        collection: &Collection,
+        field: CollectionField,
+    ) -> anyhow::Result<Collection, StorageError> {
+        if collection.fields.iter().any(|f| f.name == field.name) {
+            return Err(StorageError::CollectionFieldExists {
+                collection: collection.name.to_string(),
+                field: field.name,
+            });
+        }
+
+        let mut state = self.state.lock().unwrap();
+
+        let schema =
+            state
+                .schemas
+                .get_mut(&collection.name)
+                .ok_or(StorageError::CollectionAlterTable {
+                    collection: collection.name.to_string(),
+                    field: field.name.to_string(),
+                })?;
+
+        let mut fields = collection.fields.clone();
+        fields.push(field);
+
+        schema.fields = fields;
+        schema.updated_at = Self::now();
+
+        Ok(schema.clone())
+    }
+
+    async fn remove_field_from_collection(
+        &self,
+        collection: &Collection,
+        field: CollectionField,
+    ) -> anyhow::Result<Collection, StorageError> {
+        let position = collection.fields.iter().position(|f| f.name == field.name);
+
+        if position.is_none() {
+            return Err(StorageError::CollectionFieldExists {
+                collection: collection.name.to_string(),
+                field: field.name,
+            });
+        }
+
+        let mut state = self.state.lock().unwrap();
+
+        let rows =
+            state
+                .tables
+                .get_mut(&collection.name)
+                .ok_or(StorageError::CollectionFieldRemove {
+                    collection: collection.name.to_string(),
+                    field: field.name.to_string(),
+                })?;
+
+        for row in rows.iter_mut() {
+            row.remove(field.name.as_str());
+        }
+
+        let mut fields = collection.fields.clone();
+        fields.remove(position.unwrap());
+
+        let schema =
+            state
+                .schemas
+                .get_mut(&collection.name)
+                .ok_or(StorageError::CollectionCreate {
+                    collection: collection.name.to_string(),
+                })?;
+
+        schema.fields = fields;
+        schema.updated_at = Self::now();
+
+        Ok(schema.clone())
+    }
+
+    async fn insert_data_into_collection(
+        &self,
+        collection: &Collection,
+        data: Value,
+    ) -> anyhow::Result<Value, StorageError> {
+        let (collection_id, row) = Self::new_row(collection, &data);
+
+        Self::write_rows(&mut self.state.lock().unwrap(), collection, |rows| {
+            rows.push(row);
+            Ok(())
+        })?;
+
+        info!(
+            "insert_data_into_collection: {collection_name} with id: {collection_id}",
+            collection_name = collection.name
+        );
+
+        self.get_data_from_collection(collection, collection_id, &Projection::default())
+            .await
+    }
+
+    async fn upsert_data_into_collection(
+        &self,
+        collection: &Collection,
+        data: Value,
+        key: &str,
+        update: &Value,
+    ) -> anyhow::Result<Value, StorageError> {
+        let key_value = collection
+            .get_field(&key.to_string())
+            .map(|field| coerce_value(field, data.get(key).unwrap_or(&Value::Null)))
+            .unwrap_or(Value::Null);
+
+        let (collection_id, new_row) = Self::new_row(collection, &data);
+
+        let (mut update_fields, operations) = Self::update_values(collection, update);
+        update_fields.remove(ID_FIELD);
+
+        let collection_id = Self::write_rows(
+            &mut self.state.lock().unwrap(),
+            collection,
+            |rows| match rows.iter_mut().find(|r| r.get(key) == Some(&key_value)) {
+                Some(row) => {
+                    Self::apply_update(row, update_fields, &operations);
+                    Ok(row_id(row).to_string())
+                }
+                None => {
+                    rows.push(new_row);
+                    Ok(collection_id)
+                }
+            },
+        )?;
+
+        debug!(
+            "upsert_data_into_collection: {collection_name} with id: {collection_id}",
+            collection_name = collection.name
+        );
+
+        self.get_data_from_collection(collection, collection_id, &Projection::default())
+            .await
+    }
+
+    async fn update_data_into_collection(
+        &self,
+        collection: &Collection,
+        collection_id: String,
+        data: Value,
+        precondition: &Precondition,
+    ) -> anyhow::Result<Value, StorageError> {
+        let (update_fields, operations) = Self::update_values(collection, &data);
+
+        let mut collection_id = collection_id;
+
+        if !update_fields.is_empty() || !operations.is_empty() || !precondition.is_empty() {
+            let mut state = self.state.lock().unwrap();
+
+            collection_id = Self::write_rows(&mut state, collection, |rows| {
+                let row = rows.iter_mut().find(|r| row_id(r) == collection_id);
+
+                let row = match row {
+                    Some(row) if !Self::matches_precondition(collection, row, precondition) => None,
+                    row => row,
+                };
+
+                match row {
+                    Some(row) => {
+                        Self::apply_update(row, update_fields, &operations);
+                        Ok(row_id(row).to_string())
+                    }
+                    None if !precondition.is_empty() => Err(StorageError::Conflict {
+                        collection: collection.name.to_string(),
+                        id: collection_id.to_string(),
+                    }),
+                    None => Ok(collection_id.to_string()),
+                }
+            })?;
+
+            debug!(
+                "update_into_collection: {collection_name} with id: {collection_id}",
+                collection_name = collection.name
+            );
+        }
+
+        self.get_data_from_collection(collection, collection_id, &Projection::default())
+            .await
+    }
+
+    async fn delete_data_from_collection(
+        &self,
+        collection: &Collection,
+        collection_id: String,
+        precondition: &Precondition,
+    ) -> anyhow::Result<Value, StorageError> {
+        let mut state = self.state.lock().unwrap();
+        let mut tables = Self::delete_scope(&state, &collection.name);
+
+        let deleted = tables
+            .get_mut(&collection.name)
+            .map(|rows| {
+                Self::remove_rows(rows, |r| {
+                    r.get(ID_FIELD).and_then(|v| v.as_str()) == Some(&collection_id)
+                        && Self::matches_precondition(collection, r, precondition)
+                })
+            })
+            .unwrap_or_default();
+
+        if deleted.is_empty() && !precondition.is_empty() {
+            return Err(StorageError::Conflict {
+                collection: collection.name.to_string(),
+                id: collection_id,
+            });
+        }
+
+        Self::delete_references(&state.schemas, &mut tables, &collection.name, &deleted)?;
+        state.tables.extend(tables);
+
+        Ok(Value::Null)
+    }
+
+    async fn bulk_update_data_into_collection(
+        &self,
+        collection: &Collection,
+        query: WhereQuery,
+        data: Value,
+    ) -> anyhow::Result<u64, StorageError> {
+        let (mut update_fields, operations) = Self::update_values(collection, &data);
+        update_fields.remove(ID_FIELD);
+
+        let mut state = self.state.lock().unwrap();
+        let query = Self::resolve_related_to(&state, query);
+
+        let count = Self::write_rows(&mut state, collection, |rows| {
+            let mut count = 0;
+
+            for row in rows
+                .iter_mut()
+                .filter(|row| match_where(collection, row, &query))
+            {
+                Self::apply_update(row, update_fields.clone(), &operations);
+                count += 1;
+            }
+
+            Ok(count)
+        })?;
+
+        debug!(
+            "bulk_update_data_into_collection: {collection_name} updated {count}",
+            collection_name = collection.name
+        );
+
+        Ok(count)
+    }
+
+    async fn bulk_delete_data_from_collection(
+        &self,
+        collection: &Collection,
+        query: WhereQuery,
+    ) -> anyhow::Result<u64, StorageError> {
+        let mut state = self.state.lock().unwrap();
+        let query = Self::resolve_related_to(&state, query);
+        let mut tables = Self::delete_scope(&state, &collection.name);
+
+        let deleted = tables
+            .get_mut(&collection.name)
+            .map(|rows| Self::remove_rows(rows, |row| match_where(collection, row, &query)))
+            .unwrap_or_default();
+
+        Self::delete_references(&state.schemas, &mut tables, &collection.name, &deleted)?;
+        state.tables.extend(tables);
+
+        Ok(deleted.len() as u64)
+    }
+
+    async fn get_data_from_collection(
+        &self,
+        collection: &Collection,
+        collection_id: String,
+        projection: &Projection,
+    ) -> anyhow::Result<Value, StorageError> {
+        let state = self.state.lock().unwrap();
+        let projection = projection.apply(collection);
+
+        state
+            .tables
+            .get(&collection.name)
+            .and_then(|rows| {
+                rows.iter()
+                    .find(|r| r.get(ID_FIELD).and_then(|v| v.as_str()) == Some(&collection_id))
+            })
+            .map(|row| Self::serialize_row(&projection, row))
+            .ok_or(StorageError::ValueNotFound {
+                collection: collection.name.to_string(),
+                id: collection_id,
+            })
+    }
+
+    async fn list_data_from_collection(
+        &self,
+        collection: &Collection,
+        query: WhereQuery,
+        options: &QueryOptions,
+    ) -> anyhow::Result<Vec<Value>, StorageError> {
+        let state = self.state.lock().unwrap();
+        let query = Self::resolve_related_to(&state, query);
+
+        let mut rows = state
+            .tables
+            .get(&collection.name)
+            .map(|rows| {
+                rows.iter()
+                    .filter(|row| match_where(collection, row, &query))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        rows.sort_by(|left, right| Self::compare_rows(collection, options, left, right));
+
+        let projection = options.projection.apply(collection);
+
+        let result = rows
+            .into_iter()
+            .skip(options.skip.unwrap_or_default())
+            .take(options.limit.unwrap_or(usize::MAX))
+            .map(|row| Self::serialize_row(&projection, row))
+            .collect();
+
+        Ok(result)
+    }
+
+    async fn count_data_from_collection(
+        &self,
+        collection: &Collection,
+        query: WhereQuery,
+    ) -> anyhow::Result<i64, StorageError> {
+        let state = self.state.lock().unwrap();
+        let query = Self::resolve_related_to(&state, query);
+
+        let count = state
+            .tables
+            .get(&collection.name)
+            .map(|rows| {
+                rows.iter()
+                    .filter(|row| match_where(collection, row, &query))
+                    .count()
+            })
+            .unwrap_or_default();
+
+        Ok(count as i64)
+    }
+}
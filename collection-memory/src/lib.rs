@@ -0,0 +1,5 @@
+pub use crate::collection_memory::StoreMemory;
+
+mod coerce_value;
+mod collection_memory;
+mod match_where;
@@ -0,0 +1,233 @@
+use std::cmp::Ordering;
+
+use regex::RegexBuilder;
+use serde_json::{Map, Value};
+
+use vivalaakam_seattle_collection::{
+    Collection, CollectionField, Decimal, FieldType, TimeStamp, Where, WhereQuery,
+};
+
+use crate::coerce_value::coerce_value;
+
+pub fn compare_values(field: &CollectionField, left: &Value, right: &Value) -> Option<Ordering> {
+    if left.is_null() || right.is_null() {
+        return None;
+    }
+
+    match field.field_type {
+        FieldType::String => left.as_str()?.partial_cmp(right.as_str()?),
+        FieldType::Number => left.as_f64()?.partial_cmp(&right.as_f64()?),
+        FieldType::Integer | FieldType::BigInt => match (left.as_i64(), right.as_i64()) {
+            (Some(left), Some(right)) => left.partial_cmp(&right),
+            _ => left.as_f64()?.partial_cmp(&right.as_f64()?),
+        },
+        FieldType::Decimal { .. } => {
+            Decimal::from_value(left)?.partial_cmp(&Decimal::from_value(right)?)
+        }
+        FieldType::Boolean => left.as_bool()?.partial_cmp(&right.as_bool()?),
+        FieldType::TimeStamp => TimeStamp::from_value(left)?
+            .0
+            .partial_cmp(&TimeStamp::from_value(right)?.0),
+        FieldType::Array
+        | FieldType::Object
+        | FieldType::Pointer { .. }
+        | FieldType::Relation { .. } => None,
+    }
+}
+
+fn equal_values(field: &CollectionField, left: &Value, right: &Value) -> Option<bool> {
+    if left.is_null() || right.is_null() {
+        return None;
+    }
+
+    match field.field_type {
+        FieldType::Array | FieldType::Object | FieldType::Pointer { .. } => Some(left == right),
+        FieldType::Relation { .. } => Some(left.as_array()?.contains(right)),
+        _ => compare_values(field, left, right).map(|ordering| ordering == Ordering::Equal),
+    }
+}
+
+// conditions follow SQL three-valued logic, None stands for NULL
+fn all_of(values: impl IntoIterator<Item = Option<bool>>) -> Option<bool> {
+    values
+        .into_iter()
+        .try_fold(Some(true), |result, value| match value {
+            Some(false) => Err(()),
+            Some(true) => Ok(result),
+            None => Ok(None),
+        })
+        .unwrap_or(Some(false))
+}
+
+fn any_of(values: impl IntoIterator<Item = Option<bool>>) -> Option<bool> {
+    all_of(values.into_iter().map(|value| value.map(|v| !v))).map(|v| !v)
+}
+
+fn match_field(field: &CollectionField, value: &Value, query: &Where) -> Option<bool> {
+    let equal = |other: &Value| equal_values(field, value, &coerce_value(field, other));
+    let compare = |other: &Value| compare_values(field, value, &coerce_value(field, other));
+
+    let mut conditions = vec![];
+
+    if let Some(eq) = &query.eq {
+        conditions.push(equal(eq));
+    }
+
+    if let Some(ne) = &query.ne {
+        conditions.push(equal(ne).map(|v| !v));
+    }
+
+    if let Some(gt) = &query.gt {
+        conditions.push(compare(gt).map(|o| o == Ordering::Greater));
+    }
+
+    if let Some(gte) = &query.gte {
+        conditions.push(compare(gte).map(|o| o != Ordering::Less));
+    }
+
+    if let Some(lt) = &query.lt {
+        conditions.push(compare(lt).map(|o| o == Ordering::Less));
+    }
+
+    if let Some(lte) = &query.lte {
+        conditions.push(compare(lte).map(|o| o != Ordering::Greater));
+    }
+
+    if let Some(in_) = &query.in_ {
+        conditions.push(any_of(in_.iter().map(equal)));
+    }
+
+    if let Some(nin) = &query.nin {
+        conditions.push(any_of(nin.iter().map(equal)).map(|v| !v));
+    }
+
+    if let Some(exists) = query.exists {
+        conditions.push(Some(value.is_null() != exists));
+    }
+
+    let case_insensitive = query.case_insensitive();
+    let text = value.as_str().map(|v| match case_insensitive {
+        true => v.to_lowercase(),
+        false => v.to_string(),
+    });
+    let pattern = |other: &String| match case_insensitive {
+        true => other.to_lowercase(),
+        false => other.to_string(),
+    };
+
+    if let Some(regex) = &query.regex {
+        let regex = RegexBuilder::new(regex)
+            .case_insensitive(case_insensitive)
+            .build()
+            .ok();
+
+        conditions.push(
+            value
+                .as_str()
+                .zip(regex)
+                .map(|(value, regex)| regex.is_match(value)),
+        );
+    }
+
+    if let Some(starts_with) = &query.starts_with {
+        conditions.push(text.as_ref().map(|v| v.starts_with(&pattern(starts_with))));
+    }
+
+    if let Some(ends_with) = &query.ends_with {
+        conditions.push(text.as_ref().map(|v| v.ends_with(&pattern(ends_with))));
+    }
+
+    if let Some(contains) = &query.text {
+        conditions.push(text.as_ref().map(|v| v.contains(&pattern(contains))));
+    }
+
+    let array = value.as_array();
+
+    if let Some(all) = &query.all {
+        conditions.push(array.map(|array| all.iter().all(|v| array.contains(v))));
+    }
+
+    if let Some(contained_by) = &query.contained_by {
+        conditions.push(array.map(|array| array.iter().all(|v| contained_by.contains(v))));
+    }
+
+    if let Some(contains) = &query.contains {
+        conditions.push(array.map(|array| array.contains(contains)));
+    }
+
+    if let Some(size) = query.size {
+        conditions.push(array.map(|array| array.len() == size));
+    }
+
+    all_of(conditions)
+}
+
+// resolves plain and dotted keys into the field used for comparison and its row value
+pub fn resolve_field<'a>(
+    collection: &Collection,
+    row: &'a Map<String, Value>,
+    key: &str,
+    value_type: Option<FieldType>,
+) -> Option<(CollectionField, &'a Value)> {
+    if let Some(field) = collection.get_field(&key.to_string()) {
+        return Some((field.clone(), row.get(key).unwrap_or(&Value::Null)));
+    }
+
+    let (field, path) = collection.get_nested_field(key)?;
+
+    let value = path
+        .iter()
+        .try_fold(
+            row.get(field.name.as_str()).unwrap_or(&Value::Null),
+            |value, part| value.get(part),
+        )
+        .unwrap_or(&Value::Null);
+
+    let value = match &value_type {
+        Some(field_type) if !field_type.is_maybe_exists(value) => &Value::Null,
+        _ => value,
+    };
+
+    let field = CollectionField {
+        name: key.to_string(),
+        field_type: value_type.unwrap_or_else(|| value.clone().into()),
+        default: None,
+        required: None,
+        unique: None,
+        on_delete: None,
+    };
+
+    Some((field, value))
+}
+
+fn match_query(
+    collection: &Collection,
+    row: &Map<String, Value>,
+    query: &WhereQuery,
+) -> Option<bool> {
+    let fields = query.fields.iter().filter_map(|(key, value)| {
+        resolve_field(collection, row, key, value.value_type())
+            .map(|(field, row_value)| match_field(&field, row_value, value))
+    });
+
+    let and = query.and.iter().map(|q| match_query(collection, row, q));
+
+    let mut conditions = fields.chain(and).collect::<Vec<_>>();
+
+    if !query.or.is_empty() {
+        conditions.push(any_of(
+            query.or.iter().map(|q| match_query(collection, row, q)),
+        ));
+    }
+
+    if !query.nor.is_empty() {
+        conditions
+            .push(any_of(query.nor.iter().map(|q| match_query(collection, row, q))).map(|v| !v));
+    }
+
+    all_of(conditions)
+}
+
+pub fn match_where(collection: &Collection, row: &Map<String, Value>, query: &WhereQuery) -> bool {
+    match_query(collection, row, query) == Some(true)
+}
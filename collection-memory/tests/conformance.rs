@@ -0,0 +1,8 @@
+use vivalaakam_seattle_collection_memory::StoreMemory;
+use vivalaakam_seattle_collection_tests::storage_tests;
+
+async fn storage(_tables: &[&str]) -> StoreMemory {
+    StoreMemory::new()
+}
+
+storage_tests!(storage);
@@ -0,0 +1,63 @@
+use serde_json::json;
+
+use vivalaakam_seattle_collection::Collections;
+use vivalaakam_seattle_collection_memory::StoreMemory;
+
+// sqlite in memory has a single connection, so this can't be part of the shared suite
+#[tokio::test]
+async fn memory_transaction() {
+    let table_name = "MemoryTransaction";
+
+    let collections = Collections::new(StoreMemory::new()).await;
+
+    let first = collections
+        .insert(table_name.to_string(), json!({ "name": "a" }))
+        .await
+        .unwrap();
+
+    collections
+        .insert(table_name.to_string(), json!({ "name": "b" }))
+        .await
+        .unwrap();
+
+    let transaction = collections.begin().await.unwrap();
+
+    // writes made outside the open transaction survive its commit
+    collections
+        .insert(table_name.to_string(), json!({ "name": "c" }))
+        .await
+        .unwrap();
+
+    collections
+        .bulk_update(
+            table_name.to_string(),
+            json!({ "name": "b" }),
+            json!({ "name": "d" }),
+        )
+        .await
+        .unwrap();
+
+    transaction
+        .insert(table_name.to_string(), json!({ "name": "e" }))
+        .await
+        .unwrap();
+
+    transaction
+        .delete(
+            table_name.to_string(),
+            first["id"].as_str().unwrap().to_string(),
+        )
+        .await
+        .unwrap();
+
+    collections.commit(transaction).await.unwrap();
+
+    for (name, count) in [("a", 0), ("b", 0), ("c", 1), ("d", 1), ("e", 1)] {
+        let result = collections
+            .count(table_name.to_string(), json!({ "name": name }))
+            .await
+            .unwrap();
+
+        assert_eq!(result, count, "{name}");
+    }
+}
@@ -0,0 +1 @@
+ALTER TABLE storage_collection_schema ADD COLUMN IF NOT EXISTS policy VARCHAR;
@@ -1,22 +1,41 @@
 use serde_json::Value;
 use sqlx::postgres::PgArguments;
+use sqlx::types::Json;
 use sqlx::Arguments;
 
-use vivalaakam_seattle_collection::{CollectionField, FieldType};
+use vivalaakam_seattle_collection::{CollectionField, Decimal, FieldType, Pointer, TimeStamp};
 
 pub fn add_value_into_args(field: &CollectionField, value: &Value, args: &mut PgArguments) {
     match field.field_type {
-        FieldType::String | FieldType::Array | FieldType::Object => {
+        FieldType::String => {
             args.add(value.as_str());
         }
+        FieldType::Array | FieldType::Object => {
+            args.add((!value.is_null()).then(|| Json(value.clone())));
+        }
         FieldType::Number => {
             args.add(value.as_f64());
         }
+        // int4 columns take int8 parameters
+        FieldType::Integer | FieldType::BigInt => {
+            args.add(FieldType::integer_value(value));
+        }
+        FieldType::Decimal { scale, .. } => {
+            args.add(Decimal::from_value(value).map(|v| v.round(scale).0));
+        }
         FieldType::Boolean => {
             args.add(value.as_bool());
         }
         FieldType::TimeStamp => {
-            args.add(value.as_i64());
+            args.add(TimeStamp::from_value(value).map(|v| v.0));
+        }
+        // queries may compare pointers with a plain object id
+        FieldType::Pointer { .. } | FieldType::Relation { .. } => {
+            args.add(
+                Pointer::from_value(value)
+                    .map(|pointer| pointer.object_id)
+                    .or_else(|| value.as_str().map(|v| v.to_string())),
+            );
         }
     };
 }
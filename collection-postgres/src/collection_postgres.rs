@@ -1,22 +1,30 @@
-use std::collections::HashMap;
-use std::iter;
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use serde_json::{json, Value};
-use sqlx::{postgres::PgArguments, Arguments, PgPool, Pool, Postgres, Row};
+use sqlx::{
+    postgres::PgArguments, Arguments, Connection, PgConnection, PgPool, Pool, Postgres, Row,
+    Transaction,
+};
+use tokio::sync::Mutex;
 use tracing::{debug, error, info};
 
 use vivalaakam_seattle_collection::{
-    make_id, Collection, CollectionField, FieldType, Storage, StorageError, Where,
+    make_id, Collection, CollectionField, FieldOperation, FieldType, OnDelete, OrderDirection,
+    Precondition, Projection, QueryOptions, SchemaPolicy, Storage, StorageError, WhereQuery,
 };
 
 use crate::add_value_into_args::add_value_into_args;
 use crate::serialize_pg_row::serialize_pg_row;
+use crate::store_connection::{SharedTransaction, StoreConnection};
 use crate::store_schema_query::StoreCollectionQuery;
+use crate::update_expression::update_expression;
+use crate::where_query::{join_table, order_expression, precondition_query, where_query};
 
 #[derive(Clone)]
 pub struct StorePostgresql {
     pub pool: Pool<Postgres>,
+    transaction: Option<SharedTransaction>,
 }
 
 const ID_FIELD: &str = "id";
@@ -24,6 +32,16 @@ const CREATED_AT_FIELD: &str = "created_at";
 const UPDATED_AT_FIELD: &str = "updated_at";
 
 impl StorePostgresql {
+    fn select_fields(collection: &Collection) -> String {
+        collection
+            .fields
+            .iter()
+            .filter(|field| !field.field_type.is_relation())
+            .map(|field| format!(r#""{}""#, field.name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
     pub async fn new(database_url: &str) -> Self {
         let pool = PgPool::connect(database_url)
             .await
@@ -34,41 +52,545 @@ impl StorePostgresql {
             .await
             .expect("postgres migration failed");
 
-        Self { pool }
+        Self {
+            pool,
+            transaction: None,
+        }
     }
 
     fn query_field_to_collection(schema: String, field: &CollectionField) -> String {
-        match field.field_type {
-            FieldType::String => format!(r#"ALTER TABLE "{schema}" ADD "{}" text;"#, field.name),
+        let unique = match field.unique.unwrap_or_default() {
+            true => " UNIQUE",
+            false => "",
+        };
+
+        match &field.field_type {
+            FieldType::String => format!(
+                r#"ALTER TABLE "{schema}" ADD "{}" text{unique};"#,
+                field.name
+            ),
             FieldType::Number => format!(
-                r#"ALTER TABLE "{schema}" ADD "{}" double precision;"#,
+                r#"ALTER TABLE "{schema}" ADD "{}" double precision{unique};"#,
+                field.name
+            ),
+            FieldType::Integer => {
+                format!(
+                    r#"ALTER TABLE "{schema}" ADD "{}" integer{unique};"#,
+                    field.name
+                )
+            }
+            FieldType::BigInt => format!(
+                r#"ALTER TABLE "{schema}" ADD "{}" bigint{unique};"#,
+                field.name
+            ),
+            FieldType::Decimal { precision, scale } => format!(
+                r#"ALTER TABLE "{schema}" ADD "{}" numeric({precision}, {scale}){unique};"#,
                 field.name
             ),
             FieldType::Boolean => {
-                format!(r#"ALTER TABLE "{schema}" ADD "{}" boolean;"#, field.name)
+                format!(
+                    r#"ALTER TABLE "{schema}" ADD "{}" boolean{unique};"#,
+                    field.name
+                )
             }
             FieldType::Array => format!(r#"ALTER TABLE "{schema}" ADD "{}" jsonb;"#, field.name),
             FieldType::Object => format!(r#"ALTER TABLE "{schema}" ADD "{}" jsonb;"#, field.name),
             FieldType::TimeStamp => {
                 format!(
-                    r#"ALTER TABLE "{schema}" ADD "{}" timestamptz;"#,
+                    r#"ALTER TABLE "{schema}" ADD "{}" timestamptz{unique};"#,
                     field.name
                 )
             }
+            FieldType::Pointer { target } => format!(
+                r#"ALTER TABLE "{schema}" ADD "{}" varchar{unique}{};"#,
+                field.name,
+                Self::foreign_key(target, field.on_delete)
+            ),
+            FieldType::Relation { .. } => format!(
+                r#"CREATE TABLE IF NOT EXISTS "{join_table}" ("owningId" varchar NOT NULL, "relatedId" varchar NOT NULL, PRIMARY KEY ("owningId", "relatedId"));"#,
+                join_table = join_table(&schema, &field.name)
+            ),
         }
     }
 
+    // pointers without a rule may dangle, as they did before rules existed
+    fn foreign_key(target: &str, on_delete: Option<OnDelete>) -> String {
+        let action = match on_delete {
+            Some(OnDelete::Restrict) => "RESTRICT",
+            Some(OnDelete::Cascade) => "CASCADE",
+            Some(OnDelete::SetNull) => "SET NULL",
+            None => return String::new(),
+        };
+
+        format!(r#" REFERENCES "{target}" (id) ON DELETE {action}"#)
+    }
+
     pub fn get_pool(&self) -> &Pool<Postgres> {
         &self.pool
     }
+
+    // statements of a transactional store share its transaction
+    async fn connection(
+        &self,
+        collection_name: &str,
+    ) -> anyhow::Result<StoreConnection<'_>, StorageError> {
+        let connection = match &self.transaction {
+            Some(transaction) => {
+                let transaction = transaction.lock().await;
+
+                transaction
+                    .is_some()
+                    .then_some(StoreConnection::Transaction(transaction))
+                    .ok_or_else(|| "transaction is finished".to_string())
+            }
+            None => self
+                .pool
+                .acquire()
+                .await
+                .map(|connection| StoreConnection::Pool(Box::new(connection)))
+                .map_err(|e| e.to_string()),
+        };
+
+        connection.map_err(|err| {
+            error!("connection: {err}");
+            StorageError::DBErr {
+                collection: collection_name.to_string(),
+                err,
+            }
+        })
+    }
+
+    async fn finish_transaction(&self) -> Option<Transaction<'static, Postgres>> {
+        match &self.transaction {
+            Some(transaction) => transaction.lock().await.take(),
+            None => None,
+        }
+    }
+
+    // applies AddRelation and RemoveRelation of `data` to every owning row
+    async fn update_relations(
+        &self,
+        collection: &Collection,
+        ids: &[String],
+        data: &Value,
+    ) -> anyhow::Result<(), StorageError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        for field in &collection.fields {
+            let Some(Ok(operation)) = data
+                .get(field.name.as_str())
+                .and_then(FieldOperation::from_value)
+            else {
+                continue;
+            };
+
+            let join_table = join_table(&collection.name, &field.name);
+
+            let query = match operation {
+                FieldOperation::AddRelation { .. } => format!(
+                    r#"INSERT INTO "{join_table}" ("owningId", "relatedId") SELECT owning_id, related_id FROM UNNEST($1::varchar[]) AS owning_id CROSS JOIN UNNEST($2::varchar[]) AS related_id ON CONFLICT DO NOTHING"#
+                ),
+                FieldOperation::RemoveRelation { .. } => format!(
+                    r#"DELETE FROM "{join_table}" WHERE "owningId" = ANY($1) AND "relatedId" = ANY($2)"#
+                ),
+                _ => continue,
+            };
+
+            sqlx::query(query.as_str())
+                .bind(ids)
+                .bind(operation.relation_ids())
+                .execute(&mut *self.connection(&collection.name).await?)
+                .await
+                .map_err(|e| {
+                    error!("update_relations: {e}");
+                    StorageError::DBErr {
+                        collection: collection.name.to_string(),
+                        err: e.to_string(),
+                    }
+                })?;
+        }
+
+        Ok(())
+    }
+
+    // schemas with a pointer or a relation to `target`
+    async fn referencing_collections(
+        connection: &mut PgConnection,
+        target: &str,
+    ) -> anyhow::Result<Vec<Collection>, StorageError> {
+        let schemas: Vec<StoreCollectionQuery> = sqlx::query_as(
+            r#"SELECT * FROM storage_collection_schema WHERE EXISTS (SELECT 1 FROM jsonb_array_elements(fields) AS field WHERE field->'field_type'->'Pointer'->>'target' = $1 OR field->'field_type'->'Relation'->>'target' = $1)"#,
+        )
+        .bind(target)
+        .fetch_all(connection)
+        .await
+        .map_err(|e| {
+            error!("referencing_collections: {e}");
+            StorageError::DBErr {
+                collection: target.to_string(),
+                err: e.to_string(),
+            }
+        })?;
+
+        Ok(schemas.into_iter().map(|schema| schema.into()).collect())
+    }
+
+    // deletes the matching rows in one transaction with the relations they hold or that point
+    // at them, following cascading pointers. `None` when a restricting pointer still
+    // references one of them
+    async fn delete_rows(
+        &self,
+        collection: &Collection,
+        condition: &str,
+        arguments: PgArguments,
+    ) -> anyhow::Result<Option<u64>, StorageError> {
+        let db_error = |e: sqlx::Error| {
+            error!("delete_rows: {e}");
+            StorageError::DBErr {
+                collection: collection.name.to_string(),
+                err: e.to_string(),
+            }
+        };
+
+        let mut connection = self.connection(&collection.name).await?;
+        let mut transaction = connection
+            .begin()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
+
+        let ids = sqlx::query_with(
+            format!(
+                r#"SELECT id FROM "{collection_name}" WHERE {condition}"#,
+                collection_name = collection.name
+            )
+            .as_str(),
+            arguments,
+        )
+        .fetch_all(&mut *transaction)
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .map(|row| row.get::<String, _>("id"))
+        .collect::<Vec<_>>();
+
+        if ids.is_empty() {
+            return Ok(Some(0));
+        }
+
+        // cascaded rows have to be found while they still exist
+        let mut deleted = vec![(collection.clone(), ids.clone())];
+        let mut index = 0;
+
+        while let Some((target, target_ids)) = deleted.get(index).cloned() {
+            index += 1;
+
+            for field in target
+                .fields
+                .iter()
+                .filter(|field| field.field_type.is_relation())
+            {
+                sqlx::query(
+                    format!(
+                        r#"DELETE FROM "{join_table}" WHERE "owningId" = ANY($1)"#,
+                        join_table = join_table(&target.name, &field.name)
+                    )
+                    .as_str(),
+                )
+                .bind(&target_ids)
+                .execute(&mut *transaction)
+                .await
+                .map_err(db_error)?;
+            }
+
+            for schema in Self::referencing_collections(&mut transaction, &target.name).await? {
+                for field in &schema.fields {
+                    match &field.field_type {
+                        FieldType::Relation { target: related } if *related == target.name => {
+                            sqlx::query(
+                                format!(
+                                    r#"DELETE FROM "{join_table}" WHERE "relatedId" = ANY($1)"#,
+                                    join_table = join_table(&schema.name, &field.name)
+                                )
+                                .as_str(),
+                            )
+                            .bind(&target_ids)
+                            .execute(&mut *transaction)
+                            .await
+                            .map_err(db_error)?;
+                        }
+                        FieldType::Pointer { target: pointed }
+                            if *pointed == target.name
+                                && field.on_delete == Some(OnDelete::Cascade) =>
+                        {
+                            let cascaded = sqlx::query(
+                                format!(
+                                    r#"SELECT id FROM "{collection_name}" WHERE "{field_name}" = ANY($1)"#,
+                                    collection_name = schema.name,
+                                    field_name = field.name
+                                )
+                                .as_str(),
+                            )
+                            .bind(&target_ids)
+                            .fetch_all(&mut *transaction)
+                            .await
+                            .map_err(db_error)?
+                            .into_iter()
+                            .map(|row| row.get::<String, _>("id"))
+                            .filter(|id| {
+                                !deleted
+                                    .iter()
+                                    .any(|(c, ids)| c.name == schema.name && ids.contains(id))
+                            })
+                            .collect::<Vec<_>>();
+
+                            if !cascaded.is_empty() {
+                                deleted.push((schema.clone(), cascaded));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        let rec = sqlx::query(
+            format!(
+                r#"DELETE FROM "{collection_name}" WHERE id = ANY($1)"#,
+                collection_name = collection.name
+            )
+            .as_str(),
+        )
+        .bind(&ids)
+        .execute(&mut *transaction)
+        .await;
+
+        let rec = match rec {
+            Ok(rec) => rec,
+            Err(e)
+                if e.as_database_error()
+                    .is_some_and(|e| e.is_foreign_key_violation()) =>
+            {
+                return Ok(None);
+            }
+            Err(e) => return Err(db_error(e)),
+        };
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
+
+        Ok(Some(rec.rows_affected()))
+    }
+
+    // the database doesn't name the referenced row, so prefer one a restricting pointer holds
+    async fn referenced_id(
+        &self,
+        collection: &Collection,
+        query: WhereQuery,
+    ) -> anyhow::Result<String, StorageError> {
+        let references = Self::referencing_collections(
+            &mut *self.connection(&collection.name).await?,
+            &collection.name,
+        )
+        .await?
+        .iter()
+            .flat_map(|schema| {
+                schema
+                    .fields
+                    .iter()
+                    .filter(|field| {
+                        field.on_delete == Some(OnDelete::Restrict)
+                            && matches!(&field.field_type, FieldType::Pointer { target } if *target == collection.name)
+                    })
+                    .map(|field| format!(r#"SELECT "{}" FROM "{}""#, field.name, schema.name))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let order = match references.is_empty() {
+            true => String::new(),
+            false => format!(" ORDER BY id IN ({}) DESC", references.join(" UNION ")),
+        };
+
+        let mut arguments = PgArguments::default();
+        let mut counter: usize = 1;
+        let where_query = where_query(collection, query, &mut arguments, &mut counter);
+
+        let rec = sqlx::query_with(
+            format!(
+                r#"SELECT id FROM "{collection_name}" WHERE {where_query}{order} LIMIT 1"#,
+                collection_name = collection.name
+            )
+            .as_str(),
+            arguments,
+        )
+        .fetch_optional(&mut *self.connection(&collection.name).await?)
+        .await
+        .map_err(|e| {
+            error!("referenced_id: {e}");
+            StorageError::DBErr {
+                collection: collection.name.to_string(),
+                err: e.to_string(),
+            }
+        })?;
+
+        Ok(rec
+            .map(|rec| rec.get::<String, _>("id"))
+            .unwrap_or_default())
+    }
+
+    async fn insert_row(
+        &self,
+        collection: &Collection,
+        data: Value,
+        upsert: Option<(&str, &Value)>,
+    ) -> anyhow::Result<Value, StorageError> {
+        let mut arguments = PgArguments::default();
+
+        let mut insert_fields = vec![];
+        let mut insert_indexes = vec![];
+        let mut counter = 1;
+        for field in &collection.fields {
+            match field.name.as_str() {
+                CREATED_AT_FIELD | UPDATED_AT_FIELD => {
+                    continue;
+                }
+                ID_FIELD => {
+                    let id = match data.get(field.name.as_str()) {
+                        Some(v) => v.as_str().unwrap_or(&make_id(10)).to_string(),
+                        None => make_id(10),
+                    };
+
+                    insert_fields.push(field.name.to_string());
+                    insert_indexes.push(format!("${counter}"));
+                    arguments.add(id);
+                    counter += 1;
+                }
+                _ if field.field_type.is_relation() => {
+                    continue;
+                }
+                _ => {
+                    let v = data.get(field.name.as_str()).cloned();
+
+                    if let Some(v) = v {
+                        insert_fields.push(field.name.to_string());
+                        insert_indexes.push(format!("${counter}"));
+                        add_value_into_args(field, &v, &mut arguments);
+                        counter += 1;
+                    }
+                }
+            }
+        }
+
+        insert_fields.push(CREATED_AT_FIELD.to_string());
+        insert_fields.push(UPDATED_AT_FIELD.to_string());
+        insert_indexes.push("NOW()".to_string());
+        insert_indexes.push("NOW()".to_string());
+
+        let insert_fields = insert_fields.join(", ");
+        let insert_indexes = insert_indexes.join(", ");
+
+        let on_conflict = match upsert {
+            Some((key, update)) => {
+                let mut update_fields = collection
+                    .fields
+                    .iter()
+                    .filter(|field| {
+                        !matches!(
+                            field.name.as_str(),
+                            ID_FIELD | CREATED_AT_FIELD | UPDATED_AT_FIELD
+                        ) && !field.field_type.is_relation()
+                    })
+                    .filter_map(|field| {
+                        update.get(field.name.as_str()).map(|v| {
+                            update_expression(
+                                &collection.name,
+                                field,
+                                v,
+                                &mut arguments,
+                                &mut counter,
+                            )
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                update_fields.push(format!("{UPDATED_AT_FIELD} = NOW()"));
+
+                format!(
+                    r#" ON CONFLICT ("{key}") DO UPDATE SET {update_fields}"#,
+                    update_fields = update_fields.join(", ")
+                )
+            }
+            None => String::new(),
+        };
+
+        let rec = sqlx::query_with(format!(r#"INSERT INTO "{collection_name}" ({insert_fields}) VALUES ({insert_indexes}){on_conflict} RETURNING id"#, collection_name = collection.name).as_str(), arguments)
+            .fetch_one(&mut *self.connection(&collection.name).await?)
+            .await
+            .map_err(|e| {
+                error!("insert_row: {e}");
+                StorageError::DBErr {
+                    collection: collection.name.to_string(),
+                    err: e.to_string(),
+                }
+            })?;
+
+        let collection_id = rec.get::<String, _>("id");
+
+        info!(
+            "insert_row: {collection_name} with id: {collection_id}",
+            collection_name = collection.name
+        );
+
+        self.update_relations(collection, &[collection_id.to_string()], &data)
+            .await?;
+
+        self.get_data_from_collection(collection, collection_id, &Projection::default())
+            .await
+    }
 }
 
 #[async_trait]
 impl Storage for StorePostgresql {
+    async fn begin(&self) -> anyhow::Result<Self, StorageError> {
+        let transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
+
+        Ok(Self {
+            pool: self.pool.clone(),
+            transaction: Some(Arc::new(Mutex::new(Some(transaction)))),
+        })
+    }
+
+    async fn commit(&self) -> anyhow::Result<(), StorageError> {
+        match self.finish_transaction().await {
+            Some(transaction) => transaction
+                .commit()
+                .await
+                .map_err(|e| StorageError::Transaction { err: e.to_string() }),
+            None => Ok(()),
+        }
+    }
+
+    async fn rollback(&self) -> anyhow::Result<(), StorageError> {
+        match self.finish_transaction().await {
+            Some(transaction) => transaction
+                .rollback()
+                .await
+                .map_err(|e| StorageError::Transaction { err: e.to_string() }),
+            None => Ok(()),
+        }
+    }
+
     async fn get_collections(&self) -> anyhow::Result<Vec<Collection>, StorageError> {
         let schemas: Vec<StoreCollectionQuery> =
             sqlx::query_as(r#"SELECT * FROM storage_collection_schema"#)
-                .fetch_all(&self.pool)
+                .fetch_all(&mut *self.connection("storage_collection_schema").await?)
                 .await
                 .unwrap_or_default();
 
@@ -81,7 +603,7 @@ impl Storage for StorePostgresql {
     ) -> anyhow::Result<Collection, StorageError> {
         sqlx::query_as(r#"SELECT * FROM storage_collection_schema WHERE name = $1"#)
             .bind(collection_name.to_string())
-            .fetch_optional(&self.pool)
+            .fetch_optional(&mut *self.connection(&collection_name).await?)
             .await
             .unwrap_or_default()
             .map(|schema: StoreCollectionQuery| schema.into())
@@ -95,7 +617,11 @@ impl Storage for StorePostgresql {
         collection_name: String,
         collection_fields: Vec<CollectionField>,
     ) -> anyhow::Result<Collection, StorageError> {
-        let mut transaction = self.pool.begin().await.expect("transaction failed");
+        let mut connection = self.connection(&collection_name).await?;
+        let mut transaction = connection
+            .begin()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
 
         let q = format!(
             r#"
@@ -110,8 +636,8 @@ impl Storage for StorePostgresql {
 
         let create_table = sqlx::query(q.as_str()).execute(&mut *transaction).await;
 
-        if create_table.is_err() {
-            error!("create_table: {:?}", create_table.unwrap_err());
+        if let Err(err) = create_table {
+            error!("create_table: {err:?}");
             transaction.rollback().await.unwrap();
             return Err(StorageError::CollectionCreateTable {
                 collection: collection_name.to_string(),
@@ -128,15 +654,12 @@ impl Storage for StorePostgresql {
             match field.name.as_str() {
                 ID_FIELD => {
                     id_exists = true;
-                    break;
                 }
                 CREATED_AT_FIELD => {
                     created_at_exists = true;
-                    break;
                 }
                 UPDATED_AT_FIELD => {
                     updated_at_exists = true;
-                    break;
                 }
                 _ => {
                     let query = Self::query_field_to_collection(collection_name.to_string(), field);
@@ -158,6 +681,8 @@ impl Storage for StorePostgresql {
                 name: ID_FIELD.to_string(),
                 default: None,
                 required: None,
+                unique: None,
+                on_delete: None,
                 field_type: FieldType::String,
             });
         }
@@ -167,6 +692,8 @@ impl Storage for StorePostgresql {
                 name: CREATED_AT_FIELD.to_string(),
                 default: None,
                 required: None,
+                unique: None,
+                on_delete: None,
                 field_type: FieldType::TimeStamp,
             });
         }
@@ -176,6 +703,8 @@ impl Storage for StorePostgresql {
                 name: UPDATED_AT_FIELD.to_string(),
                 default: None,
                 required: None,
+                unique: None,
+                on_delete: None,
                 field_type: FieldType::TimeStamp,
             });
         }
@@ -190,8 +719,8 @@ impl Storage for StorePostgresql {
             .execute(&mut *transaction)
             .await;
 
-        if insert_collection.is_err() {
-            error!("insert_collection: {:?}", insert_collection.unwrap_err());
+        if let Err(err) = insert_collection {
+            error!("insert_collection: {err:?}");
 
             transaction.rollback().await.unwrap();
             return Err(StorageError::CollectionCreate {
@@ -199,23 +728,43 @@ impl Storage for StorePostgresql {
             });
         }
 
-        transaction.commit().await.unwrap();
+        transaction
+            .commit()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
+        drop(connection);
 
         self.get_collection(collection_name.to_string()).await
     }
 
     async fn remove_collection(&self, collection: &Collection) -> Result<(), StorageError> {
-        let mut transaction = self.pool.begin().await.expect("transaction failed");
+        let mut connection = self.connection(&collection.name).await?;
+        let mut transaction = connection
+            .begin()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
+
+        let mut tables = collection
+            .fields
+            .iter()
+            .filter(|field| field.field_type.is_relation())
+            .map(|field| join_table(&collection.name, &field.name))
+            .collect::<Vec<_>>();
+        tables.push(collection.name.to_string());
 
         let drop_table = sqlx::query(&format!(
-            r#"DROP TABLE IF EXISTS "{collection_name}""#,
-            collection_name = collection.name
+            r#"DROP TABLE IF EXISTS {tables}"#,
+            tables = tables
+                .iter()
+                .map(|table| format!(r#""{table}""#))
+                .collect::<Vec<_>>()
+                .join(", ")
         ))
         .execute(&mut *transaction)
         .await;
 
-        if drop_table.is_err() {
-            error!("drop_table: {:?}", drop_table.unwrap_err());
+        if let Err(err) = drop_table {
+            error!("drop_table: {err:?}");
             transaction.rollback().await.unwrap();
             return Err(StorageError::CollectionCreateTable {
                 collection: collection.name.to_string(),
@@ -228,8 +777,8 @@ impl Storage for StorePostgresql {
                 .execute(&mut *transaction)
                 .await;
 
-        if remove_collection.is_err() {
-            error!("remove_collection: {:?}", remove_collection.unwrap_err());
+        if let Err(err) = remove_collection {
+            error!("remove_collection: {err:?}");
 
             transaction.rollback().await.unwrap();
             return Err(StorageError::CollectionRemove {
@@ -237,11 +786,36 @@ impl Storage for StorePostgresql {
             });
         }
 
-        transaction.commit().await.unwrap();
+        transaction
+            .commit()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
+        drop(connection);
 
         Ok(())
     }
 
+    async fn set_collection_policy(
+        &self,
+        collection: &Collection,
+        policy: Option<SchemaPolicy>,
+    ) -> anyhow::Result<Collection, StorageError> {
+        sqlx::query(r#"UPDATE storage_collection_schema SET policy = $1, updated_at = NOW() WHERE name = $2;"#)
+            .bind(policy.map(|policy| policy.as_str()))
+            .bind(collection.name.to_string())
+            .execute(&mut *self.connection(&collection.name).await?)
+            .await
+            .map_err(|e| {
+                error!("set_collection_policy: {e}");
+                StorageError::DBErr {
+                    collection: collection.name.to_string(),
+                    err: e.to_string(),
+                }
+            })?;
+
+        self.get_collection(collection.name.to_string()).await
+    }
+
     async fn insert_field_to_collection(
         &self,
         collection: &Collection,
@@ -254,7 +828,11 @@ impl Storage for StorePostgresql {
             });
         }
 
-        let mut transaction = self.pool.begin().await.expect("transaction failed");
+        let mut connection = self.connection(&collection.name).await?;
+        let mut transaction = connection
+            .begin()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
 
         let query = Self::query_field_to_collection(collection.name.to_string(), &field);
         let create_field = sqlx::query(query.as_str()).execute(&mut *transaction).await;
@@ -286,7 +864,11 @@ impl Storage for StorePostgresql {
             });
         }
 
-        transaction.commit().await.unwrap();
+        transaction
+            .commit()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
+        drop(connection);
 
         self.get_collection(collection.name.to_string()).await
     }
@@ -305,13 +887,23 @@ impl Storage for StorePostgresql {
             });
         }
 
-        let mut transaction = self.pool.begin().await.expect("transaction failed");
+        let mut connection = self.connection(&collection.name).await?;
+        let mut transaction = connection
+            .begin()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
 
-        let query = format!(
-            r#"ALTER TABLE "{collection_name}" DROP COLUMN "{column_name}";"#,
-            collection_name = collection.name,
-            column_name = field.name
-        );
+        let query = match field.field_type {
+            FieldType::Relation { .. } => format!(
+                r#"DROP TABLE IF EXISTS "{join_table}";"#,
+                join_table = join_table(&collection.name, &field.name)
+            ),
+            _ => format!(
+                r#"ALTER TABLE "{collection_name}" DROP COLUMN "{column_name}";"#,
+                collection_name = collection.name,
+                column_name = field.name
+            ),
+        };
         let remove_field = sqlx::query(query.as_str()).execute(&mut *transaction).await;
 
         if remove_field.is_err() {
@@ -347,7 +939,11 @@ impl Storage for StorePostgresql {
             });
         }
 
-        transaction.commit().await.unwrap();
+        transaction
+            .commit()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
+        drop(connection);
 
         self.get_collection(collection.name.to_string()).await
     }
@@ -357,68 +953,17 @@ impl Storage for StorePostgresql {
         collection: &Collection,
         data: Value,
     ) -> anyhow::Result<Value, StorageError> {
-        let mut arguments = PgArguments::default();
-
-        let mut insert_fields = vec![];
-        let mut insert_indexes = vec![];
-        let mut counter = 1;
-        for field in &collection.fields {
-            match field.name.as_str() {
-                CREATED_AT_FIELD | UPDATED_AT_FIELD => {
-                    continue;
-                }
-                ID_FIELD => {
-                    let id = match data.get(field.name.as_str()) {
-                        Some(v) => v.as_str().unwrap_or(&make_id(10)).to_string(),
-                        None => make_id(10),
-                    };
-
-                    insert_fields.push(field.name.to_string());
-                    insert_indexes.push(format!("${counter}"));
-                    arguments.add(id);
-                    counter += 1;
-                }
-                _ => {
-                    let v = data.get(field.name.as_str()).cloned();
-
-                    if let Some(v) = v {
-                        insert_fields.push(field.name.to_string());
-                        insert_indexes.push(format!("${counter}"));
-                        add_value_into_args(field, &v, &mut arguments);
-                        counter += 1;
-                    }
-                }
-            }
-        }
-
-        insert_fields.push(CREATED_AT_FIELD.to_string());
-        insert_fields.push(UPDATED_AT_FIELD.to_string());
-        insert_indexes.push("NOW()".to_string());
-        insert_indexes.push("NOW()".to_string());
-
-        let insert_fields = insert_fields.join(", ");
-        let insert_indexes = insert_indexes.join(", ");
-
-        let rec = sqlx::query_with(format!(r#"INSERT INTO "{collection_name}" ({insert_fields}) VALUES ({insert_indexes}) RETURNING id"#, collection_name = collection.name).as_str(), arguments)
-            .fetch_one(&self.pool)
-            .await
-            .map_err(|e| {
-                error!("insert_data_into_collection: {e}");
-                StorageError::DBErr {
-                    collection: collection.name.to_string(),
-                    err: e.to_string(),
-                }
-            })?;
-
-        let collection_id = rec.get::<String, _>("id");
-
-        info!(
-            "insert_data_into_collection: {collection_name} with id: {collection_id}",
-            collection_name = collection.name
-        );
+        self.insert_row(collection, data, None).await
+    }
 
-        self.get_data_from_collection(collection, collection_id)
-            .await
+    async fn upsert_data_into_collection(
+        &self,
+        collection: &Collection,
+        data: Value,
+        key: &str,
+        update: &Value,
+    ) -> anyhow::Result<Value, StorageError> {
+        self.insert_row(collection, data, Some((key, update))).await
     }
 
     async fn update_data_into_collection(
@@ -426,16 +971,21 @@ impl Storage for StorePostgresql {
         collection: &Collection,
         collection_id: String,
         data: Value,
+        precondition: &Precondition,
     ) -> anyhow::Result<Value, StorageError> {
         let mut arguments = PgArguments::default();
 
         let mut update_fields = vec![];
-        let mut counter = 1;
+        let mut counter: usize = 1;
+        let mut relations = false;
         for field in &collection.fields {
             match field.name.as_str() {
                 CREATED_AT_FIELD | UPDATED_AT_FIELD => {
                     continue;
                 }
+                _ if field.field_type.is_relation() => {
+                    relations |= data.get(field.name.as_str()).is_some();
+                }
                 ID_FIELD => {
                     if let Some(id) = data.get(field.name.as_str()) {
                         if !id.is_null() {
@@ -446,28 +996,36 @@ impl Storage for StorePostgresql {
                     }
                 }
                 _ => {
-                    if let Some(v) = data.get(field.name.as_str()).cloned() {
-                        update_fields.push(format!("{} = ${counter}", field.name));
-                        add_value_into_args(field, &v, &mut arguments);
-                        counter += 1;
+                    if let Some(v) = data.get(field.name.as_str()) {
+                        update_fields.push(update_expression(
+                            &collection.name,
+                            field,
+                            v,
+                            &mut arguments,
+                            &mut counter,
+                        ));
                     }
                 }
             }
         }
-        if !update_fields.is_empty() {
+        if !update_fields.is_empty() || !precondition.is_empty() || relations {
             update_fields.push(format!("{UPDATED_AT_FIELD} = NOW()"));
             arguments.add(collection_id.to_string());
+            let id_counter = counter;
+            counter += 1;
             let update_fields = update_fields.join(", ");
+            let precondition_query =
+                precondition_query(collection, precondition, &mut arguments, &mut counter);
 
-            let _rec = sqlx::query_with(
+            let rec = sqlx::query_with(
                 format!(
-                    r#"UPDATE "{collection_name}" SET {update_fields} WHERE id = ${counter}"#,
+                    r#"UPDATE "{collection_name}" SET {update_fields} WHERE id = ${id_counter} AND {precondition_query}"#,
                     collection_name = collection.name
                 )
                 .as_str(),
                 arguments,
             )
-            .execute(&self.pool)
+            .execute(&mut *self.connection(&collection.name).await?)
             .await
             .map_err(|e| {
                 error!("update_data_into_collection: {e}");
@@ -477,13 +1035,25 @@ impl Storage for StorePostgresql {
                 }
             })?;
 
+            if rec.rows_affected() == 0 && !precondition.is_empty() {
+                return Err(StorageError::Conflict {
+                    collection: collection.name.to_string(),
+                    id: collection_id,
+                });
+            }
+
+            if rec.rows_affected() > 0 {
+                self.update_relations(collection, &[collection_id.to_string()], &data)
+                    .await?;
+            }
+
             debug!(
                 "update_into_collection: {collection_name} with id: {collection_id}",
                 collection_name = collection.name
             );
         }
 
-        self.get_data_from_collection(collection, collection_id)
+        self.get_data_from_collection(collection, collection_id, &Projection::default())
             .await
     }
 
@@ -491,40 +1061,133 @@ impl Storage for StorePostgresql {
         &self,
         collection: &Collection,
         collection_id: String,
+        precondition: &Precondition,
     ) -> anyhow::Result<Value, StorageError> {
-        let query = format!(
-            r#"DELETE FROM "{collection_name}" WHERE id = $1"#,
-            collection_name = collection.name
-        );
+        let mut arguments = PgArguments::default();
+        arguments.add(collection_id.to_string());
 
-        let _rec = sqlx::query(query.as_str())
-            .bind(collection_id)
-            .execute(&self.pool)
-            .await
-            .map_err(|e| {
-                error!("delete_data_from_collection: {e}");
-                StorageError::DBErr {
-                    collection: collection.name.to_string(),
-                    err: e.to_string(),
-                }
-            })?;
+        let mut counter = 2;
+        let precondition_query =
+            precondition_query(collection, precondition, &mut arguments, &mut counter);
+
+        let condition = format!("id = $1 AND {precondition_query}");
+
+        let Some(deleted) = self.delete_rows(collection, &condition, arguments).await? else {
+            return Err(StorageError::Referenced {
+                collection: collection.name.to_string(),
+                id: collection_id,
+            });
+        };
+
+        if deleted == 0 && !precondition.is_empty() {
+            return Err(StorageError::Conflict {
+                collection: collection.name.to_string(),
+                id: collection_id,
+            });
+        }
 
         Ok(Value::Null)
     }
 
+    async fn bulk_update_data_into_collection(
+        &self,
+        collection: &Collection,
+        query: WhereQuery,
+        data: Value,
+    ) -> anyhow::Result<u64, StorageError> {
+        let mut arguments = PgArguments::default();
+        let mut counter: usize = 1;
+
+        let mut update_fields = collection
+            .fields
+            .iter()
+            .filter(|field| {
+                !matches!(
+                    field.name.as_str(),
+                    ID_FIELD | CREATED_AT_FIELD | UPDATED_AT_FIELD
+                ) && !field.field_type.is_relation()
+            })
+            .filter_map(|field| {
+                data.get(field.name.as_str()).map(|v| {
+                    update_expression(&collection.name, field, v, &mut arguments, &mut counter)
+                })
+            })
+            .collect::<Vec<_>>();
+        update_fields.push(format!("{UPDATED_AT_FIELD} = NOW()"));
+
+        let update_fields = update_fields.join(", ");
+        let where_query = where_query(collection, query, &mut arguments, &mut counter);
+
+        let ids = sqlx::query_with(
+            format!(
+                r#"UPDATE "{collection_name}" SET {update_fields} WHERE {where_query} RETURNING id"#,
+                collection_name = collection.name
+            )
+            .as_str(),
+            arguments,
+        )
+        .fetch_all(&mut *self.connection(&collection.name).await?)
+        .await
+        .map_err(|e| {
+            error!("bulk_update_data_into_collection: {e}");
+            StorageError::DBErr {
+                collection: collection.name.to_string(),
+                err: e.to_string(),
+            }
+        })?
+        .into_iter()
+        .map(|row| row.get::<String, _>("id"))
+        .collect::<Vec<_>>();
+
+        self.update_relations(collection, &ids, &data).await?;
+
+        debug!(
+            "bulk_update_data_into_collection: {collection_name} updated {count}",
+            collection_name = collection.name,
+            count = ids.len()
+        );
+
+        Ok(ids.len() as u64)
+    }
+
+    async fn bulk_delete_data_from_collection(
+        &self,
+        collection: &Collection,
+        query: WhereQuery,
+    ) -> anyhow::Result<u64, StorageError> {
+        let mut arguments = PgArguments::default();
+        let mut counter: usize = 1;
+        let where_query = where_query(collection, query.clone(), &mut arguments, &mut counter);
+
+        match self
+            .delete_rows(collection, &where_query, arguments)
+            .await?
+        {
+            Some(deleted) => Ok(deleted),
+            None => Err(StorageError::Referenced {
+                collection: collection.name.to_string(),
+                id: self.referenced_id(collection, query).await?,
+            }),
+        }
+    }
+
     async fn get_data_from_collection(
         &self,
         collection: &Collection,
         collection_id: String,
+        projection: &Projection,
     ) -> anyhow::Result<Value, StorageError> {
+        let collection = &projection.apply(collection);
+
         let query = format!(
-            r#"SELECT * FROM "{collection_name}" WHERE id = $1"#,
+            r#"SELECT {select_fields} FROM "{collection_name}" WHERE id = $1"#,
+            select_fields = Self::select_fields(collection),
             collection_name = collection.name
         );
 
         sqlx::query(query.as_str())
             .bind(collection_id.to_string())
-            .fetch_optional(&self.pool)
+            .fetch_optional(&mut *self.connection(&collection.name).await?)
             .await
             .unwrap_or_default()
             .map(|row| serialize_pg_row(collection, row))
@@ -537,100 +1200,96 @@ impl Storage for StorePostgresql {
     async fn list_data_from_collection(
         &self,
         collection: &Collection,
-        query: HashMap<String, Where>,
+        query: WhereQuery,
+        options: &QueryOptions,
     ) -> anyhow::Result<Vec<Value>, StorageError> {
         let mut arguments = PgArguments::default();
-        let mut where_query = vec![];
         let mut counter = 1;
-        for (key, value) in query {
-            if let Some(field) = collection.get_field(&key) {
-                if let Some(eq) = value.eq {
-                    where_query.push(format!(r#""{key}" = ${counter}"#));
-                    add_value_into_args(field, &eq, &mut arguments);
-                    counter += 1;
-                }
+        let where_query = where_query(collection, query, &mut arguments, &mut counter);
 
-                if let Some(ne) = value.ne {
-                    where_query.push(format!(r#""{key}" != ${counter}"#));
-                    add_value_into_args(field, &ne, &mut arguments);
-                    counter += 1;
-                }
+        let projection = options.projection.apply(collection);
 
-                if let Some(gt) = value.gt {
-                    where_query.push(format!(r#""{key}" > ${counter}"#));
-                    add_value_into_args(field, &gt, &mut arguments);
-                    counter += 1;
-                }
-
-                if let Some(gte) = value.gte {
-                    where_query.push(format!(r#""{key}" >= ${counter}"#));
-                    add_value_into_args(field, &gte, &mut arguments);
-                    counter += 1;
-                }
+        let mut query = format!(
+            r#"SELECT {select_fields} FROM "{collection_name}" WHERE {where_query}"#,
+            select_fields = Self::select_fields(&projection),
+            collection_name = collection.name,
+            where_query = where_query
+        );
 
-                if let Some(lt) = value.lt {
-                    where_query.push(format!(r#""{key}" < ${counter}"#));
-                    add_value_into_args(field, &lt, &mut arguments);
-                    counter += 1;
-                }
+        let order_query = options
+            .order
+            .iter()
+            .filter_map(|order| {
+                let column =
+                    order_expression(collection, &order.field, &mut arguments, &mut counter)?;
+
+                Some(match order.direction {
+                    OrderDirection::Asc => format!("{column} ASC"),
+                    OrderDirection::Desc => format!("{column} DESC"),
+                })
+            })
+            .collect::<Vec<_>>();
 
-                if let Some(lte) = value.lte {
-                    where_query.push(format!(r#""{key}" <= ${counter}"#));
-                    add_value_into_args(field, &lte, &mut arguments);
-                    counter += 1;
-                }
+        if !order_query.is_empty() {
+            query.push_str(format!(" ORDER BY {}", order_query.join(", ")).as_str());
+        }
 
-                if let Some(in_) = value.in_ {
-                    let in_query = iter::repeat(in_.len())
-                        .take(in_.len())
-                        .enumerate()
-                        .map(|a| format!("${}", counter + a.0))
-                        .collect::<Vec<_>>()
-                        .join(", ");
+        if let Some(limit) = options.limit {
+            query.push_str(format!(" LIMIT ${counter}").as_str());
+            arguments.add(limit as i64);
+            counter += 1;
+        }
 
-                    where_query.push(format!(r#""{key}" = ANY(ARRAY[{in_query}])"#));
-                    counter += in_.len();
+        if let Some(skip) = options.skip {
+            query.push_str(format!(" OFFSET ${counter}").as_str());
+            arguments.add(skip as i64);
+        }
 
-                    for v in in_ {
-                        add_value_into_args(field, &v, &mut arguments);
-                    }
+        let values = sqlx::query_with(query.as_str(), arguments)
+            .fetch_all(&mut *self.connection(&collection.name).await?)
+            .await
+            .map_err(|e| {
+                error!("list_data_from_collection: {e}");
+                StorageError::DBErr {
+                    collection: collection.name.to_string(),
+                    err: e.to_string(),
                 }
+            })?;
 
-                if let Some(nin) = value.nin {
-                    let in_query = iter::repeat(nin.len())
-                        .take(nin.len())
-                        .enumerate()
-                        .map(|a| format!("${}", counter + a.0))
-                        .collect::<Vec<_>>()
-                        .join(", ");
-
-                    where_query.push(format!(r#"NOT("{key}" = ANY(ARRAY[{in_query}]))"#));
-                    counter += nin.len();
-                    for v in nin {
-                        add_value_into_args(field, &v, &mut arguments);
-                    }
-                }
-            }
+        let mut result = vec![];
+        for value in values {
+            result.push(serialize_pg_row(&projection, value));
         }
 
-        let where_query = where_query.join(" AND ");
+        Ok(result)
+    }
+
+    async fn count_data_from_collection(
+        &self,
+        collection: &Collection,
+        query: WhereQuery,
+    ) -> anyhow::Result<i64, StorageError> {
+        let mut arguments = PgArguments::default();
+        let mut counter = 1;
+        let where_query = where_query(collection, query, &mut arguments, &mut counter);
 
         let query = format!(
-            r#"SELECT * FROM "{collection_name}" WHERE {where_query}"#,
+            r#"SELECT COUNT(*) AS count FROM "{collection_name}" WHERE {where_query}"#,
             collection_name = collection.name,
             where_query = where_query
         );
 
-        let values = sqlx::query_with(query.as_str(), arguments)
-            .fetch_all(&self.pool)
+        let rec = sqlx::query_with(query.as_str(), arguments)
+            .fetch_one(&mut *self.connection(&collection.name).await?)
             .await
-            .unwrap_or_default();
-
-        let mut result = vec![];
-        for value in values {
-            result.push(serialize_pg_row(collection, value));
-        }
+            .map_err(|e| {
+                error!("count_data_from_collection: {e}");
+                StorageError::DBErr {
+                    collection: collection.name.to_string(),
+                    err: e.to_string(),
+                }
+            })?;
 
-        Ok(result)
+        Ok(rec.get::<i64, _>("count"))
     }
 }
@@ -3,4 +3,7 @@ pub use crate::collection_postgres::StorePostgresql;
 mod add_value_into_args;
 mod collection_postgres;
 mod serialize_pg_row;
+mod store_connection;
 mod store_schema_query;
+mod update_expression;
+mod where_query;
@@ -2,13 +2,13 @@ use chrono::{DateTime, Utc};
 use serde_json::{json, Map, Value};
 use sqlx::{postgres::PgRow, types::Json, Row};
 
-use vivalaakam_seattle_collection::{Collection, FieldType};
+use vivalaakam_seattle_collection::{Collection, Decimal, FieldType, Pointer, TimeStamp};
 
 pub fn serialize_pg_row(collection: &Collection, row: PgRow) -> Value {
     let mut map = Map::new();
 
     for field in &collection.fields {
-        let v = match field.field_type {
+        let v = match &field.field_type {
             FieldType::String => match row.get::<Option<String>, _>(field.name.as_str()) {
                 Some(v) => Value::String(v),
                 None => Value::Null,
@@ -17,6 +17,20 @@ pub fn serialize_pg_row(collection: &Collection, row: PgRow) -> Value {
                 Some(v) => json!(v),
                 None => Value::Null,
             },
+            FieldType::Integer => match row.get::<Option<i32>, _>(field.name.as_str()) {
+                Some(v) => json!(v),
+                None => Value::Null,
+            },
+            FieldType::BigInt => match row.get::<Option<i64>, _>(field.name.as_str()) {
+                Some(v) => json!(v),
+                None => Value::Null,
+            },
+            FieldType::Decimal { .. } => {
+                match row.get::<Option<sqlx::types::Decimal>, _>(field.name.as_str()) {
+                    Some(v) => Decimal(v).to_value(),
+                    None => Value::Null,
+                }
+            }
             FieldType::Boolean => match row.get::<Option<bool>, _>(field.name.as_str()) {
                 Some(v) => Value::Bool(v),
                 None => Value::Null,
@@ -29,13 +43,17 @@ pub fn serialize_pg_row(collection: &Collection, row: PgRow) -> Value {
             }
             FieldType::TimeStamp => {
                 match row.get::<Option<DateTime<Utc>>, _>(field.name.as_str()) {
-                    Some(v) => json!({
-                        "__type": "TimeStamp",
-                        "value": v.to_rfc3339(),
-                    }),
+                    Some(v) => TimeStamp(v).to_value(),
+                    None => Value::Null,
+                }
+            }
+            FieldType::Pointer { target } => {
+                match row.get::<Option<String>, _>(field.name.as_str()) {
+                    Some(v) => Pointer::new(target, &v).to_value(),
                     None => Value::Null,
                 }
             }
+            FieldType::Relation { target } => Pointer::relation_value(target),
         };
 
         map.insert(field.name.to_string(), v);
@@ -0,0 +1,38 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+use sqlx::pool::PoolConnection;
+use sqlx::{PgConnection, Postgres, Transaction};
+use tokio::sync::{Mutex, MutexGuard};
+
+// `None` once the transaction was committed or rolled back
+pub type SharedTransaction = Arc<Mutex<Option<Transaction<'static, Postgres>>>>;
+
+pub enum StoreConnection<'a> {
+    Pool(Box<PoolConnection<Postgres>>),
+    Transaction(MutexGuard<'a, Option<Transaction<'static, Postgres>>>),
+}
+
+impl Deref for StoreConnection<'_> {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            StoreConnection::Pool(connection) => connection,
+            StoreConnection::Transaction(transaction) => {
+                transaction.as_ref().expect("transaction is finished")
+            }
+        }
+    }
+}
+
+impl DerefMut for StoreConnection<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            StoreConnection::Pool(connection) => connection,
+            StoreConnection::Transaction(transaction) => {
+                transaction.as_mut().expect("transaction is finished")
+            }
+        }
+    }
+}
@@ -10,6 +10,7 @@ pub struct StoreCollectionQuery {
     fields: Json<Value>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
+    policy: Option<String>,
 }
 
 impl From<StoreCollectionQuery> for Collection {
@@ -19,6 +20,7 @@ impl From<StoreCollectionQuery> for Collection {
             fields: serde_json::from_value(val.fields.0).unwrap(),
             created_at: val.created_at,
             updated_at: val.updated_at,
+            policy: val.policy.and_then(|policy| policy.parse().ok()),
         }
     }
 }
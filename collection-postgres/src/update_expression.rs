@@ -0,0 +1,55 @@
+use serde_json::Value;
+use sqlx::postgres::PgArguments;
+use sqlx::types::Json;
+use sqlx::Arguments;
+
+use vivalaakam_seattle_collection::{CollectionField, FieldOperation};
+
+use crate::add_value_into_args::add_value_into_args;
+
+// operations are evaluated against the stored value so concurrent updates don't overwrite each other.
+// reads name the table, `ON CONFLICT` would find the column in `EXCLUDED` too
+pub fn update_expression(
+    collection_name: &str,
+    field: &CollectionField,
+    value: &Value,
+    arguments: &mut PgArguments,
+    counter: &mut usize,
+) -> String {
+    let target = format!(r#""{}""#, field.name);
+    let column = format!(r#""{collection_name}".{target}"#);
+    let array = format!("COALESCE({column}, '[]'::jsonb)");
+
+    let expression = match FieldOperation::from_value(value) {
+        Some(Ok(FieldOperation::Increment { amount })) => {
+            // bound with the column type, float8 would round large integers and decimals
+            add_value_into_args(field, &Value::Number(amount), arguments);
+            format!("COALESCE({column}, 0) + ${counter}")
+        }
+        Some(Ok(FieldOperation::Add { objects })) => {
+            arguments.add(Json(Value::Array(objects)));
+            format!("{array} || ${counter}")
+        }
+        Some(Ok(FieldOperation::AddUnique { objects })) => {
+            arguments.add(Json(Value::Array(FieldOperation::unique_objects(&objects))));
+            format!(
+                "{array} || COALESCE((SELECT jsonb_agg(added.value ORDER BY added.position) FROM jsonb_array_elements(${counter}) WITH ORDINALITY AS added(value, position) WHERE NOT EXISTS (SELECT 1 FROM jsonb_array_elements({array}) AS current(value) WHERE current.value = added.value)), '[]'::jsonb)"
+            )
+        }
+        Some(Ok(FieldOperation::Remove { objects })) => {
+            arguments.add(Json(Value::Array(objects)));
+            format!(
+                "COALESCE((SELECT jsonb_agg(current.value ORDER BY current.position) FROM jsonb_array_elements({array}) WITH ORDINALITY AS current(value, position) WHERE NOT EXISTS (SELECT 1 FROM jsonb_array_elements(${counter}) AS removed(value) WHERE removed.value = current.value)), '[]'::jsonb)"
+            )
+        }
+        Some(Ok(FieldOperation::Delete)) => return format!("{target} = NULL"),
+        _ => {
+            add_value_into_args(field, value, arguments);
+            format!("${counter}")
+        }
+    };
+
+    *counter += 1;
+
+    format!("{target} = {expression}")
+}
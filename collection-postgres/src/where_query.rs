@@ -0,0 +1,342 @@
+use sqlx::postgres::PgArguments;
+use sqlx::types::Json;
+use sqlx::Arguments;
+
+use vivalaakam_seattle_collection::{
+    Collection, CollectionField, FieldType, Precondition, Where, WhereQuery,
+};
+
+use crate::add_value_into_args::add_value_into_args;
+
+// relations of `collection.field` as ("owningId", "relatedId") pairs
+pub fn join_table(collection_name: &str, field_name: &str) -> String {
+    format!("_Join:{field_name}:{collection_name}")
+}
+
+fn relation_query(
+    collection: &Collection,
+    field: &CollectionField,
+    value: Where,
+    arguments: &mut PgArguments,
+    counter: &mut usize,
+) -> Vec<String> {
+    let owning_ids = |condition: String| {
+        format!(
+            r#""id" IN (SELECT "owningId" FROM "{join_table}" WHERE {condition})"#,
+            join_table = join_table(&collection.name, &field.name)
+        )
+    };
+
+    let mut conditions = vec![];
+
+    if let Some(eq) = value.eq {
+        conditions.push(owning_ids(format!(r#""relatedId" = ${counter}"#)));
+        add_value_into_args(field, &eq, arguments);
+        *counter += 1;
+    }
+
+    if let Some(in_) = value.in_ {
+        let in_query = (0..in_.len())
+            .map(|i| format!("${}", *counter + i))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        conditions.push(owning_ids(format!(
+            r#""relatedId" = ANY(ARRAY[{in_query}])"#
+        )));
+        *counter += in_.len();
+
+        for v in in_ {
+            add_value_into_args(field, &v, arguments);
+        }
+    }
+
+    conditions
+}
+
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+pub fn order_expression(
+    collection: &Collection,
+    key: &str,
+    arguments: &mut PgArguments,
+    counter: &mut usize,
+) -> Option<String> {
+    if let Some(field) = collection.get_field(&key.to_string()) {
+        return Some(format!(r#""{}""#, field.name));
+    }
+
+    let (field, path) = collection.get_nested_field(key)?;
+    let column = format!(r#""{}" #> ${counter}"#, field.name);
+    arguments.add(path);
+    *counter += 1;
+
+    Some(column)
+}
+
+fn column_expression(
+    collection: &Collection,
+    key: &str,
+    value_type: Option<FieldType>,
+    arguments: &mut PgArguments,
+    counter: &mut usize,
+) -> Option<(String, CollectionField)> {
+    if let Some(field) = collection.get_field(&key.to_string()) {
+        return Some((format!(r#""{key}""#), field.clone()));
+    }
+
+    let (field, path) = collection.get_nested_field(key)?;
+    let json = format!(r#""{}" #> ${counter}"#, field.name);
+    let text = format!(r#""{}" #>> ${counter}"#, field.name);
+    arguments.add(path);
+    *counter += 1;
+
+    let column = match value_type {
+        None => format!("({text})"),
+        Some(FieldType::String | FieldType::TimeStamp) => {
+            format!("(CASE WHEN jsonb_typeof({json}) = 'string' THEN {text} END)")
+        }
+        Some(FieldType::Number | FieldType::Integer | FieldType::BigInt) => {
+            format!("(CASE WHEN jsonb_typeof({json}) = 'number' THEN ({text})::float8 END)")
+        }
+        Some(FieldType::Boolean) => {
+            format!("(CASE WHEN jsonb_typeof({json}) = 'boolean' THEN ({text})::boolean END)")
+        }
+        Some(FieldType::Array) => {
+            format!("(CASE WHEN jsonb_typeof({json}) = 'array' THEN {json} END)")
+        }
+        Some(
+            FieldType::Object
+            | FieldType::Pointer { .. }
+            | FieldType::Relation { .. }
+            | FieldType::Decimal { .. },
+        ) => {
+            format!("(CASE WHEN jsonb_typeof({json}) = 'object' THEN {json} END)")
+        }
+    };
+
+    let field = CollectionField {
+        name: key.to_string(),
+        field_type: value_type.unwrap_or(FieldType::String),
+        default: None,
+        required: None,
+        unique: None,
+        on_delete: None,
+    };
+
+    Some((column, field))
+}
+
+pub fn where_query(
+    collection: &Collection,
+    query: WhereQuery,
+    arguments: &mut PgArguments,
+    counter: &mut usize,
+) -> String {
+    let mut conditions = vec![];
+    for (key, value) in query.fields {
+        let value_type = value.value_type();
+        if let Some((column, field)) =
+            column_expression(collection, &key, value_type, arguments, counter)
+        {
+            if field.field_type.is_relation() {
+                conditions.extend(relation_query(
+                    collection, &field, value, arguments, counter,
+                ));
+                continue;
+            }
+
+            let case_insensitive = value.case_insensitive();
+
+            if let Some(eq) = value.eq {
+                conditions.push(format!(r#"{column} = ${counter}"#));
+                add_value_into_args(&field, &eq, arguments);
+                *counter += 1;
+            }
+
+            if let Some(ne) = value.ne {
+                conditions.push(format!(r#"{column} != ${counter}"#));
+                add_value_into_args(&field, &ne, arguments);
+                *counter += 1;
+            }
+
+            if let Some(gt) = value.gt {
+                conditions.push(format!(r#"{column} > ${counter}"#));
+                add_value_into_args(&field, &gt, arguments);
+                *counter += 1;
+            }
+
+            if let Some(gte) = value.gte {
+                conditions.push(format!(r#"{column} >= ${counter}"#));
+                add_value_into_args(&field, &gte, arguments);
+                *counter += 1;
+            }
+
+            if let Some(lt) = value.lt {
+                conditions.push(format!(r#"{column} < ${counter}"#));
+                add_value_into_args(&field, &lt, arguments);
+                *counter += 1;
+            }
+
+            if let Some(lte) = value.lte {
+                conditions.push(format!(r#"{column} <= ${counter}"#));
+                add_value_into_args(&field, &lte, arguments);
+                *counter += 1;
+            }
+
+            if let Some(in_) = value.in_ {
+                let in_query = (0..in_.len())
+                    .map(|i| format!("${}", *counter + i))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                conditions.push(format!(r#"{column} = ANY(ARRAY[{in_query}])"#));
+                *counter += in_.len();
+
+                for v in in_ {
+                    add_value_into_args(&field, &v, arguments);
+                }
+            }
+
+            if let Some(nin) = value.nin {
+                let in_query = (0..nin.len())
+                    .map(|i| format!("${}", *counter + i))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                conditions.push(format!(r#"NOT({column} = ANY(ARRAY[{in_query}]))"#));
+                *counter += nin.len();
+                for v in nin {
+                    add_value_into_args(&field, &v, arguments);
+                }
+            }
+
+            if let Some(exists) = value.exists {
+                let operator = if exists { "IS NOT NULL" } else { "IS NULL" };
+                conditions.push(format!(r#"{column} {operator}"#));
+            }
+
+            if let Some(regex) = value.regex {
+                let operator = if case_insensitive { "~*" } else { "~" };
+                conditions.push(format!(r#"{column} {operator} ${counter}"#));
+                arguments.add(regex);
+                *counter += 1;
+            }
+
+            let like = if case_insensitive { "ILIKE" } else { "LIKE" };
+
+            if let Some(starts_with) = value.starts_with {
+                conditions.push(format!(r#"{column} {like} ${counter}"#));
+                arguments.add(format!("{}%", escape_like(&starts_with)));
+                *counter += 1;
+            }
+
+            if let Some(ends_with) = value.ends_with {
+                conditions.push(format!(r#"{column} {like} ${counter}"#));
+                arguments.add(format!("%{}", escape_like(&ends_with)));
+                *counter += 1;
+            }
+
+            if let Some(text) = value.text {
+                conditions.push(format!(r#"{column} {like} ${counter}"#));
+                arguments.add(format!("%{}%", escape_like(&text)));
+                *counter += 1;
+            }
+
+            if let Some(all) = value.all {
+                conditions.push(format!(r#"{column} @> ${counter}"#));
+                arguments.add(Json(all));
+                *counter += 1;
+            }
+
+            if let Some(contained_by) = value.contained_by {
+                conditions.push(format!(r#"{column} <@ ${counter}"#));
+                arguments.add(Json(contained_by));
+                *counter += 1;
+            }
+
+            if let Some(contains) = value.contains {
+                conditions.push(format!(r#"{column} @> ${counter}"#));
+                arguments.add(Json(vec![contains]));
+                *counter += 1;
+            }
+
+            if let Some(size) = value.size {
+                conditions.push(format!(r#"jsonb_array_length({column}) = ${counter}"#));
+                arguments.add(size as i32);
+                *counter += 1;
+            }
+        }
+    }
+
+    for and in query.and {
+        let and_query = where_query(collection, and, arguments, counter);
+        conditions.push(format!("({and_query})"));
+    }
+
+    if !query.or.is_empty() {
+        let or_query = query
+            .or
+            .into_iter()
+            .map(|or| format!("({})", where_query(collection, or, arguments, counter)))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        conditions.push(format!("({or_query})"));
+    }
+
+    if !query.nor.is_empty() {
+        let nor_query = query
+            .nor
+            .into_iter()
+            .map(|nor| format!("({})", where_query(collection, nor, arguments, counter)))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        conditions.push(format!("NOT ({nor_query})"));
+    }
+
+    if let Some(related_to) = query.related_to {
+        conditions.push(format!(
+            r#""id" IN (SELECT "relatedId" FROM "{join_table}" WHERE "owningId" = ${counter})"#,
+            join_table = join_table(&related_to.object.class_name, &related_to.key)
+        ));
+        arguments.add(related_to.object.object_id);
+        *counter += 1;
+    }
+
+    if conditions.is_empty() {
+        "TRUE".to_string()
+    } else {
+        conditions.join(" AND ")
+    }
+}
+
+pub fn precondition_query(
+    collection: &Collection,
+    precondition: &Precondition,
+    arguments: &mut PgArguments,
+    counter: &mut usize,
+) -> String {
+    let mut conditions = vec![];
+
+    if let Some(updated_at) = precondition.updated_at {
+        conditions.push(format!(r#""updated_at" = ${counter}"#));
+        arguments.add(updated_at);
+        *counter += 1;
+    }
+
+    if !precondition.query.is_empty() {
+        let query = where_query(collection, precondition.query.clone(), arguments, counter);
+        conditions.push(format!("({query})"));
+    }
+
+    if conditions.is_empty() {
+        "TRUE".to_string()
+    } else {
+        conditions.join(" AND ")
+    }
+}
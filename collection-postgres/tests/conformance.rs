@@ -0,0 +1,33 @@
+use std::env;
+
+use dotenv::dotenv;
+use tracing_subscriber::filter::LevelFilter;
+
+use helpers::cleanup_table::cleanup_table;
+use vivalaakam_seattle_collection_postgres::StorePostgresql;
+use vivalaakam_seattle_collection_tests::storage_tests;
+
+mod helpers;
+
+async fn storage(tables: &[&str]) -> StorePostgresql {
+    dotenv().ok();
+
+    // tests share the binary, only the first one installs the subscriber
+    let _ = tracing_subscriber::fmt()
+        .with_max_level(LevelFilter::DEBUG)
+        .with_test_writer()
+        .try_init();
+
+    let database_url =
+        env::var("POSTGRES_DATABASE_URL").expect("POSTGRES_DATABASE_URL must be set");
+
+    let instance = StorePostgresql::new(database_url.as_str()).await;
+
+    for table_name in tables {
+        cleanup_table(instance.get_pool(), table_name).await;
+    }
+
+    instance
+}
+
+storage_tests!(storage);
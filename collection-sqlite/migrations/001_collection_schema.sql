@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS storage_collection_schema
+(
+    name       VARCHAR(36) PRIMARY KEY,
+    fields     TEXT DEFAULT '[]' NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
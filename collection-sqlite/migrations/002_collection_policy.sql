@@ -0,0 +1 @@
+ALTER TABLE storage_collection_schema ADD COLUMN policy TEXT;
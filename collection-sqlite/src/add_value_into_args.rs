@@ -0,0 +1,45 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::Value;
+use sqlx::sqlite::SqliteArguments;
+use sqlx::Arguments;
+
+use vivalaakam_seattle_collection::{CollectionField, Decimal, FieldType, Pointer, TimeStamp};
+
+pub fn format_timestamp(value: DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::Micros, true)
+}
+
+pub fn add_value_into_args(field: &CollectionField, value: &Value, args: &mut SqliteArguments) {
+    match field.field_type {
+        FieldType::String => {
+            args.add(value.as_str().map(|v| v.to_string()));
+        }
+        FieldType::Array | FieldType::Object => {
+            args.add((!value.is_null()).then(|| value.to_string()));
+        }
+        FieldType::Number => {
+            args.add(value.as_f64());
+        }
+        FieldType::Integer | FieldType::BigInt => {
+            args.add(FieldType::integer_value(value));
+        }
+        // kept as text, sqlite has no exact decimal type
+        FieldType::Decimal { scale, .. } => {
+            args.add(Decimal::from_value(value).map(|v| v.round(scale).to_string()));
+        }
+        FieldType::Boolean => {
+            args.add(value.as_bool());
+        }
+        FieldType::TimeStamp => {
+            args.add(TimeStamp::from_value(value).map(|v| format_timestamp(v.0)));
+        }
+        // queries may compare pointers with a plain object id
+        FieldType::Pointer { .. } | FieldType::Relation { .. } => {
+            args.add(
+                Pointer::from_value(value)
+                    .map(|pointer| pointer.object_id)
+                    .or_else(|| value.as_str().map(|v| v.to_string())),
+            );
+        }
+    };
+}
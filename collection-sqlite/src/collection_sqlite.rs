@@ -0,0 +1,1291 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::{json, Value};
+use sqlx::sqlite::{SqliteArguments, SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{Arguments, Connection, Pool, Row, Sqlite, SqliteConnection, Transaction};
+use tokio::sync::Mutex;
+use tracing::{debug, error, info};
+
+use vivalaakam_seattle_collection::{
+    make_id, Collection, CollectionField, FieldOperation, FieldType, OnDelete, OrderDirection,
+    Precondition, Projection, QueryOptions, SchemaPolicy, Storage, StorageError, WhereQuery,
+};
+
+use crate::add_value_into_args::{add_value_into_args, format_timestamp};
+use crate::serialize_sqlite_row::serialize_sqlite_row;
+use crate::store_connection::{SharedTransaction, StoreConnection};
+use crate::store_schema_query::StoreCollectionQuery;
+use crate::update_expression::update_expression;
+use crate::where_query::{
+    compare_decimals, join_table, order_expression, precondition_query, where_query,
+    DECIMAL_COLLATION,
+};
+
+#[derive(Clone)]
+pub struct StoreSqlite {
+    pub pool: Pool<Sqlite>,
+    transaction: Option<SharedTransaction>,
+}
+
+const ID_FIELD: &str = "id";
+const CREATED_AT_FIELD: &str = "created_at";
+const UPDATED_AT_FIELD: &str = "updated_at";
+
+impl StoreSqlite {
+    fn select_fields(collection: &Collection) -> String {
+        collection
+            .fields
+            .iter()
+            .filter(|field| !field.field_type.is_relation())
+            .map(|field| format!(r#""{}""#, field.name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub async fn new(database_url: &str) -> Self {
+        let options = SqliteConnectOptions::from_str(database_url)
+            .expect("sqlite url is invalid")
+            .create_if_missing(true)
+            .with_regexp()
+            .collation(DECIMAL_COLLATION, compare_decimals);
+
+        // every connection to `sqlite::memory:` opens its own database
+        let pool = if database_url.contains(":memory:") {
+            SqlitePoolOptions::new()
+                .max_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None)
+        } else {
+            SqlitePoolOptions::new()
+        }
+        .connect_with(options)
+        .await
+        .expect("sqlite fails");
+
+        sqlx::migrate!()
+            .run(&pool)
+            .await
+            .expect("sqlite migration failed");
+
+        Self {
+            pool,
+            transaction: None,
+        }
+    }
+
+    fn query_field_to_collection(schema: String, field: &CollectionField) -> String {
+        let query = Self::query_column_to_collection(&schema, field);
+
+        // sqlite can't add a column with a unique constraint
+        match field.unique.unwrap_or_default() {
+            true => format!(
+                r#"{query} CREATE UNIQUE INDEX "{schema}_{name}_key" ON "{schema}" ("{name}");"#,
+                name = field.name
+            ),
+            false => query,
+        }
+    }
+
+    fn query_column_to_collection(schema: &str, field: &CollectionField) -> String {
+        match &field.field_type {
+            FieldType::String => format!(r#"ALTER TABLE "{schema}" ADD "{}" TEXT;"#, field.name),
+            FieldType::Number => format!(r#"ALTER TABLE "{schema}" ADD "{}" REAL;"#, field.name),
+            FieldType::Integer | FieldType::BigInt => {
+                format!(r#"ALTER TABLE "{schema}" ADD "{}" INTEGER;"#, field.name)
+            }
+            FieldType::Decimal { .. } => {
+                format!(r#"ALTER TABLE "{schema}" ADD "{}" TEXT;"#, field.name)
+            }
+            FieldType::Boolean => {
+                format!(r#"ALTER TABLE "{schema}" ADD "{}" BOOLEAN;"#, field.name)
+            }
+            FieldType::Array | FieldType::Object => {
+                format!(r#"ALTER TABLE "{schema}" ADD "{}" TEXT;"#, field.name)
+            }
+            FieldType::TimeStamp => {
+                format!(r#"ALTER TABLE "{schema}" ADD "{}" TEXT;"#, field.name)
+            }
+            FieldType::Pointer { target } => format!(
+                r#"ALTER TABLE "{schema}" ADD "{}" TEXT{};"#,
+                field.name,
+                Self::foreign_key(target, field.on_delete)
+            ),
+            FieldType::Relation { .. } => format!(
+                r#"CREATE TABLE IF NOT EXISTS "{join_table}" ("owningId" TEXT NOT NULL, "relatedId" TEXT NOT NULL, PRIMARY KEY ("owningId", "relatedId"));"#,
+                join_table = join_table(schema, &field.name)
+            ),
+        }
+    }
+
+    fn now() -> String {
+        format_timestamp(Utc::now())
+    }
+
+    // pointers without a rule may dangle, as they did before rules existed
+    fn foreign_key(target: &str, on_delete: Option<OnDelete>) -> String {
+        let action = match on_delete {
+            Some(OnDelete::Restrict) => "RESTRICT",
+            Some(OnDelete::Cascade) => "CASCADE",
+            Some(OnDelete::SetNull) => "SET NULL",
+            None => return String::new(),
+        };
+
+        format!(r#" REFERENCES "{target}" (id) ON DELETE {action}"#)
+    }
+
+    // RESTRICT actions fail with SQLITE_CONSTRAINT_TRIGGER rather than the foreign key code
+    fn is_foreign_key_violation(error: &sqlx::Error) -> bool {
+        error.as_database_error().is_some_and(|e| {
+            e.is_foreign_key_violation() || e.message().contains("FOREIGN KEY constraint failed")
+        })
+    }
+
+    pub fn get_pool(&self) -> &Pool<Sqlite> {
+        &self.pool
+    }
+
+    // statements of a transactional store share its transaction
+    async fn connection(
+        &self,
+        collection_name: &str,
+    ) -> anyhow::Result<StoreConnection<'_>, StorageError> {
+        let connection = match &self.transaction {
+            Some(transaction) => {
+                let transaction = transaction.lock().await;
+
+                transaction
+                    .is_some()
+                    .then_some(StoreConnection::Transaction(transaction))
+                    .ok_or_else(|| "transaction is finished".to_string())
+            }
+            None => self
+                .pool
+                .acquire()
+                .await
+                .map(|connection| StoreConnection::Pool(Box::new(connection)))
+                .map_err(|e| e.to_string()),
+        };
+
+        connection.map_err(|err| {
+            error!("connection: {err}");
+            StorageError::DBErr {
+                collection: collection_name.to_string(),
+                err,
+            }
+        })
+    }
+
+    async fn finish_transaction(&self) -> Option<Transaction<'static, Sqlite>> {
+        match &self.transaction {
+            Some(transaction) => transaction.lock().await.take(),
+            None => None,
+        }
+    }
+
+    // applies AddRelation and RemoveRelation of `data` to every owning row
+    async fn update_relations(
+        &self,
+        collection: &Collection,
+        ids: &[String],
+        data: &Value,
+    ) -> anyhow::Result<(), StorageError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        for field in &collection.fields {
+            let Some(Ok(operation)) = data
+                .get(field.name.as_str())
+                .and_then(FieldOperation::from_value)
+            else {
+                continue;
+            };
+
+            let join_table = join_table(&collection.name, &field.name);
+
+            let query = match operation {
+                FieldOperation::AddRelation { .. } => format!(
+                    r#"INSERT OR IGNORE INTO "{join_table}" ("owningId", "relatedId") SELECT owning.value, related.value FROM json_each(?1) AS owning CROSS JOIN json_each(?2) AS related"#
+                ),
+                FieldOperation::RemoveRelation { .. } => format!(
+                    r#"DELETE FROM "{join_table}" WHERE "owningId" IN (SELECT value FROM json_each(?1)) AND "relatedId" IN (SELECT value FROM json_each(?2))"#
+                ),
+                _ => continue,
+            };
+
+            sqlx::query(query.as_str())
+                .bind(json!(ids).to_string())
+                .bind(json!(operation.relation_ids()).to_string())
+                .execute(&mut *self.connection(&collection.name).await?)
+                .await
+                .map_err(|e| {
+                    error!("update_relations: {e}");
+                    StorageError::DBErr {
+                        collection: collection.name.to_string(),
+                        err: e.to_string(),
+                    }
+                })?;
+        }
+
+        Ok(())
+    }
+
+    // schemas with a pointer or a relation to `target`
+    async fn referencing_collections(
+        connection: &mut SqliteConnection,
+        target: &str,
+    ) -> anyhow::Result<Vec<Collection>, StorageError> {
+        let schemas: Vec<StoreCollectionQuery> = sqlx::query_as(
+            r#"SELECT * FROM storage_collection_schema WHERE EXISTS (SELECT 1 FROM json_each(fields) AS field WHERE json_extract(field.value, '$.field_type.Pointer.target') = ?1 OR json_extract(field.value, '$.field_type.Relation.target') = ?1)"#,
+        )
+        .bind(target)
+        .fetch_all(connection)
+        .await
+        .map_err(|e| {
+            error!("referencing_collections: {e}");
+            StorageError::DBErr {
+                collection: target.to_string(),
+                err: e.to_string(),
+            }
+        })?;
+
+        Ok(schemas.into_iter().map(|schema| schema.into()).collect())
+    }
+
+    // deletes the matching rows in one transaction with the relations they hold or that point
+    // at them, following cascading pointers. `None` when a restricting pointer still
+    // references one of them
+    async fn delete_rows(
+        &self,
+        collection: &Collection,
+        condition: &str,
+        arguments: SqliteArguments<'_>,
+    ) -> anyhow::Result<Option<u64>, StorageError> {
+        let db_error = |e: sqlx::Error| {
+            error!("delete_rows: {e}");
+            StorageError::DBErr {
+                collection: collection.name.to_string(),
+                err: e.to_string(),
+            }
+        };
+
+        let mut connection = self.connection(&collection.name).await?;
+        let mut transaction = connection
+            .begin()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
+
+        let ids = sqlx::query_with(
+            format!(
+                r#"SELECT id FROM "{collection_name}" WHERE {condition}"#,
+                collection_name = collection.name
+            )
+            .as_str(),
+            arguments,
+        )
+        .fetch_all(&mut *transaction)
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .map(|row| row.get::<String, _>("id"))
+        .collect::<Vec<_>>();
+
+        if ids.is_empty() {
+            return Ok(Some(0));
+        }
+
+        // cascaded rows have to be found while they still exist
+        let mut deleted = vec![(collection.clone(), ids.clone())];
+        let mut index = 0;
+
+        while let Some((target, target_ids)) = deleted.get(index).cloned() {
+            index += 1;
+
+            for field in target
+                .fields
+                .iter()
+                .filter(|field| field.field_type.is_relation())
+            {
+                sqlx::query(
+                    format!(
+                        r#"DELETE FROM "{join_table}" WHERE "owningId" IN (SELECT value FROM json_each(?1))"#,
+                        join_table = join_table(&target.name, &field.name)
+                    )
+                    .as_str(),
+                )
+                .bind(json!(target_ids).to_string())
+                .execute(&mut *transaction)
+                .await
+                .map_err(db_error)?;
+            }
+
+            for schema in Self::referencing_collections(&mut transaction, &target.name).await? {
+                for field in &schema.fields {
+                    match &field.field_type {
+                        FieldType::Relation { target: related } if *related == target.name => {
+                            sqlx::query(
+                                format!(
+                                    r#"DELETE FROM "{join_table}" WHERE "relatedId" IN (SELECT value FROM json_each(?1))"#,
+                                    join_table = join_table(&schema.name, &field.name)
+                                )
+                                .as_str(),
+                            )
+                            .bind(json!(target_ids).to_string())
+                            .execute(&mut *transaction)
+                            .await
+                            .map_err(db_error)?;
+                        }
+                        FieldType::Pointer { target: pointed }
+                            if *pointed == target.name
+                                && field.on_delete == Some(OnDelete::Cascade) =>
+                        {
+                            let cascaded = sqlx::query(
+                                format!(
+                                    r#"SELECT id FROM "{collection_name}" WHERE "{field_name}" IN (SELECT value FROM json_each(?1))"#,
+                                    collection_name = schema.name,
+                                    field_name = field.name
+                                )
+                                .as_str(),
+                            )
+                            .bind(json!(target_ids).to_string())
+                            .fetch_all(&mut *transaction)
+                            .await
+                            .map_err(db_error)?
+                            .into_iter()
+                            .map(|row| row.get::<String, _>("id"))
+                            .filter(|id| {
+                                !deleted
+                                    .iter()
+                                    .any(|(c, ids)| c.name == schema.name && ids.contains(id))
+                            })
+                            .collect::<Vec<_>>();
+
+                            if !cascaded.is_empty() {
+                                deleted.push((schema.clone(), cascaded));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        let rec = sqlx::query(
+            format!(
+                r#"DELETE FROM "{collection_name}" WHERE id IN (SELECT value FROM json_each(?1))"#,
+                collection_name = collection.name
+            )
+            .as_str(),
+        )
+        .bind(json!(ids).to_string())
+        .execute(&mut *transaction)
+        .await;
+
+        let rec = match rec {
+            Ok(rec) => rec,
+            Err(e) if Self::is_foreign_key_violation(&e) => return Ok(None),
+            Err(e) => return Err(db_error(e)),
+        };
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
+
+        Ok(Some(rec.rows_affected()))
+    }
+
+    // the database doesn't name the referenced row, so prefer one a restricting pointer holds
+    async fn referenced_id(
+        &self,
+        collection: &Collection,
+        query: WhereQuery,
+    ) -> anyhow::Result<String, StorageError> {
+        let references = Self::referencing_collections(
+            &mut *self.connection(&collection.name).await?,
+            &collection.name,
+        )
+        .await?
+        .iter()
+            .flat_map(|schema| {
+                schema
+                    .fields
+                    .iter()
+                    .filter(|field| {
+                        field.on_delete == Some(OnDelete::Restrict)
+                            && matches!(&field.field_type, FieldType::Pointer { target } if *target == collection.name)
+                    })
+                    .map(|field| format!(r#"SELECT "{}" FROM "{}""#, field.name, schema.name))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let order = match references.is_empty() {
+            true => String::new(),
+            false => format!(" ORDER BY id IN ({}) DESC", references.join(" UNION ")),
+        };
+
+        let mut arguments = SqliteArguments::default();
+        let mut counter: usize = 1;
+        let where_query = where_query(collection, query, &mut arguments, &mut counter);
+
+        let rec = sqlx::query_with(
+            format!(
+                r#"SELECT id FROM "{collection_name}" WHERE {where_query}{order} LIMIT 1"#,
+                collection_name = collection.name
+            )
+            .as_str(),
+            arguments,
+        )
+        .fetch_optional(&mut *self.connection(&collection.name).await?)
+        .await
+        .map_err(|e| {
+            error!("referenced_id: {e}");
+            StorageError::DBErr {
+                collection: collection.name.to_string(),
+                err: e.to_string(),
+            }
+        })?;
+
+        Ok(rec
+            .map(|rec| rec.get::<String, _>("id"))
+            .unwrap_or_default())
+    }
+
+    async fn insert_row(
+        &self,
+        collection: &Collection,
+        data: Value,
+        upsert: Option<(&str, &Value)>,
+    ) -> anyhow::Result<Value, StorageError> {
+        let mut arguments = SqliteArguments::default();
+
+        let mut insert_fields = vec![];
+        let mut insert_indexes = vec![];
+        let mut counter = 1;
+        for field in &collection.fields {
+            match field.name.as_str() {
+                CREATED_AT_FIELD | UPDATED_AT_FIELD => {
+                    continue;
+                }
+                ID_FIELD => {
+                    let id = match data.get(field.name.as_str()) {
+                        Some(v) => v.as_str().unwrap_or(&make_id(10)).to_string(),
+                        None => make_id(10),
+                    };
+
+                    insert_fields.push(format!(r#""{}""#, field.name));
+                    insert_indexes.push(format!("?{counter}"));
+                    arguments.add(id);
+                    counter += 1;
+                }
+                _ if field.field_type.is_relation() => {
+                    continue;
+                }
+                _ => {
+                    let v = data.get(field.name.as_str()).cloned();
+
+                    if let Some(v) = v {
+                        insert_fields.push(format!(r#""{}""#, field.name));
+                        insert_indexes.push(format!("?{counter}"));
+                        add_value_into_args(field, &v, &mut arguments);
+                        counter += 1;
+                    }
+                }
+            }
+        }
+
+        insert_fields.push(CREATED_AT_FIELD.to_string());
+        insert_fields.push(UPDATED_AT_FIELD.to_string());
+        insert_indexes.push(format!("?{counter}"));
+        insert_indexes.push(format!("?{counter}"));
+        arguments.add(Self::now());
+        let now = counter;
+        counter += 1;
+
+        let insert_fields = insert_fields.join(", ");
+        let insert_indexes = insert_indexes.join(", ");
+
+        let on_conflict = match upsert {
+            // unqualified columns are the stored row, `excluded` holds the new one
+            Some((key, update)) => {
+                let mut update_fields = collection
+                    .fields
+                    .iter()
+                    .filter(|field| {
+                        !matches!(
+                            field.name.as_str(),
+                            ID_FIELD | CREATED_AT_FIELD | UPDATED_AT_FIELD
+                        ) && !field.field_type.is_relation()
+                    })
+                    .filter_map(|field| {
+                        update
+                            .get(field.name.as_str())
+                            .map(|v| update_expression(field, v, &mut arguments, &mut counter))
+                    })
+                    .collect::<Vec<_>>();
+                update_fields.push(format!("{UPDATED_AT_FIELD} = ?{now}"));
+
+                format!(
+                    r#" ON CONFLICT ("{key}") DO UPDATE SET {update_fields}"#,
+                    update_fields = update_fields.join(", ")
+                )
+            }
+            None => String::new(),
+        };
+
+        let rec = sqlx::query_with(format!(r#"INSERT INTO "{collection_name}" ({insert_fields}) VALUES ({insert_indexes}){on_conflict} RETURNING id"#, collection_name = collection.name).as_str(), arguments)
+            .fetch_one(&mut *self.connection(&collection.name).await?)
+            .await
+            .map_err(|e| {
+                error!("insert_row: {e}");
+                StorageError::DBErr {
+                    collection: collection.name.to_string(),
+                    err: e.to_string(),
+                }
+            })?;
+
+        let collection_id = rec.get::<String, _>("id");
+
+        info!(
+            "insert_row: {collection_name} with id: {collection_id}",
+            collection_name = collection.name
+        );
+
+        self.update_relations(collection, &[collection_id.to_string()], &data)
+            .await?;
+
+        self.get_data_from_collection(collection, collection_id, &Projection::default())
+            .await
+    }
+}
+
+#[async_trait]
+impl Storage for StoreSqlite {
+    async fn begin(&self) -> anyhow::Result<Self, StorageError> {
+        let transaction = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
+
+        Ok(Self {
+            pool: self.pool.clone(),
+            transaction: Some(Arc::new(Mutex::new(Some(transaction)))),
+        })
+    }
+
+    async fn commit(&self) -> anyhow::Result<(), StorageError> {
+        match self.finish_transaction().await {
+            Some(transaction) => transaction
+                .commit()
+                .await
+                .map_err(|e| StorageError::Transaction { err: e.to_string() }),
+            None => Ok(()),
+        }
+    }
+
+    async fn rollback(&self) -> anyhow::Result<(), StorageError> {
+        match self.finish_transaction().await {
+            Some(transaction) => transaction
+                .rollback()
+                .await
+                .map_err(|e| StorageError::Transaction { err: e.to_string() }),
+            None => Ok(()),
+        }
+    }
+
+    async fn get_collections(&self) -> anyhow::Result<Vec<Collection>, StorageError> {
+        let schemas: Vec<StoreCollectionQuery> =
+            sqlx::query_as(r#"SELECT * FROM storage_collection_schema"#)
+                .fetch_all(&mut *self.connection("storage_collection_schema").await?)
+                .await
+                .unwrap_or_default();
+
+        Ok(schemas.into_iter().map(|schema| schema.into()).collect())
+    }
+
+    async fn get_collection(
+        &self,
+        collection_name: String,
+    ) -> anyhow::Result<Collection, StorageError> {
+        sqlx::query_as(r#"SELECT * FROM storage_collection_schema WHERE name = ?1"#)
+            .bind(collection_name.to_string())
+            .fetch_optional(&mut *self.connection(&collection_name).await?)
+            .await
+            .unwrap_or_default()
+            .map(|schema: StoreCollectionQuery| schema.into())
+            .ok_or(StorageError::CollectionNotFound {
+                collection: collection_name,
+            })
+    }
+
+    async fn create_collection(
+        &self,
+        collection_name: String,
+        collection_fields: Vec<CollectionField>,
+    ) -> anyhow::Result<Collection, StorageError> {
+        let mut connection = self.connection(&collection_name).await?;
+        let mut transaction = connection
+            .begin()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
+
+        let q = format!(
+            r#"
+        CREATE TABLE IF NOT EXISTS "{collection_name}"
+            (
+                id          VARCHAR NOT NULL PRIMARY KEY,
+                created_at  TEXT    NOT NULL,
+                updated_at  TEXT    NOT NULL
+            );
+        "#
+        );
+
+        let create_table = sqlx::query(q.as_str()).execute(&mut *transaction).await;
+
+        if let Err(err) = create_table {
+            error!("create_table: {err:?}");
+            transaction.rollback().await.unwrap();
+            return Err(StorageError::CollectionCreateTable {
+                collection: collection_name.to_string(),
+            });
+        }
+
+        let mut fields = collection_fields.clone();
+
+        for field in &collection_fields {
+            match field.name.as_str() {
+                ID_FIELD | CREATED_AT_FIELD | UPDATED_AT_FIELD => {}
+                _ => {
+                    let query = Self::query_field_to_collection(collection_name.to_string(), field);
+                    let create_field = sqlx::query(query.as_str()).execute(&mut *transaction).await;
+
+                    if create_field.is_err() {
+                        transaction.rollback().await.unwrap();
+                        return Err(StorageError::CollectionAlterTable {
+                            collection: collection_name,
+                            field: field.name.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+
+        for (name, field_type) in [
+            (ID_FIELD, FieldType::String),
+            (CREATED_AT_FIELD, FieldType::TimeStamp),
+            (UPDATED_AT_FIELD, FieldType::TimeStamp),
+        ] {
+            if !fields.iter().any(|field| field.name == name) {
+                fields.push(CollectionField {
+                    name: name.to_string(),
+                    default: None,
+                    required: None,
+                    unique: None,
+                    on_delete: None,
+                    field_type,
+                });
+            }
+        }
+
+        let fields = json!(fields).to_string();
+
+        debug!("create collection: {collection_name} with fields: {fields}");
+
+        let insert_collection = sqlx::query(r#"INSERT INTO storage_collection_schema ( name, fields, created_at, updated_at ) VALUES ( ?1 , ?2, ?3, ?3 )"#)
+            .bind(collection_name.to_string())
+            .bind(fields)
+            .bind(Self::now())
+            .execute(&mut *transaction)
+            .await;
+
+        if let Err(err) = insert_collection {
+            error!("insert_collection: {err:?}");
+
+            transaction.rollback().await.unwrap();
+            return Err(StorageError::CollectionCreate {
+                collection: collection_name,
+            });
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
+        drop(connection);
+
+        self.get_collection(collection_name.to_string()).await
+    }
+
+    async fn remove_collection(&self, collection: &Collection) -> Result<(), StorageError> {
+        let mut connection = self.connection(&collection.name).await?;
+        let mut transaction = connection
+            .begin()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
+
+        let mut tables = collection
+            .fields
+            .iter()
+            .filter(|field| field.field_type.is_relation())
+            .map(|field| join_table(&collection.name, &field.name))
+            .collect::<Vec<_>>();
+        tables.push(collection.name.to_string());
+
+        // sqlite drops a single table per statement
+        for table in tables {
+            let drop_table = sqlx::query(&format!(r#"DROP TABLE IF EXISTS "{table}""#))
+                .execute(&mut *transaction)
+                .await;
+
+            if let Err(err) = drop_table {
+                error!("drop_table: {err:?}");
+                transaction.rollback().await.unwrap();
+                return Err(StorageError::CollectionCreateTable {
+                    collection: collection.name.to_string(),
+                });
+            }
+        }
+
+        let remove_collection =
+            sqlx::query(r#"DELETE FROM storage_collection_schema WHERE name = ?1"#)
+                .bind(collection.name.to_string())
+                .execute(&mut *transaction)
+                .await;
+
+        if let Err(err) = remove_collection {
+            error!("remove_collection: {err:?}");
+
+            transaction.rollback().await.unwrap();
+            return Err(StorageError::CollectionRemove {
+                collection: collection.name.to_string(),
+            });
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
+        drop(connection);
+
+        Ok(())
+    }
+
+    async fn set_collection_policy(
+        &self,
+        collection: &Collection,
+        policy: Option<SchemaPolicy>,
+    ) -> anyhow::Result<Collection, StorageError> {
+        sqlx::query(
+            r#"UPDATE storage_collection_schema SET policy = ?1, updated_at = ?2 WHERE name = ?3;"#,
+        )
+        .bind(policy.map(|policy| policy.as_str()))
+        .bind(Self::now())
+        .bind(collection.name.to_string())
+        .execute(&mut *self.connection(&collection.name).await?)
+        .await
+        .map_err(|e| {
+            error!("set_collection_policy: {e}");
+            StorageError::DBErr {
+                collection: collection.name.to_string(),
+                err: e.to_string(),
+            }
+        })?;
+
+        self.get_collection(collection.name.to_string()).await
+    }
+
+    async fn insert_field_to_collection(
+        &self,
+        collection: &Collection,
+        field: CollectionField,
+    ) -> anyhow::Result<Collection, StorageError> {
+        if collection.fields.iter().any(|f| f.name == field.name) {
+            return Err(StorageError::CollectionFieldExists {
+                collection: collection.name.to_string(),
+                field: field.name,
+            });
+        }
+
+        let mut connection = self.connection(&collection.name).await?;
+        let mut transaction = connection
+            .begin()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
+
+        let query = Self::query_field_to_collection(collection.name.to_string(), &field);
+        let create_field = sqlx::query(query.as_str()).execute(&mut *transaction).await;
+
+        if create_field.is_err() {
+            transaction.rollback().await.unwrap();
+            return Err(StorageError::CollectionAlterTable {
+                collection: collection.name.to_string(),
+                field: field.name.to_string(),
+            });
+        }
+
+        let mut fields = collection.fields.clone();
+
+        fields.push(field);
+
+        let update_collection = sqlx::query(
+            r#"UPDATE storage_collection_schema SET fields = ?1, updated_at = ?2 WHERE name = ?3;"#,
+        )
+        .bind(json!(fields).to_string())
+        .bind(Self::now())
+        .bind(collection.name.to_string())
+        .execute(&mut *transaction)
+        .await;
+
+        if update_collection.is_err() {
+            transaction.rollback().await.unwrap();
+            return Err(StorageError::CollectionCreate {
+                collection: collection.name.to_string(),
+            });
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
+        drop(connection);
+
+        self.get_collection(collection.name.to_string()).await
+    }
+
+    async fn remove_field_from_collection(
+        &self,
+        collection: &Collection,
+        field: CollectionField,
+    ) -> anyhow::Result<Collection, StorageError> {
+        let position = collection.fields.iter().position(|f| f.name == field.name);
+
+        if position.is_none() {
+            return Err(StorageError::CollectionFieldExists {
+                collection: collection.name.to_string(),
+                field: field.name,
+            });
+        }
+
+        let mut connection = self.connection(&collection.name).await?;
+        let mut transaction = connection
+            .begin()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
+
+        // sqlite refuses to drop indexed columns, unique fields are indexed
+        let query = format!(
+            r#"DROP INDEX IF EXISTS "{collection_name}_{column_name}_key";"#,
+            collection_name = collection.name,
+            column_name = field.name
+        );
+        let _ = sqlx::query(query.as_str()).execute(&mut *transaction).await;
+
+        let query = match field.field_type {
+            FieldType::Relation { .. } => format!(
+                r#"DROP TABLE IF EXISTS "{join_table}";"#,
+                join_table = join_table(&collection.name, &field.name)
+            ),
+            _ => format!(
+                r#"ALTER TABLE "{collection_name}" DROP COLUMN "{column_name}";"#,
+                collection_name = collection.name,
+                column_name = field.name
+            ),
+        };
+        let remove_field = sqlx::query(query.as_str()).execute(&mut *transaction).await;
+
+        if remove_field.is_err() {
+            transaction.rollback().await.unwrap();
+            return Err(StorageError::CollectionFieldRemove {
+                collection: collection.name.to_string(),
+                field: field.name.to_string(),
+            });
+        }
+
+        let mut fields = collection.fields.clone();
+        fields.remove(position.unwrap());
+
+        let fields = json!(fields).to_string();
+
+        debug!(
+            "remove_field_from_create_collection: {collection_name} with fields: {fields}",
+            collection_name = collection.name
+        );
+
+        let update_collection = sqlx::query(
+            r#"UPDATE storage_collection_schema SET fields = ?1, updated_at = ?2 WHERE name = ?3;"#,
+        )
+        .bind(fields)
+        .bind(Self::now())
+        .bind(collection.name.to_string())
+        .execute(&mut *transaction)
+        .await;
+
+        if update_collection.is_err() {
+            transaction.rollback().await.unwrap();
+            return Err(StorageError::CollectionCreate {
+                collection: collection.name.to_string(),
+            });
+        }
+
+        transaction
+            .commit()
+            .await
+            .map_err(|e| StorageError::Transaction { err: e.to_string() })?;
+        drop(connection);
+
+        self.get_collection(collection.name.to_string()).await
+    }
+
+    async fn insert_data_into_collection(
+        &self,
+        collection: &Collection,
+        data: Value,
+    ) -> anyhow::Result<Value, StorageError> {
+        self.insert_row(collection, data, None).await
+    }
+
+    async fn upsert_data_into_collection(
+        &self,
+        collection: &Collection,
+        data: Value,
+        key: &str,
+        update: &Value,
+    ) -> anyhow::Result<Value, StorageError> {
+        self.insert_row(collection, data, Some((key, update))).await
+    }
+
+    async fn update_data_into_collection(
+        &self,
+        collection: &Collection,
+        collection_id: String,
+        data: Value,
+        precondition: &Precondition,
+    ) -> anyhow::Result<Value, StorageError> {
+        let mut arguments = SqliteArguments::default();
+
+        let mut update_fields = vec![];
+        let mut counter: usize = 1;
+        let mut relations = false;
+        for field in &collection.fields {
+            match field.name.as_str() {
+                CREATED_AT_FIELD | UPDATED_AT_FIELD => {
+                    continue;
+                }
+                _ if field.field_type.is_relation() => {
+                    relations |= data.get(field.name.as_str()).is_some();
+                }
+                _ => {
+                    if let Some(v) = data.get(field.name.as_str()).cloned() {
+                        if field.name == ID_FIELD && v.is_null() {
+                            continue;
+                        }
+
+                        update_fields.push(update_expression(
+                            field,
+                            &v,
+                            &mut arguments,
+                            &mut counter,
+                        ));
+                    }
+                }
+            }
+        }
+
+        if !update_fields.is_empty() || !precondition.is_empty() || relations {
+            update_fields.push(format!("{UPDATED_AT_FIELD} = ?{counter}"));
+            arguments.add(Self::now());
+            counter += 1;
+
+            arguments.add(collection_id.to_string());
+            let id_counter = counter;
+            counter += 1;
+            let update_fields = update_fields.join(", ");
+            let precondition_query =
+                precondition_query(collection, precondition, &mut arguments, &mut counter);
+
+            let rec = sqlx::query_with(
+                format!(
+                    r#"UPDATE "{collection_name}" SET {update_fields} WHERE id = ?{id_counter} AND {precondition_query}"#,
+                    collection_name = collection.name
+                )
+                .as_str(),
+                arguments,
+            )
+            .execute(&mut *self.connection(&collection.name).await?)
+            .await
+            .map_err(|e| {
+                error!("update_data_into_collection: {e}");
+                StorageError::DBErr {
+                    collection: collection.name.to_string(),
+                    err: e.to_string(),
+                }
+            })?;
+
+            if rec.rows_affected() == 0 && !precondition.is_empty() {
+                return Err(StorageError::Conflict {
+                    collection: collection.name.to_string(),
+                    id: collection_id,
+                });
+            }
+
+            if rec.rows_affected() > 0 {
+                self.update_relations(collection, &[collection_id.to_string()], &data)
+                    .await?;
+            }
+
+            debug!(
+                "update_into_collection: {collection_name} with id: {collection_id}",
+                collection_name = collection.name
+            );
+        }
+
+        self.get_data_from_collection(collection, collection_id, &Projection::default())
+            .await
+    }
+
+    async fn delete_data_from_collection(
+        &self,
+        collection: &Collection,
+        collection_id: String,
+        precondition: &Precondition,
+    ) -> anyhow::Result<Value, StorageError> {
+        let mut arguments = SqliteArguments::default();
+        arguments.add(collection_id.to_string());
+
+        let mut counter = 2;
+        let precondition_query =
+            precondition_query(collection, precondition, &mut arguments, &mut counter);
+
+        let condition = format!("id = ?1 AND {precondition_query}");
+
+        let Some(deleted) = self.delete_rows(collection, &condition, arguments).await? else {
+            return Err(StorageError::Referenced {
+                collection: collection.name.to_string(),
+                id: collection_id,
+            });
+        };
+
+        if deleted == 0 && !precondition.is_empty() {
+            return Err(StorageError::Conflict {
+                collection: collection.name.to_string(),
+                id: collection_id,
+            });
+        }
+
+        Ok(Value::Null)
+    }
+
+    async fn bulk_update_data_into_collection(
+        &self,
+        collection: &Collection,
+        query: WhereQuery,
+        data: Value,
+    ) -> anyhow::Result<u64, StorageError> {
+        let mut arguments = SqliteArguments::default();
+        let mut counter: usize = 1;
+
+        let mut update_fields = collection
+            .fields
+            .iter()
+            .filter(|field| {
+                !matches!(
+                    field.name.as_str(),
+                    ID_FIELD | CREATED_AT_FIELD | UPDATED_AT_FIELD
+                ) && !field.field_type.is_relation()
+            })
+            .filter_map(|field| {
+                data.get(field.name.as_str())
+                    .map(|v| update_expression(field, v, &mut arguments, &mut counter))
+            })
+            .collect::<Vec<_>>();
+        update_fields.push(format!("{UPDATED_AT_FIELD} = ?{counter}"));
+        arguments.add(Self::now());
+        counter += 1;
+
+        let update_fields = update_fields.join(", ");
+        let where_query = where_query(collection, query, &mut arguments, &mut counter);
+
+        let ids = sqlx::query_with(
+            format!(
+                r#"UPDATE "{collection_name}" SET {update_fields} WHERE {where_query} RETURNING id"#,
+                collection_name = collection.name
+            )
+            .as_str(),
+            arguments,
+        )
+        .fetch_all(&mut *self.connection(&collection.name).await?)
+        .await
+        .map_err(|e| {
+            error!("bulk_update_data_into_collection: {e}");
+            StorageError::DBErr {
+                collection: collection.name.to_string(),
+                err: e.to_string(),
+            }
+        })?
+        .into_iter()
+        .map(|row| row.get::<String, _>("id"))
+        .collect::<Vec<_>>();
+
+        self.update_relations(collection, &ids, &data).await?;
+
+        debug!(
+            "bulk_update_data_into_collection: {collection_name} updated {count}",
+            collection_name = collection.name,
+            count = ids.len()
+        );
+
+        Ok(ids.len() as u64)
+    }
+
+    async fn bulk_delete_data_from_collection(
+        &self,
+        collection: &Collection,
+        query: WhereQuery,
+    ) -> anyhow::Result<u64, StorageError> {
+        let mut arguments = SqliteArguments::default();
+        let mut counter: usize = 1;
+        let where_query = where_query(collection, query.clone(), &mut arguments, &mut counter);
+
+        match self
+            .delete_rows(collection, &where_query, arguments)
+            .await?
+        {
+            Some(deleted) => Ok(deleted),
+            None => Err(StorageError::Referenced {
+                collection: collection.name.to_string(),
+                id: self.referenced_id(collection, query).await?,
+            }),
+        }
+    }
+
+    async fn get_data_from_collection(
+        &self,
+        collection: &Collection,
+        collection_id: String,
+        projection: &Projection,
+    ) -> anyhow::Result<Value, StorageError> {
+        let collection = &projection.apply(collection);
+
+        let query = format!(
+            r#"SELECT {select_fields} FROM "{collection_name}" WHERE id = ?1"#,
+            select_fields = Self::select_fields(collection),
+            collection_name = collection.name
+        );
+
+        sqlx::query(query.as_str())
+            .bind(collection_id.to_string())
+            .fetch_optional(&mut *self.connection(&collection.name).await?)
+            .await
+            .unwrap_or_default()
+            .map(|row| serialize_sqlite_row(collection, row))
+            .ok_or(StorageError::ValueNotFound {
+                collection: collection.name.to_string(),
+                id: collection_id,
+            })
+    }
+
+    async fn list_data_from_collection(
+        &self,
+        collection: &Collection,
+        query: WhereQuery,
+        options: &QueryOptions,
+    ) -> anyhow::Result<Vec<Value>, StorageError> {
+        let mut arguments = SqliteArguments::default();
+        let mut counter = 1;
+        let where_query = where_query(collection, query, &mut arguments, &mut counter);
+
+        let projection = options.projection.apply(collection);
+
+        let mut query = format!(
+            r#"SELECT {select_fields} FROM "{collection_name}" WHERE {where_query}"#,
+            select_fields = Self::select_fields(&projection),
+            collection_name = collection.name,
+            where_query = where_query
+        );
+
+        let order_query = options
+            .order
+            .iter()
+            .filter_map(|order| {
+                let column =
+                    order_expression(collection, &order.field, &mut arguments, &mut counter)?;
+
+                Some(match order.direction {
+                    OrderDirection::Asc => format!("{column} ASC NULLS LAST"),
+                    OrderDirection::Desc => format!("{column} DESC NULLS FIRST"),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if !order_query.is_empty() {
+            query.push_str(format!(" ORDER BY {}", order_query.join(", ")).as_str());
+        }
+
+        if let Some(limit) = options.limit {
+            query.push_str(format!(" LIMIT ?{counter}").as_str());
+            arguments.add(limit as i64);
+            counter += 1;
+        }
+
+        if options.limit.is_none() && options.skip.is_some() {
+            query.push_str(" LIMIT -1");
+        }
+
+        if let Some(skip) = options.skip {
+            query.push_str(format!(" OFFSET ?{counter}").as_str());
+            arguments.add(skip as i64);
+        }
+
+        let values = sqlx::query_with(query.as_str(), arguments)
+            .fetch_all(&mut *self.connection(&collection.name).await?)
+            .await
+            .map_err(|e| {
+                error!("list_data_from_collection: {e}");
+                StorageError::DBErr {
+                    collection: collection.name.to_string(),
+                    err: e.to_string(),
+                }
+            })?;
+
+        let mut result = vec![];
+        for value in values {
+            result.push(serialize_sqlite_row(&projection, value));
+        }
+
+        Ok(result)
+    }
+
+    async fn count_data_from_collection(
+        &self,
+        collection: &Collection,
+        query: WhereQuery,
+    ) -> anyhow::Result<i64, StorageError> {
+        let mut arguments = SqliteArguments::default();
+        let mut counter = 1;
+        let where_query = where_query(collection, query, &mut arguments, &mut counter);
+
+        let query = format!(
+            r#"SELECT COUNT(*) AS count FROM "{collection_name}" WHERE {where_query}"#,
+            collection_name = collection.name,
+            where_query = where_query
+        );
+
+        let rec = sqlx::query_with(query.as_str(), arguments)
+            .fetch_one(&mut *self.connection(&collection.name).await?)
+            .await
+            .map_err(|e| {
+                error!("count_data_from_collection: {e}");
+                StorageError::DBErr {
+                    collection: collection.name.to_string(),
+                    err: e.to_string(),
+                }
+            })?;
+
+        Ok(rec.get::<i64, _>("count"))
+    }
+}
@@ -0,0 +1,9 @@
+pub use crate::collection_sqlite::StoreSqlite;
+
+mod add_value_into_args;
+mod collection_sqlite;
+mod serialize_sqlite_row;
+mod store_connection;
+mod store_schema_query;
+mod update_expression;
+mod where_query;
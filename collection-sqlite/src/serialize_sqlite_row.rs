@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+use serde_json::{json, Map, Value};
+use sqlx::{sqlite::SqliteRow, Row};
+
+use vivalaakam_seattle_collection::{Collection, Decimal, FieldType, Pointer, TimeStamp};
+
+pub fn serialize_sqlite_row(collection: &Collection, row: SqliteRow) -> Value {
+    let mut map = Map::new();
+
+    for field in &collection.fields {
+        let v = match &field.field_type {
+            FieldType::String => match row.get::<Option<String>, _>(field.name.as_str()) {
+                Some(v) => Value::String(v),
+                None => Value::Null,
+            },
+            FieldType::Number => match row.get::<Option<f64>, _>(field.name.as_str()) {
+                Some(v) => json!(v),
+                None => Value::Null,
+            },
+            FieldType::Integer | FieldType::BigInt => {
+                match row.get::<Option<i64>, _>(field.name.as_str()) {
+                    Some(v) => json!(v),
+                    None => Value::Null,
+                }
+            }
+            // increments store the sum as a plain number
+            FieldType::Decimal { scale, .. } => {
+                match row.get::<Option<String>, _>(field.name.as_str()) {
+                    Some(v) => Decimal::parse(&v)
+                        .map(|v| v.round(*scale).to_value())
+                        .unwrap_or(Value::Null),
+                    None => Value::Null,
+                }
+            }
+            FieldType::Boolean => match row.get::<Option<bool>, _>(field.name.as_str()) {
+                Some(v) => Value::Bool(v),
+                None => Value::Null,
+            },
+            FieldType::Array | FieldType::Object => {
+                match row.get::<Option<String>, _>(field.name.as_str()) {
+                    Some(v) => serde_json::from_str(v.as_str()).unwrap_or(Value::Null),
+                    None => Value::Null,
+                }
+            }
+            FieldType::TimeStamp => {
+                match row.get::<Option<DateTime<Utc>>, _>(field.name.as_str()) {
+                    Some(v) => TimeStamp(v).to_value(),
+                    None => Value::Null,
+                }
+            }
+            FieldType::Pointer { target } => {
+                match row.get::<Option<String>, _>(field.name.as_str()) {
+                    Some(v) => Pointer::new(target, &v).to_value(),
+                    None => Value::Null,
+                }
+            }
+            FieldType::Relation { target } => Pointer::relation_value(target),
+        };
+
+        map.insert(field.name.to_string(), v);
+    }
+
+    Value::Object(map)
+}
@@ -0,0 +1,97 @@
+use serde_json::Value;
+use sqlx::sqlite::SqliteArguments;
+use sqlx::Arguments;
+
+use vivalaakam_seattle_collection::{CollectionField, Decimal, FieldOperation, FieldType};
+
+use crate::add_value_into_args::add_value_into_args;
+use crate::where_query::JSON_ELEMENT;
+
+// operations are evaluated against the stored value inside the UPDATE statement
+pub fn update_expression(
+    field: &CollectionField,
+    value: &Value,
+    arguments: &mut SqliteArguments,
+    counter: &mut usize,
+) -> String {
+    let column = format!(r#""{}""#, field.name);
+    let array = format!("COALESCE({column}, '[]')");
+
+    let expression = match FieldOperation::from_value(value) {
+        Some(Ok(FieldOperation::Increment { amount })) => {
+            let amount = Value::Number(amount);
+
+            match field.field_type {
+                FieldType::Integer | FieldType::BigInt => {
+                    arguments.add(FieldType::integer_value(&amount));
+                    format!("COALESCE({column}, 0) + ?{counter}")
+                }
+                FieldType::Decimal { scale, .. } => {
+                    decimal_increment(&column, scale, &amount, arguments, *counter)
+                }
+                _ => {
+                    arguments.add(amount.as_f64());
+                    format!("COALESCE({column}, 0) + ?{counter}")
+                }
+            }
+        }
+        Some(Ok(FieldOperation::Add { objects })) => {
+            arguments.add(Value::Array(objects).to_string());
+            format!(
+                "(SELECT json_group_array(json(element)) FROM (SELECT {JSON_ELEMENT} AS element, 0 AS source, key AS position FROM json_each({array}) UNION ALL SELECT {JSON_ELEMENT}, 1, key FROM json_each(?{counter}) ORDER BY source, position))"
+            )
+        }
+        Some(Ok(FieldOperation::AddUnique { objects })) => {
+            arguments.add(Value::Array(FieldOperation::unique_objects(&objects)).to_string());
+            format!(
+                "(SELECT json_group_array(json(element)) FROM (SELECT {JSON_ELEMENT} AS element, 0 AS source, key AS position FROM json_each({array}) UNION ALL SELECT {JSON_ELEMENT}, 1, key FROM json_each(?{counter}) WHERE {JSON_ELEMENT} NOT IN (SELECT {JSON_ELEMENT} FROM json_each({array})) ORDER BY source, position))"
+            )
+        }
+        Some(Ok(FieldOperation::Remove { objects })) => {
+            arguments.add(Value::Array(objects).to_string());
+            format!(
+                "(SELECT json_group_array(json(element)) FROM (SELECT {JSON_ELEMENT} AS element FROM json_each({array}) WHERE {JSON_ELEMENT} NOT IN (SELECT {JSON_ELEMENT} FROM json_each(?{counter})) ORDER BY key))"
+            )
+        }
+        Some(Ok(FieldOperation::Delete)) => return format!("{column} = NULL"),
+        _ => {
+            add_value_into_args(field, value, arguments);
+            format!("?{counter}")
+        }
+    };
+
+    *counter += 1;
+
+    format!("{column} = {expression}")
+}
+
+// decimals are stored as text with exactly `scale` digits after the point, so dropping the point
+// gives an integer count of the smallest unit that sqlite can add without rounding
+fn decimal_increment(
+    column: &str,
+    scale: u32,
+    amount: &Value,
+    arguments: &mut SqliteArguments,
+    counter: usize,
+) -> String {
+    let factor = 10i64.checked_pow(scale);
+    let units = Decimal::from_value(amount)
+        .and_then(|amount| i64::try_from(amount.round(scale).0.mantissa()).ok());
+
+    let (Some(factor), Some(units)) = (factor, units) else {
+        // past 18 digits only a float sum is left
+        arguments.add(amount.as_f64());
+        return format!("printf('%.{scale}f', COALESCE({column}, 0) + ?{counter})");
+    };
+
+    arguments.add(units);
+
+    let sum = format!("(CAST(REPLACE(COALESCE({column}, '0'), '.', '') AS INTEGER) + ?{counter})");
+
+    match scale {
+        0 => format!("CAST({sum} AS TEXT)"),
+        _ => format!(
+            "CASE WHEN {sum} < 0 THEN '-' ELSE '' END || (abs({sum}) / {factor}) || '.' || printf('%0{scale}d', abs({sum}) % {factor})"
+        ),
+    }
+}
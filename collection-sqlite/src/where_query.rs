@@ -0,0 +1,425 @@
+use std::cmp::Ordering;
+
+use sqlx::sqlite::SqliteArguments;
+use sqlx::Arguments;
+
+use vivalaakam_seattle_collection::{
+    Collection, CollectionField, Decimal, FieldType, Precondition, Where, WhereQuery,
+};
+
+use crate::add_value_into_args::{add_value_into_args, format_timestamp};
+
+pub const DECIMAL_COLLATION: &str = "decimal";
+
+// exact numeric order, text that isn't a decimal sorts after every number
+pub fn compare_decimals(a: &str, b: &str) -> Ordering {
+    match (Decimal::parse(a), Decimal::parse(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(b),
+    }
+}
+
+// relations of `collection.field` as ("owningId", "relatedId") pairs
+pub fn join_table(collection_name: &str, field_name: &str) -> String {
+    format!("_Join:{field_name}:{collection_name}")
+}
+
+fn relation_query(
+    collection: &Collection,
+    field: &CollectionField,
+    value: Where,
+    arguments: &mut SqliteArguments,
+    counter: &mut usize,
+) -> Vec<String> {
+    let owning_ids = |condition: String| {
+        format!(
+            r#""id" IN (SELECT "owningId" FROM "{join_table}" WHERE {condition})"#,
+            join_table = join_table(&collection.name, &field.name)
+        )
+    };
+
+    let mut conditions = vec![];
+
+    if let Some(eq) = value.eq {
+        conditions.push(owning_ids(format!(r#""relatedId" = ?{counter}"#)));
+        add_value_into_args(field, &eq, arguments);
+        *counter += 1;
+    }
+
+    if let Some(in_) = value.in_ {
+        let in_query = (0..in_.len())
+            .map(|i| format!("?{}", *counter + i))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        conditions.push(owning_ids(format!(r#""relatedId" IN ({in_query})"#)));
+        *counter += in_.len();
+
+        for v in in_ {
+            add_value_into_args(field, &v, arguments);
+        }
+    }
+
+    conditions
+}
+
+// LIKE ignores case for ascii in sqlite, GLOB does not
+fn escape_glob(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '*' | '?' | '[' => format!("[{c}]"),
+            _ => c.to_string(),
+        })
+        .collect()
+}
+
+fn glob_condition(column: &str, counter: usize, case_insensitive: bool) -> String {
+    if case_insensitive {
+        format!(r#"lower({column}) GLOB lower(?{counter})"#)
+    } else {
+        format!(r#"{column} GLOB ?{counter}"#)
+    }
+}
+
+// json_each row rendered as json text, to compare with serialized query values
+pub const JSON_ELEMENT: &str = "CASE type WHEN 'true' THEN 'true' WHEN 'false' THEN 'false' WHEN 'null' THEN 'null' WHEN 'object' THEN value WHEN 'array' THEN value ELSE json_quote(value) END";
+
+// json_each over NULL yields no rows, keep NULL like postgres does
+fn null_if_missing(column: &str, condition: String) -> String {
+    let condition = if condition.is_empty() {
+        "TRUE".to_string()
+    } else {
+        condition
+    };
+
+    format!(r#"CASE WHEN {column} IS NULL THEN NULL ELSE ({condition}) END"#)
+}
+
+fn json_path(path: Vec<String>) -> String {
+    let path = path
+        .iter()
+        .map(|part| format!(r#"."{part}""#))
+        .collect::<String>();
+
+    format!("${path}")
+}
+
+// decimals are stored as text and compared by `compare_decimals`
+fn decimal_column(field: &CollectionField) -> String {
+    match field.field_type {
+        FieldType::Decimal { .. } => {
+            format!(r#""{}" COLLATE {DECIMAL_COLLATION}"#, field.name)
+        }
+        _ => format!(r#""{}""#, field.name),
+    }
+}
+
+pub fn order_expression(
+    collection: &Collection,
+    key: &str,
+    arguments: &mut SqliteArguments,
+    counter: &mut usize,
+) -> Option<String> {
+    if let Some(field) = collection.get_field(&key.to_string()) {
+        return Some(decimal_column(field));
+    }
+
+    let (field, path) = collection.get_nested_field(key)?;
+    let column = format!(r#"json_extract("{}", ?{counter})"#, field.name);
+    arguments.add(json_path(path));
+    *counter += 1;
+
+    Some(column)
+}
+
+fn column_expression(
+    collection: &Collection,
+    key: &str,
+    value_type: Option<FieldType>,
+    arguments: &mut SqliteArguments,
+    counter: &mut usize,
+) -> Option<(String, CollectionField)> {
+    if let Some(field) = collection.get_field(&key.to_string()) {
+        return Some((decimal_column(field), field.clone()));
+    }
+
+    let (field, path) = collection.get_nested_field(key)?;
+    let json_type = format!(r#"json_type("{}", ?{counter})"#, field.name);
+    let value = format!(r#"json_extract("{}", ?{counter})"#, field.name);
+    arguments.add(json_path(path));
+    *counter += 1;
+
+    let column = match value_type {
+        None => format!("({value})"),
+        Some(FieldType::String | FieldType::TimeStamp) => {
+            format!("(CASE WHEN {json_type} = 'text' THEN {value} END)")
+        }
+        Some(FieldType::Number | FieldType::Integer | FieldType::BigInt) => {
+            format!("(CASE WHEN {json_type} IN ('integer', 'real') THEN {value} END)")
+        }
+        Some(FieldType::Boolean) => {
+            format!("(CASE WHEN {json_type} IN ('true', 'false') THEN {value} END)")
+        }
+        Some(FieldType::Array) => format!("(CASE WHEN {json_type} = 'array' THEN {value} END)"),
+        Some(
+            FieldType::Object
+            | FieldType::Pointer { .. }
+            | FieldType::Relation { .. }
+            | FieldType::Decimal { .. },
+        ) => {
+            format!("(CASE WHEN {json_type} = 'object' THEN {value} END)")
+        }
+    };
+
+    let field = CollectionField {
+        name: key.to_string(),
+        field_type: value_type.unwrap_or(FieldType::String),
+        default: None,
+        required: None,
+        unique: None,
+        on_delete: None,
+    };
+
+    Some((column, field))
+}
+
+pub fn where_query(
+    collection: &Collection,
+    query: WhereQuery,
+    arguments: &mut SqliteArguments,
+    counter: &mut usize,
+) -> String {
+    let mut conditions = vec![];
+    for (key, value) in query.fields {
+        let value_type = value.value_type();
+        if let Some((column, field)) =
+            column_expression(collection, &key, value_type, arguments, counter)
+        {
+            if field.field_type.is_relation() {
+                conditions.extend(relation_query(
+                    collection, &field, value, arguments, counter,
+                ));
+                continue;
+            }
+
+            let case_insensitive = value.case_insensitive();
+
+            if let Some(eq) = value.eq {
+                conditions.push(format!(r#"{column} = ?{counter}"#));
+                add_value_into_args(&field, &eq, arguments);
+                *counter += 1;
+            }
+
+            if let Some(ne) = value.ne {
+                conditions.push(format!(r#"{column} != ?{counter}"#));
+                add_value_into_args(&field, &ne, arguments);
+                *counter += 1;
+            }
+
+            if let Some(gt) = value.gt {
+                conditions.push(format!(r#"{column} > ?{counter}"#));
+                add_value_into_args(&field, &gt, arguments);
+                *counter += 1;
+            }
+
+            if let Some(gte) = value.gte {
+                conditions.push(format!(r#"{column} >= ?{counter}"#));
+                add_value_into_args(&field, &gte, arguments);
+                *counter += 1;
+            }
+
+            if let Some(lt) = value.lt {
+                conditions.push(format!(r#"{column} < ?{counter}"#));
+                add_value_into_args(&field, &lt, arguments);
+                *counter += 1;
+            }
+
+            if let Some(lte) = value.lte {
+                conditions.push(format!(r#"{column} <= ?{counter}"#));
+                add_value_into_args(&field, &lte, arguments);
+                *counter += 1;
+            }
+
+            if let Some(in_) = value.in_ {
+                let in_query = (0..in_.len())
+                    .map(|i| format!("?{}", *counter + i))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                conditions.push(format!(r#"{column} IN ({in_query})"#));
+                *counter += in_.len();
+
+                for v in in_ {
+                    add_value_into_args(&field, &v, arguments);
+                }
+            }
+
+            if let Some(nin) = value.nin {
+                let in_query = (0..nin.len())
+                    .map(|i| format!("?{}", *counter + i))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                conditions.push(format!(r#"NOT({column} IN ({in_query}))"#));
+                *counter += nin.len();
+                for v in nin {
+                    add_value_into_args(&field, &v, arguments);
+                }
+            }
+
+            if let Some(exists) = value.exists {
+                let operator = if exists { "IS NOT NULL" } else { "IS NULL" };
+                conditions.push(format!(r#"{column} {operator}"#));
+            }
+
+            if let Some(regex) = value.regex {
+                conditions.push(format!(r#"{column} REGEXP ?{counter}"#));
+                if case_insensitive {
+                    arguments.add(format!("(?i){regex}"));
+                } else {
+                    arguments.add(regex);
+                }
+                *counter += 1;
+            }
+
+            if let Some(starts_with) = value.starts_with {
+                conditions.push(glob_condition(&column, *counter, case_insensitive));
+                arguments.add(format!("{}*", escape_glob(&starts_with)));
+                *counter += 1;
+            }
+
+            if let Some(ends_with) = value.ends_with {
+                conditions.push(glob_condition(&column, *counter, case_insensitive));
+                arguments.add(format!("*{}", escape_glob(&ends_with)));
+                *counter += 1;
+            }
+
+            if let Some(text) = value.text {
+                conditions.push(glob_condition(&column, *counter, case_insensitive));
+                arguments.add(format!("*{}*", escape_glob(&text)));
+                *counter += 1;
+            }
+
+            if let Some(all) = value.all {
+                let elements = all
+                    .iter()
+                    .map(|element| {
+                        let condition = format!(
+                            r#"EXISTS (SELECT 1 FROM json_each({column}) WHERE {JSON_ELEMENT} = ?{counter})"#
+                        );
+                        arguments.add(element.to_string());
+                        *counter += 1;
+                        condition
+                    })
+                    .collect::<Vec<_>>();
+
+                conditions.push(null_if_missing(&column, elements.join(" AND ")));
+            }
+
+            if let Some(contained_by) = value.contained_by {
+                let in_query = (0..contained_by.len())
+                    .map(|i| format!("?{}", *counter + i))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                conditions.push(null_if_missing(
+                    &column,
+                    format!(
+                        r#"NOT EXISTS (SELECT 1 FROM json_each({column}) WHERE {JSON_ELEMENT} NOT IN ({in_query}))"#
+                    ),
+                ));
+                *counter += contained_by.len();
+
+                for element in contained_by {
+                    arguments.add(element.to_string());
+                }
+            }
+
+            if let Some(contains) = value.contains {
+                conditions.push(null_if_missing(
+                    &column,
+                    format!(
+                        r#"EXISTS (SELECT 1 FROM json_each({column}) WHERE {JSON_ELEMENT} = ?{counter})"#
+                    ),
+                ));
+                arguments.add(contains.to_string());
+                *counter += 1;
+            }
+
+            if let Some(size) = value.size {
+                conditions.push(format!(r#"json_array_length({column}) = ?{counter}"#));
+                arguments.add(size as i64);
+                *counter += 1;
+            }
+        }
+    }
+
+    for and in query.and {
+        let and_query = where_query(collection, and, arguments, counter);
+        conditions.push(format!("({and_query})"));
+    }
+
+    if !query.or.is_empty() {
+        let or_query = query
+            .or
+            .into_iter()
+            .map(|or| format!("({})", where_query(collection, or, arguments, counter)))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        conditions.push(format!("({or_query})"));
+    }
+
+    if !query.nor.is_empty() {
+        let nor_query = query
+            .nor
+            .into_iter()
+            .map(|nor| format!("({})", where_query(collection, nor, arguments, counter)))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        conditions.push(format!("NOT ({nor_query})"));
+    }
+
+    if let Some(related_to) = query.related_to {
+        conditions.push(format!(
+            r#""id" IN (SELECT "relatedId" FROM "{join_table}" WHERE "owningId" = ?{counter})"#,
+            join_table = join_table(&related_to.object.class_name, &related_to.key)
+        ));
+        arguments.add(related_to.object.object_id);
+        *counter += 1;
+    }
+
+    if conditions.is_empty() {
+        "TRUE".to_string()
+    } else {
+        conditions.join(" AND ")
+    }
+}
+
+pub fn precondition_query(
+    collection: &Collection,
+    precondition: &Precondition,
+    arguments: &mut SqliteArguments,
+    counter: &mut usize,
+) -> String {
+    let mut conditions = vec![];
+
+    if let Some(updated_at) = precondition.updated_at {
+        conditions.push(format!(r#""updated_at" = ?{counter}"#));
+        arguments.add(format_timestamp(updated_at));
+        *counter += 1;
+    }
+
+    if !precondition.query.is_empty() {
+        let query = where_query(collection, precondition.query.clone(), arguments, counter);
+        conditions.push(format!("({query})"));
+    }
+
+    if conditions.is_empty() {
+        "TRUE".to_string()
+    } else {
+        conditions.join(" AND ")
+    }
+}
@@ -0,0 +1,8 @@
+use vivalaakam_seattle_collection_sqlite::StoreSqlite;
+use vivalaakam_seattle_collection_tests::storage_tests;
+
+async fn storage(_tables: &[&str]) -> StoreSqlite {
+    StoreSqlite::new("sqlite::memory:").await
+}
+
+storage_tests!(storage);
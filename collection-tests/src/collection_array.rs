@@ -0,0 +1,98 @@
+use serde_json::{json, Value};
+
+use vivalaakam_seattle_collection::{Collections, QueryOptions, Storage};
+
+async fn list_names<T: Storage>(
+    collections: &Collections<T>,
+    table_name: &str,
+    query: Value,
+) -> Vec<String> {
+    let mut names = collections
+        .list(table_name.to_string(), query, QueryOptions::default())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| row.get("name").unwrap().as_str().unwrap().to_string())
+        .collect::<Vec<String>>();
+
+    names.sort();
+    names
+}
+
+pub const TABLES: &[&str] = &["CollectionArray"];
+
+pub async fn collection_array<T: Storage>(storage: T) {
+    let table_name = "CollectionArray";
+
+    let collections = Collections::new(storage).await;
+
+    for (name, tags) in [
+        ("ab", json!(["a", "b"])),
+        ("bc", json!(["b", "c"])),
+        ("abc", json!(["a", "b", "c"])),
+        ("empty", json!([])),
+        ("missing", Value::Null),
+    ] {
+        let result = collections
+            .insert(
+                table_name.to_string(),
+                json!({ "name": name, "tags": tags }),
+            )
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    // test eq
+
+    let names = list_names(&collections, table_name, json!({ "tags": ["b", "c"] })).await;
+    assert_eq!(names, vec!["bc"]);
+
+    // test contains
+
+    let names = list_names(
+        &collections,
+        table_name,
+        json!({ "tags": { "$contains": "a" } }),
+    )
+    .await;
+    assert_eq!(names, vec!["ab", "abc"]);
+
+    // test all
+
+    let names = list_names(
+        &collections,
+        table_name,
+        json!({ "tags": { "$all": ["a", "c"] } }),
+    )
+    .await;
+    assert_eq!(names, vec!["abc"]);
+
+    // test contained by
+
+    let names = list_names(
+        &collections,
+        table_name,
+        json!({ "tags": { "$containedBy": ["a", "b"] } }),
+    )
+    .await;
+    assert_eq!(names, vec!["ab", "empty"]);
+
+    // test size
+
+    let names = list_names(&collections, table_name, json!({ "tags": { "$size": 3 } })).await;
+    assert_eq!(names, vec!["abc"]);
+
+    let names = list_names(&collections, table_name, json!({ "tags": { "$size": 0 } })).await;
+    assert_eq!(names, vec!["empty"]);
+
+    // test nor, rows without array never match
+
+    let names = list_names(
+        &collections,
+        table_name,
+        json!({ "$nor": [{ "tags": { "$contains": "a" } }] }),
+    )
+    .await;
+    assert_eq!(names, vec!["bc", "empty"]);
+}
@@ -0,0 +1,91 @@
+use serde_json::json;
+
+use vivalaakam_seattle_collection::{CollectionError, Collections, Storage};
+
+pub const TABLES: &[&str] = &["CollectionBulk"];
+
+pub async fn collection_bulk<T: Storage>(storage: T) {
+    let table_name = "CollectionBulk";
+
+    let collections = Collections::new(storage).await;
+
+    for (name, age) in [("a", 10), ("b", 20), ("c", 30), ("d", 40)] {
+        collections
+            .insert(table_name.to_string(), json!({ "name": name, "age": age }))
+            .await
+            .unwrap();
+    }
+
+    let count = collections
+        .bulk_update(
+            table_name.to_string(),
+            json!({ "age": { "$gte": 20 } }),
+            json!({ "status": "old", "age": { "__op": "Increment", "amount": 1 } }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(count, 3);
+
+    let count = collections
+        .count(table_name.to_string(), json!({ "status": "old" }))
+        .await
+        .unwrap();
+
+    assert_eq!(count, 3);
+
+    let count = collections
+        .count(table_name.to_string(), json!({ "age": 41 }))
+        .await
+        .unwrap();
+
+    assert_eq!(count, 1);
+
+    let count = collections
+        .bulk_delete(
+            table_name.to_string(),
+            json!({ "$or": [{ "name": "a" }, { "age": { "$gt": 40 } }] }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(count, 2);
+
+    let count = collections
+        .count(table_name.to_string(), json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(count, 2);
+
+    let count = collections
+        .bulk_delete(table_name.to_string(), json!({ "name": "missing" }))
+        .await
+        .unwrap();
+
+    assert_eq!(count, 0);
+
+    // misspelled keys and empty filters would match every row
+    for query in [
+        json!({ "nmae": "b" }),
+        json!({ "$or": [{ "name": "b" }, { "stauts": "old" }] }),
+        json!({}),
+    ] {
+        let result = collections
+            .bulk_update(table_name.to_string(), query.clone(), json!({ "age": 0 }))
+            .await;
+
+        assert!(matches!(result, Err(CollectionError::InvalidQuery { .. })));
+
+        let result = collections.bulk_delete(table_name.to_string(), query).await;
+
+        assert!(matches!(result, Err(CollectionError::InvalidQuery { .. })));
+    }
+
+    let count = collections
+        .count(table_name.to_string(), json!({ "age": { "$gt": 0 } }))
+        .await
+        .unwrap();
+
+    assert_eq!(count, 2);
+}
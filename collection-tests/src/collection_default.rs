@@ -1,18 +1,10 @@
-use std::env;
-
-use dotenv::dotenv;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tracing::{debug, info};
-use tracing_subscriber::filter::LevelFilter;
-
-use collection::CollectionField;
-use collection::FieldType;
-use collection::{Collections, Storage};
-use collection_postgres::StorePostgresql;
-use helpers::cleanup_table::cleanup_table;
 
-mod helpers;
+use vivalaakam_seattle_collection::CollectionField;
+use vivalaakam_seattle_collection::FieldType;
+use vivalaakam_seattle_collection::{Collections, Storage};
 
 #[derive(Serialize, Deserialize)]
 pub struct CollectionResponse {
@@ -20,25 +12,12 @@ pub struct CollectionResponse {
     name: String,
 }
 
-#[tokio::test]
-async fn collection_default() {
-    dotenv().ok();
-
-    tracing_subscriber::fmt()
-        .with_max_level(LevelFilter::DEBUG)
-        .with_test_writer()
-        .init();
-
-    let database_url =
-        env::var("POSTGRES_DATABASE_URL").expect("POSTGRES_DATABASE_URL must be set");
-
-    let instance = StorePostgresql::new(database_url.as_str()).await;
-
-    let table_name = "Collection1".to_string();
+pub const TABLES: &[&str] = &["CollectionDefault"];
 
-    cleanup_table(instance.get_pool(), &table_name).await;
+pub async fn collection_default<T: Storage>(storage: T) {
+    let table_name = "CollectionDefault".to_string();
 
-    let collections = Collections::new(instance).await;
+    let collections = Collections::new(storage).await;
 
     let collection = collections
         .storage
@@ -49,6 +28,8 @@ async fn collection_default() {
                 field_type: FieldType::String,
                 default: Some(Value::String("default_name".to_string())),
                 required: None,
+                unique: None,
+                on_delete: None,
             }],
         )
         .await
@@ -60,7 +41,7 @@ async fn collection_default() {
 
     debug!("created_default {created_default:?}");
 
-    assert_eq!(created_default.is_ok(), true);
+    assert!(created_default.is_ok());
 
     let created_default = created_default.unwrap();
 
@@ -79,7 +60,7 @@ async fn collection_default() {
         )
         .await;
 
-    assert_eq!(created_exists.is_ok(), true);
+    assert!(created_exists.is_ok());
 
     let created_exists = created_exists.unwrap();
 
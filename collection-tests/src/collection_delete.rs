@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::info;
+
+use vivalaakam_seattle_collection::{Collections, Projection, Storage};
+
+#[derive(Serialize, Deserialize)]
+pub struct CollectionResponse {
+    id: String,
+    name: String,
+}
+
+pub const TABLES: &[&str] = &["CollectionDelete"];
+
+pub async fn collection_delete<T: Storage>(storage: T) {
+    let table_name = "CollectionDelete".to_string();
+
+    let collections = Collections::new(storage).await;
+
+    let created = collections
+        .insert(
+            table_name.to_string(),
+            json!({
+                "name": "test",
+            }),
+        )
+        .await;
+
+    assert!(created.is_ok());
+
+    let created = created.unwrap();
+
+    info!("created: {:?}", json!(created).to_string());
+
+    let row = serde_json::from_value::<CollectionResponse>(created).unwrap();
+
+    assert_eq!(row.name, "test");
+
+    // delete row
+
+    let deleted = collections
+        .delete(table_name.to_string(), row.id.to_string())
+        .await;
+
+    info!("deleted: {deleted:?}");
+
+    assert!(deleted.is_ok());
+
+    let row = collections
+        .get(
+            table_name.to_string(),
+            row.id.to_string(),
+            Projection::default(),
+        )
+        .await;
+
+    assert!(row.is_err());
+}
@@ -0,0 +1,97 @@
+use serde_json::{json, Value};
+
+use vivalaakam_seattle_collection::{CollectionField, Collections, FieldType, Projection, Storage};
+
+pub const TABLES: &[&str] = &["CollectionFields"];
+
+pub async fn collection_fields<T: Storage>(storage: T) {
+    let table_name = "CollectionFields".to_string();
+
+    let collections = Collections::new(storage).await;
+
+    let collection = collections
+        .storage
+        .create_collection(
+            table_name.to_string(),
+            vec![CollectionField {
+                name: "name".to_string(),
+                field_type: FieldType::String,
+                default: Some(Value::String("default_name".to_string())),
+                required: None,
+                unique: None,
+                on_delete: None,
+            }],
+        )
+        .await
+        .unwrap();
+
+    let collection = collections
+        .storage
+        .insert_field_to_collection(
+            &collection,
+            CollectionField {
+                name: "age".to_string(),
+                field_type: FieldType::Number,
+                default: None,
+                required: None,
+                unique: None,
+                on_delete: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    assert!(collection.get_field(&"age".to_string()).is_some());
+
+    collections.set_collection(&table_name, collection.clone());
+
+    let created = collections
+        .insert(table_name.to_string(), json!({ "age": 10 }))
+        .await
+        .unwrap();
+
+    assert_eq!(created["name"], json!("default_name"));
+    assert_eq!(created["age"], json!(10.0));
+
+    // remove field
+
+    let collection = collections
+        .storage
+        .remove_field_from_collection(
+            &collection,
+            collection.get_field(&"age".to_string()).cloned().unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert!(collection.get_field(&"age".to_string()).is_none());
+
+    collections.set_collection(&table_name, collection);
+
+    let check = collections
+        .get(
+            table_name.to_string(),
+            created["id"].as_str().unwrap().to_string(),
+            Projection::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(check.get("age"), None);
+
+    // remove collection
+
+    let collection = collections.get_collection(&table_name).unwrap();
+
+    assert!(collections
+        .storage
+        .remove_collection(&collection)
+        .await
+        .is_ok());
+
+    assert!(collections
+        .storage
+        .get_collection(table_name.to_string())
+        .await
+        .is_err());
+}
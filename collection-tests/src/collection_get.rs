@@ -1,16 +1,8 @@
-use std::env;
-
-use dotenv::dotenv;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tracing::info;
-use tracing_subscriber::filter::LevelFilter;
-
-use collection::{value_to_string, Collections};
-use collection_postgres::StorePostgresql;
-use helpers::cleanup_table::cleanup_table;
 
-mod helpers;
+use vivalaakam_seattle_collection::{value_to_string, Collections, Projection, Storage};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct CollectionResponse {
@@ -19,25 +11,12 @@ pub struct CollectionResponse {
     age: i32,
 }
 
-#[tokio::test]
-async fn collection_get() {
-    dotenv().ok();
-
-    tracing_subscriber::fmt()
-        .with_max_level(LevelFilter::DEBUG)
-        .with_test_writer()
-        .init();
-
-    let database_url =
-        env::var("POSTGRES_DATABASE_URL").expect("POSTGRES_DATABASE_URL must be set");
-
-    let instance = StorePostgresql::new(database_url.as_str()).await;
+pub const TABLES: &[&str] = &["CollectionGet"];
 
-    let table_name = "Collection1".to_string();
+pub async fn collection_get<T: Storage>(storage: T) {
+    let table_name = "CollectionGet".to_string();
 
-    cleanup_table(instance.get_pool(), &table_name).await;
-
-    let collections = Collections::new(instance).await;
+    let collections = Collections::new(storage).await;
 
     let created = collections
         .insert(
@@ -49,7 +28,7 @@ async fn collection_get() {
         )
         .await;
 
-    assert_eq!(created.is_ok(), true);
+    assert!(created.is_ok());
 
     let created = value_to_string(created.unwrap());
 
@@ -63,10 +42,14 @@ async fn collection_get() {
     // check
 
     let check = collections
-        .get(table_name.to_string(), row.id.to_string())
+        .get(
+            table_name.to_string(),
+            row.id.to_string(),
+            Projection::default(),
+        )
         .await;
 
-    assert_eq!(check.is_ok(), true);
+    assert!(check.is_ok());
 
     let check = value_to_string(check.unwrap());
 
@@ -77,13 +60,17 @@ async fn collection_get() {
     // check 2
 
     let check2 = collections
-        .get(table_name.to_string(), "not-exists".to_string())
+        .get(
+            table_name.to_string(),
+            "not-exists".to_string(),
+            Projection::default(),
+        )
         .await;
 
-    assert_eq!(check2.is_err(), true);
+    assert!(check2.is_err());
 
     assert_eq!(
         format!("{check2:?}"),
-        r#"Err(StorageError { error: ValueNotFound { collection: "Collection1", id: "not-exists" } })"#
+        r#"Err(StorageError { error: ValueNotFound { collection: "CollectionGet", id: "not-exists" } })"#
     );
 }
@@ -0,0 +1,108 @@
+use serde_json::json;
+
+use vivalaakam_seattle_collection::{
+    CollectionError, Collections, Precondition, Storage, WhereQuery,
+};
+
+pub const TABLES: &[&str] = &["CollectionGuard"];
+
+pub async fn collection_guard<T: Storage>(storage: T) {
+    let table_name = "CollectionGuard";
+
+    let collections = Collections::new(storage).await;
+
+    let created = collections
+        .insert(
+            table_name.to_string(),
+            json!({ "status": "paid", "amount": 10 }),
+        )
+        .await
+        .unwrap();
+
+    let id = created["id"].as_str().unwrap().to_string();
+
+    let guard = |query| Precondition {
+        query: WhereQuery::parse(&query).unwrap(),
+        ..Default::default()
+    };
+
+    let updated = collections
+        .update_with_precondition(
+            table_name.to_string(),
+            id.to_string(),
+            json!({ "status": "shipped" }),
+            guard(json!({ "status": "paid", "amount": { "$gte": 5 } })),
+            false,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(updated["status"], "shipped");
+
+    let result = collections
+        .update_with_precondition(
+            table_name.to_string(),
+            id.to_string(),
+            json!({ "status": "cancelled" }),
+            guard(json!({ "status": "paid" })),
+            false,
+        )
+        .await;
+
+    assert_eq!(
+        result,
+        Err(CollectionError::Conflict {
+            collection: table_name.to_string(),
+            id: id.to_string()
+        })
+    );
+
+    // a guard on a misspelled field fails instead of matching
+    let result = collections
+        .update_with_precondition(
+            table_name.to_string(),
+            id.to_string(),
+            json!({ "status": "refunded" }),
+            guard(json!({ "stauts": "shipped" })),
+            false,
+        )
+        .await;
+
+    assert!(matches!(result, Err(CollectionError::InvalidQuery { .. })));
+
+    let result = collections
+        .delete_with_precondition(
+            table_name.to_string(),
+            id.to_string(),
+            guard(json!({ "$or": [{ "status": "shipped" }, { "stauts": "paid" }] })),
+        )
+        .await;
+
+    assert!(matches!(result, Err(CollectionError::InvalidQuery { .. })));
+
+    let row = collections
+        .get(table_name.to_string(), id.to_string(), Default::default())
+        .await
+        .unwrap();
+
+    assert_eq!(row["status"], "shipped");
+
+    let result = collections
+        .delete_with_precondition(
+            table_name.to_string(),
+            id.to_string(),
+            guard(json!({ "$or": [{ "status": "paid" }, { "amount": { "$gt": 100 } }] })),
+        )
+        .await;
+
+    assert!(matches!(result, Err(CollectionError::Conflict { .. })));
+
+    collections
+        .delete_with_precondition(
+            table_name.to_string(),
+            id.to_string(),
+            guard(json!({ "status": "shipped" })),
+        )
+        .await
+        .unwrap();
+}
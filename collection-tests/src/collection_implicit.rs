@@ -0,0 +1,47 @@
+use serde_json::json;
+
+use vivalaakam_seattle_collection::{CollectionError, Collections, Storage};
+
+pub const TABLES: &[&str] = &["CollectionImplicit"];
+
+pub async fn collection_implicit<T: Storage>(storage: T) {
+    let table_name = "CollectionImplicit".to_string();
+
+    let collections = Collections::new(storage)
+        .await
+        .with_implicit_collections(false);
+
+    let created = collections
+        .insert(table_name.to_string(), json!({ "name": "test" }))
+        .await;
+
+    assert_eq!(
+        created,
+        Err(CollectionError::CollectionNotFound {
+            collection: table_name.to_string()
+        })
+    );
+    assert!(collections.schema(table_name.to_string()).is_err());
+
+    let created = collections
+        .create_schema(table_name.to_string(), vec![], None, false)
+        .await;
+
+    assert!(matches!(
+        created,
+        Err(CollectionError::SchemaAccessDenied { .. })
+    ));
+    assert!(collections.schema(table_name.to_string()).is_err());
+
+    collections
+        .create_schema(table_name.to_string(), vec![], None, true)
+        .await
+        .unwrap();
+
+    let created = collections
+        .insert(table_name.to_string(), json!({ "name": "test" }))
+        .await
+        .unwrap();
+
+    assert_eq!(created["name"], "test");
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use vivalaakam_seattle_collection::{value_to_string, Collections, Projection, Storage};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct CollectionResponse {
+    id: String,
+    name: String,
+    age: i32,
+}
+
+pub const TABLES: &[&str] = &["CollectionInsert"];
+
+pub async fn collection_insert<T: Storage>(storage: T) {
+    let table_name = "CollectionInsert".to_string();
+
+    let collections = Collections::new(storage).await;
+
+    let created = collections
+        .insert(
+            table_name.to_string(),
+            json!({
+                "name": "test",
+                "age": 10,
+            }),
+        )
+        .await;
+
+    assert!(created.is_ok());
+
+    let created = value_to_string(created.unwrap());
+
+    let row = serde_json::from_str::<CollectionResponse>(&created).unwrap();
+
+    assert_eq!(row.name, "test");
+    assert_eq!(row.age, 10);
+
+    // check
+
+    let check = collections
+        .get(
+            table_name.to_string(),
+            row.id.to_string(),
+            Projection::default(),
+        )
+        .await;
+
+    assert!(check.is_ok());
+
+    let check = value_to_string(check.unwrap());
+
+    let check_row = serde_json::from_str::<CollectionResponse>(&check).unwrap();
+
+    assert_eq!(check_row, row);
+
+    // check missing
+
+    let check2 = collections
+        .get(
+            table_name.to_string(),
+            "not-exists".to_string(),
+            Projection::default(),
+        )
+        .await;
+
+    assert_eq!(
+        format!("{check2:?}"),
+        r#"Err(StorageError { error: ValueNotFound { collection: "CollectionInsert", id: "not-exists" } })"#
+    );
+}
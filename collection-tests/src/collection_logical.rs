@@ -0,0 +1,116 @@
+use serde_json::{json, Value};
+
+use vivalaakam_seattle_collection::{CollectionError, Collections, QueryOptions, Storage};
+
+fn names(rows: Vec<Value>) -> Vec<String> {
+    let mut names = rows
+        .into_iter()
+        .map(|row| row.get("name").unwrap().as_str().unwrap().to_string())
+        .collect::<Vec<String>>();
+
+    names.sort();
+    names
+}
+
+pub const TABLES: &[&str] = &["CollectionLogical"];
+
+pub async fn collection_logical<T: Storage>(storage: T) {
+    let table_name = "CollectionLogical".to_string();
+
+    let collections = Collections::new(storage).await;
+
+    for i in 0..5 {
+        let result = collections
+            .insert(
+                table_name.to_string(),
+                json!({ "name": format!("test_{}", i), "age": 10 + i }),
+            )
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    let result = collections
+        .insert(table_name.to_string(), json!({ "name": "empty" }))
+        .await;
+
+    assert!(result.is_ok());
+
+    // test or
+
+    let result_or = collections
+        .list(
+            table_name.to_string(),
+            json!({ "$or": [{ "age": 10 }, { "age": 14 }] }),
+            QueryOptions::default(),
+        )
+        .await;
+
+    assert_eq!(names(result_or.unwrap()), vec!["test_0", "test_4"]);
+
+    // test and
+
+    let result_and = collections
+        .list(
+            table_name.to_string(),
+            json!({ "$and": [{ "age": { "$gte": 11 } }, { "age": { "$lte": 12 } }] }),
+            QueryOptions::default(),
+        )
+        .await;
+
+    assert_eq!(names(result_and.unwrap()), vec!["test_1", "test_2"]);
+
+    // test nor, rows with null fields never match
+
+    let result_nor = collections
+        .list(
+            table_name.to_string(),
+            json!({ "$nor": [{ "age": 10 }, { "name": "test_1" }] }),
+            QueryOptions::default(),
+        )
+        .await;
+
+    assert_eq!(
+        names(result_nor.unwrap()),
+        vec!["test_2", "test_3", "test_4"]
+    );
+
+    // test nested
+
+    let query = json!({
+        "name": { "$ne": "test_0" },
+        "$or": [
+            { "age": { "$lt": 12 } },
+            { "$and": [{ "age": { "$gt": 12 } }, { "name": "test_4" }] }
+        ]
+    });
+
+    let result_nested = collections
+        .list(
+            table_name.to_string(),
+            query.clone(),
+            QueryOptions::default(),
+        )
+        .await;
+
+    assert_eq!(names(result_nested.unwrap()), vec!["test_1", "test_4"]);
+
+    let count_nested = collections.count(table_name.to_string(), query).await;
+
+    assert_eq!(count_nested, Ok(2));
+
+    // test invalid
+
+    let result_invalid = collections
+        .list(
+            table_name.to_string(),
+            json!({ "$or": [] }),
+            QueryOptions::default(),
+        )
+        .await;
+
+    assert!(matches!(
+        result_invalid,
+        Err(CollectionError::InvalidQuery { .. })
+    ));
+}
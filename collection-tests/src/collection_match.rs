@@ -0,0 +1,162 @@
+use serde_json::{json, Value};
+
+use vivalaakam_seattle_collection::{CollectionError, Collections, QueryOptions, Storage};
+
+async fn list_names<T: Storage>(
+    collections: &Collections<T>,
+    table_name: &str,
+    query: Value,
+) -> Vec<String> {
+    let mut names = collections
+        .list(table_name.to_string(), query, QueryOptions::default())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| row.get("name").unwrap().as_str().unwrap_or("").to_string())
+        .collect::<Vec<String>>();
+
+    names.sort();
+    names
+}
+
+pub const TABLES: &[&str] = &["CollectionMatch"];
+
+pub async fn collection_match<T: Storage>(storage: T) {
+    let table_name = "CollectionMatch";
+
+    let collections = Collections::new(storage).await;
+
+    for name in ["Alice", "alina", "bob_1", "b%b"] {
+        let result = collections
+            .insert(table_name.to_string(), json!({ "name": name, "age": 1 }))
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    let result = collections
+        .insert(table_name.to_string(), json!({ "age": 2 }))
+        .await;
+
+    assert!(result.is_ok());
+
+    // test exists
+
+    let names = list_names(
+        &collections,
+        table_name,
+        json!({ "name": { "$exists": true } }),
+    )
+    .await;
+    assert_eq!(names, vec!["Alice", "alina", "b%b", "bob_1"]);
+
+    let names = list_names(
+        &collections,
+        table_name,
+        json!({ "name": { "$exists": false } }),
+    )
+    .await;
+    assert_eq!(names, vec![""]);
+
+    // test regex
+
+    let names = list_names(
+        &collections,
+        table_name,
+        json!({ "name": { "$regex": "^al" } }),
+    )
+    .await;
+    assert_eq!(names, vec!["alina"]);
+
+    let names = list_names(
+        &collections,
+        table_name,
+        json!({ "name": { "$regex": "^al", "$options": "i" } }),
+    )
+    .await;
+    assert_eq!(names, vec!["Alice", "alina"]);
+
+    let query = json!({ "name": { "$regex": "(" } });
+
+    let result = collections
+        .list(
+            table_name.to_string(),
+            query.clone(),
+            QueryOptions::default(),
+        )
+        .await;
+
+    assert!(matches!(result, Err(CollectionError::InvalidQuery { .. })));
+
+    let result = collections.count(table_name.to_string(), query).await;
+
+    assert!(matches!(result, Err(CollectionError::InvalidQuery { .. })));
+
+    // test starts with / ends with
+
+    let names = list_names(
+        &collections,
+        table_name,
+        json!({ "name": { "$startsWith": "Al" } }),
+    )
+    .await;
+    assert_eq!(names, vec!["Alice"]);
+
+    let names = list_names(
+        &collections,
+        table_name,
+        json!({ "name": { "$startsWith": "al", "$options": "i" } }),
+    )
+    .await;
+    assert_eq!(names, vec!["Alice", "alina"]);
+
+    let names = list_names(
+        &collections,
+        table_name,
+        json!({ "name": { "$endsWith": "_1" } }),
+    )
+    .await;
+    assert_eq!(names, vec!["bob_1"]);
+
+    // test text, wildcards are matched literally
+
+    let names = list_names(
+        &collections,
+        table_name,
+        json!({ "name": { "$text": "li" } }),
+    )
+    .await;
+    assert_eq!(names, vec!["Alice", "alina"]);
+
+    let names = list_names(
+        &collections,
+        table_name,
+        json!({ "name": { "$text": "LI" } }),
+    )
+    .await;
+    assert!(names.is_empty());
+
+    let names = list_names(
+        &collections,
+        table_name,
+        json!({ "name": { "$text": "LI", "$options": "i" } }),
+    )
+    .await;
+    assert_eq!(names, vec!["Alice", "alina"]);
+
+    let names = list_names(
+        &collections,
+        table_name,
+        json!({ "name": { "$text": "%" } }),
+    )
+    .await;
+    assert_eq!(names, vec!["b%b"]);
+
+    let names = list_names(
+        &collections,
+        table_name,
+        json!({ "name": { "$text": "_" } }),
+    )
+    .await;
+    assert_eq!(names, vec!["bob_1"]);
+}
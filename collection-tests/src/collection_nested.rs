@@ -0,0 +1,134 @@
+use serde_json::{json, Value};
+
+use vivalaakam_seattle_collection::{Collections, QueryOptions, Storage};
+
+async fn list_names<T: Storage>(
+    collections: &Collections<T>,
+    table_name: &str,
+    query: Value,
+    options: QueryOptions,
+) -> Vec<String> {
+    collections
+        .list(table_name.to_string(), query, options)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| row.get("name").unwrap().as_str().unwrap().to_string())
+        .collect::<Vec<String>>()
+}
+
+async fn list_sorted_names<T: Storage>(
+    collections: &Collections<T>,
+    table_name: &str,
+    query: Value,
+) -> Vec<String> {
+    let mut names = list_names(collections, table_name, query, QueryOptions::default()).await;
+    names.sort();
+    names
+}
+
+pub const TABLES: &[&str] = &["CollectionNested"];
+
+pub async fn collection_nested<T: Storage>(storage: T) {
+    let table_name = "CollectionNested";
+
+    let collections = Collections::new(storage).await;
+
+    for (name, address) in [
+        (
+            "a",
+            json!({ "city": "Seattle", "zip": 98101, "geo": { "lat": 47.6 }, "tags": ["x"] }),
+        ),
+        (
+            "b",
+            json!({ "city": "Portland", "zip": 97201, "geo": { "lat": 45.5 } }),
+        ),
+        ("c", json!({ "city": "Seattle", "zip": "98102" })),
+        ("d", Value::Null),
+    ] {
+        let result = collections
+            .insert(
+                table_name.to_string(),
+                json!({ "name": name, "address": address }),
+            )
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    // test eq
+
+    let names = list_sorted_names(
+        &collections,
+        table_name,
+        json!({ "address.city": "Seattle" }),
+    )
+    .await;
+    assert_eq!(names, vec!["a", "c"]);
+
+    // test nested value type
+
+    let names = list_sorted_names(
+        &collections,
+        table_name,
+        json!({ "address.zip": { "$gt": 98000 } }),
+    )
+    .await;
+    assert_eq!(names, vec!["a"]);
+
+    let names = list_sorted_names(
+        &collections,
+        table_name,
+        json!({ "address.geo.lat": { "$lt": 46 } }),
+    )
+    .await;
+    assert_eq!(names, vec!["b"]);
+
+    // test string, exists and array operators
+
+    let names = list_sorted_names(
+        &collections,
+        table_name,
+        json!({ "address.city": { "$startsWith": "Port" } }),
+    )
+    .await;
+    assert_eq!(names, vec!["b"]);
+
+    let names = list_sorted_names(
+        &collections,
+        table_name,
+        json!({ "address.zip": { "$exists": false } }),
+    )
+    .await;
+    assert_eq!(names, vec!["d"]);
+
+    let names = list_sorted_names(
+        &collections,
+        table_name,
+        json!({ "address.tags": { "$contains": "x" } }),
+    )
+    .await;
+    assert_eq!(names, vec!["a"]);
+
+    // test order
+
+    let names = list_names(
+        &collections,
+        table_name,
+        json!({}),
+        QueryOptions {
+            order: QueryOptions::parse_order("address.geo.lat"),
+            limit: Some(2),
+            ..Default::default()
+        },
+    )
+    .await;
+    assert_eq!(names, vec!["b", "a"]);
+
+    // test count
+
+    let count = collections
+        .count(table_name.to_string(), json!({ "address.city": "Seattle" }))
+        .await;
+    assert_eq!(count, Ok(2));
+}
@@ -0,0 +1,279 @@
+use serde_json::json;
+
+use vivalaakam_seattle_collection::{
+    CollectionError, CollectionField, Collections, FieldType, Projection, QueryOptions, Storage,
+};
+
+pub const TABLES: &[&str] = &["NumericItems", "NumericPrices", "NumericWide"];
+
+pub async fn collection_numeric<T: Storage>(storage: T) {
+    let collections = Collections::new(storage).await;
+
+    let table_name = "NumericItems".to_string();
+
+    let item = collections
+        .insert(
+            table_name.to_string(),
+            json!({
+                "count": 7,
+                "big": 9007199254740993i64,
+                "ratio": 0.5,
+                "price": { "__type": "Decimal", "value": "12.345" }
+            }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(item["count"], json!(7));
+    assert_eq!(item["big"], json!(9007199254740993i64));
+    assert_eq!(item["ratio"], json!(0.5));
+    assert_eq!(
+        item["price"],
+        json!({ "__type": "Decimal", "value": "12.345" })
+    );
+
+    let schema = collections.schema(table_name.to_string()).unwrap();
+    let field_type = |name: &str| {
+        schema
+            .fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| field.field_type.clone())
+            .unwrap()
+    };
+
+    assert_eq!(field_type("count"), FieldType::Integer);
+    assert_eq!(field_type("big"), FieldType::BigInt);
+    assert_eq!(field_type("ratio"), FieldType::Number);
+    assert_eq!(
+        field_type("price"),
+        FieldType::Decimal {
+            precision: 28,
+            scale: 3
+        }
+    );
+
+    // integers past double precision read back exactly
+    let stored = collections
+        .get(
+            table_name.to_string(),
+            item["id"].as_str().unwrap().to_string(),
+            Projection::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(stored["count"], json!(7));
+    assert_eq!(stored["big"], json!(9007199254740993i64));
+
+    let result = collections
+        .insert(table_name.to_string(), json!({ "count": 1.5 }))
+        .await;
+
+    assert!(result.is_err());
+
+    let item = collections
+        .update(
+            table_name.to_string(),
+            item["id"].as_str().unwrap().to_string(),
+            json!({
+                "count": { "__op": "Increment", "amount": 2 },
+                "price": { "__op": "Increment", "amount": 0.5 }
+            }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(item["count"], json!(9));
+    assert_eq!(
+        item["price"],
+        json!({ "__type": "Decimal", "value": "12.845" })
+    );
+
+    collections
+        .insert(
+            table_name.to_string(),
+            json!({ "count": 3, "price": "9.5" }),
+        )
+        .await
+        .unwrap();
+
+    let items = collections
+        .list(
+            table_name.to_string(),
+            json!({ "price": { "$gt": "10" } }),
+            QueryOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["count"], json!(9));
+
+    let count = collections
+        .count(table_name.to_string(), json!({ "count": { "$lte": 3 } }))
+        .await
+        .unwrap();
+
+    assert_eq!(count, 1);
+
+    // digits past double precision still order and match
+    for price in ["1234567890123456.001", "1234567890123456.002"] {
+        collections
+            .insert(table_name.to_string(), json!({ "price": price }))
+            .await
+            .unwrap();
+    }
+
+    let items = collections
+        .list(
+            table_name.to_string(),
+            json!({ "price": { "$gt": "1234567890123456.001" } }),
+            QueryOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(items.len(), 1);
+    assert_eq!(
+        items[0]["price"],
+        json!({ "__type": "Decimal", "value": "1234567890123456.002" })
+    );
+
+    let count = collections
+        .count(
+            table_name.to_string(),
+            json!({ "price": "1234567890123456.001" }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(count, 1);
+
+    // increments past double precision stay exact
+    let item = collections
+        .insert(
+            table_name.to_string(),
+            json!({ "big": 9007199254740993i64, "price": "1234567890123456.001" }),
+        )
+        .await
+        .unwrap();
+
+    let item = collections
+        .update(
+            table_name.to_string(),
+            item["id"].as_str().unwrap().to_string(),
+            json!({
+                "big": { "__op": "Increment", "amount": 2 },
+                "price": { "__op": "Increment", "amount": -0.002 }
+            }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(item["big"], json!(9007199254740995i64));
+    assert_eq!(
+        item["price"],
+        json!({ "__type": "Decimal", "value": "1234567890123455.999" })
+    );
+
+    // declared precision and scale
+    collections
+        .create_schema(
+            "NumericPrices".to_string(),
+            vec![CollectionField {
+                name: "amount".to_string(),
+                field_type: FieldType::Decimal {
+                    precision: 10,
+                    scale: 2,
+                },
+                default: None,
+                required: None,
+                unique: None,
+                on_delete: None,
+            }],
+            None,
+            false,
+        )
+        .await
+        .unwrap();
+
+    let price = collections
+        .insert("NumericPrices".to_string(), json!({ "amount": "19.999" }))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        price["amount"],
+        json!({ "__type": "Decimal", "value": "20.00" })
+    );
+
+    let price = collections
+        .get(
+            "NumericPrices".to_string(),
+            price["id"].as_str().unwrap().to_string(),
+            Projection::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        price["amount"],
+        json!({ "__type": "Decimal", "value": "20.00" })
+    );
+
+    let price = collections
+        .insert(
+            "NumericPrices".to_string(),
+            json!({ "amount": "-12345678.05" }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        price["amount"],
+        json!({ "__type": "Decimal", "value": "-12345678.05" })
+    );
+
+    let result = collections
+        .insert(
+            "NumericPrices".to_string(),
+            json!({ "amount": 123456789.5 }),
+        )
+        .await;
+
+    assert!(result.is_err());
+
+    // oversized exponents are rejected, not expanded
+    for amount in ["1e2147483647", "1e300000000"] {
+        let result = collections
+            .insert("NumericPrices".to_string(), json!({ "amount": amount }))
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    let result = collections
+        .create_schema(
+            "NumericWide".to_string(),
+            vec![CollectionField {
+                name: "amount".to_string(),
+                field_type: FieldType::Decimal {
+                    precision: 40,
+                    scale: 2,
+                },
+                default: None,
+                required: None,
+                unique: None,
+                on_delete: None,
+            }],
+            None,
+            false,
+        )
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(CollectionError::ValidateFields { .. })
+    ));
+}
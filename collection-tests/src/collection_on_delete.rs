@@ -0,0 +1,253 @@
+use serde_json::json;
+
+use vivalaakam_seattle_collection::{
+    CollectionError, CollectionField, Collections, FieldType, OnDelete, Pointer, Projection,
+    Storage,
+};
+
+fn pointer_field(name: &str, target: &str, on_delete: OnDelete) -> CollectionField {
+    CollectionField {
+        name: name.to_string(),
+        field_type: FieldType::Pointer {
+            target: target.to_string(),
+        },
+        default: None,
+        required: None,
+        unique: None,
+        on_delete: Some(on_delete),
+    }
+}
+
+pub const TABLES: &[&str] = &[
+    "_Join:read:DeleteReaders",
+    "DeleteReaders",
+    "DeleteComments",
+    "DeletePosts",
+    "DeleteUsers",
+    "DeleteCompanies",
+];
+
+pub async fn collection_on_delete<T: Storage>(storage: T) {
+    let collections = Collections::new(storage).await;
+
+    let company = collections
+        .insert("DeleteCompanies".to_string(), json!({ "name": "acme" }))
+        .await
+        .unwrap();
+
+    let company = Pointer::new("DeleteCompanies", company["id"].as_str().unwrap());
+
+    // rules only apply to pointers
+    let result = collections
+        .create_schema(
+            "DeleteUsers".to_string(),
+            vec![CollectionField {
+                name: "name".to_string(),
+                field_type: FieldType::String,
+                default: None,
+                required: None,
+                unique: None,
+                on_delete: Some(OnDelete::Cascade),
+            }],
+            None,
+            false,
+        )
+        .await;
+
+    assert!(result.is_err());
+
+    collections
+        .create_schema(
+            "DeleteUsers".to_string(),
+            vec![pointer_field(
+                "company",
+                "DeleteCompanies",
+                OnDelete::Restrict,
+            )],
+            None,
+            false,
+        )
+        .await
+        .unwrap();
+
+    collections
+        .create_schema(
+            "DeletePosts".to_string(),
+            vec![
+                pointer_field("author", "DeleteUsers", OnDelete::Cascade),
+                pointer_field("editor", "DeleteUsers", OnDelete::SetNull),
+            ],
+            None,
+            false,
+        )
+        .await
+        .unwrap();
+
+    collections
+        .create_schema(
+            "DeleteComments".to_string(),
+            vec![pointer_field("post", "DeletePosts", OnDelete::Cascade)],
+            None,
+            false,
+        )
+        .await
+        .unwrap();
+
+    let mut users = vec![];
+    for name in ["john", "jane"] {
+        let user = collections
+            .insert(
+                "DeleteUsers".to_string(),
+                json!({ "name": name, "company": company.to_value() }),
+            )
+            .await
+            .unwrap();
+
+        users.push(Pointer::new("DeleteUsers", user["id"].as_str().unwrap()));
+    }
+
+    let first = collections
+        .insert(
+            "DeletePosts".to_string(),
+            json!({ "title": "first", "author": users[0].to_value(), "editor": users[1].to_value() }),
+        )
+        .await
+        .unwrap();
+
+    let second = collections
+        .insert(
+            "DeletePosts".to_string(),
+            json!({ "title": "second", "author": users[1].to_value(), "editor": users[0].to_value() }),
+        )
+        .await
+        .unwrap();
+
+    collections
+        .insert(
+            "DeleteComments".to_string(),
+            json!({
+                "text": "comment",
+                "post": Pointer::new("DeletePosts", first["id"].as_str().unwrap()).to_value()
+            }),
+        )
+        .await
+        .unwrap();
+
+    let first = Pointer::new("DeletePosts", first["id"].as_str().unwrap());
+
+    collections
+        .insert(
+            "DeleteReaders".to_string(),
+            json!({
+                "name": "reader",
+                "read": {
+                    "__op": "AddRelation",
+                    "objects": [first.to_value(), Pointer::new("DeletePosts", second["id"].as_str().unwrap()).to_value()]
+                }
+            }),
+        )
+        .await
+        .unwrap();
+
+    // restrict
+    let result = collections
+        .delete("DeleteCompanies".to_string(), company.object_id.to_string())
+        .await;
+
+    assert_eq!(
+        result,
+        Err(CollectionError::Referenced {
+            collection: "DeleteCompanies".to_string(),
+            id: company.object_id.to_string(),
+        })
+    );
+
+    let result = collections
+        .bulk_delete("DeleteCompanies".to_string(), json!({ "name": "acme" }))
+        .await;
+
+    assert_eq!(
+        result,
+        Err(CollectionError::Referenced {
+            collection: "DeleteCompanies".to_string(),
+            id: company.object_id.to_string(),
+        })
+    );
+
+    let count = collections
+        .count("DeleteCompanies".to_string(), json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(count, 1);
+
+    // cascade through posts into comments, set null on the other post
+    collections
+        .delete("DeleteUsers".to_string(), users[0].object_id.to_string())
+        .await
+        .unwrap();
+
+    let count = collections
+        .count("DeletePosts".to_string(), json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(count, 1);
+
+    let count = collections
+        .count("DeleteComments".to_string(), json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(count, 0);
+
+    let post = collections
+        .get(
+            "DeletePosts".to_string(),
+            second["id"].as_str().unwrap().to_string(),
+            Projection::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(post["author"], users[1].to_value());
+    assert_eq!(post["editor"], json!(null));
+
+    // relations to cascaded rows are dropped with them
+    let count = collections
+        .count(
+            "DeleteReaders".to_string(),
+            json!({ "read": { "$eq": first.to_value() } }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(count, 0);
+
+    let count = collections
+        .count(
+            "DeleteReaders".to_string(),
+            json!({ "read": { "$eq": Pointer::new("DeletePosts", post["id"].as_str().unwrap()).to_value() } }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(count, 1);
+
+    collections
+        .delete("DeleteUsers".to_string(), users[1].object_id.to_string())
+        .await
+        .unwrap();
+
+    collections
+        .delete("DeleteCompanies".to_string(), company.object_id.to_string())
+        .await
+        .unwrap();
+
+    let count = collections
+        .count("DeletePosts".to_string(), json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(count, 0);
+}
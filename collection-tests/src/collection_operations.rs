@@ -0,0 +1,135 @@
+use serde_json::json;
+
+use vivalaakam_seattle_collection::{Collections, Projection, Storage};
+
+pub const TABLES: &[&str] = &["CollectionOperations"];
+
+pub async fn collection_operations<T>(storage: T)
+where
+    T: Storage + Clone + Send + Sync + 'static,
+{
+    let table_name = "CollectionOperations";
+
+    let collections = Collections::new(storage).await;
+
+    let created = collections
+        .insert(
+            table_name.to_string(),
+            json!({
+                "name": "test",
+                "views": 10,
+                "tags": ["a", "b"],
+                "likes": { "__op": "Increment", "amount": 2 },
+            }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(created["likes"].as_f64(), Some(2.0));
+
+    let id = created["id"].as_str().unwrap().to_string();
+
+    collections
+        .update(
+            table_name.to_string(),
+            id.to_string(),
+            json!({
+                "views": { "__op": "Increment", "amount": 5 },
+                "tags": { "__op": "Add", "objects": ["c", "a"] },
+                "scores": { "__op": "AddUnique", "objects": [1, 2, 2] },
+                "name": { "__op": "Delete" },
+            }),
+        )
+        .await
+        .unwrap();
+
+    let row = collections
+        .get(
+            table_name.to_string(),
+            id.to_string(),
+            Projection::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(row["views"].as_f64(), Some(15.0));
+    assert_eq!(row["tags"], json!(["a", "b", "c", "a"]));
+    assert_eq!(row["scores"], json!([1, 2]));
+    assert_eq!(row["name"], json!(null));
+
+    collections
+        .update(
+            table_name.to_string(),
+            id.to_string(),
+            json!({
+                "views": { "__op": "Increment", "amount": -1 },
+                "tags": { "__op": "Remove", "objects": ["a"] },
+                "scores": { "__op": "AddUnique", "objects": [2, 3] },
+            }),
+        )
+        .await
+        .unwrap();
+
+    let row = collections
+        .get(
+            table_name.to_string(),
+            id.to_string(),
+            Projection::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(row["views"].as_f64(), Some(14.0));
+    assert_eq!(row["tags"], json!(["b", "c"]));
+    assert_eq!(row["scores"], json!([1, 2, 3]));
+
+    // operations must match the field type
+    let result = collections
+        .update(
+            table_name.to_string(),
+            id.to_string(),
+            json!({ "tags": { "__op": "Increment" } }),
+        )
+        .await;
+    assert!(result.is_err());
+
+    let result = collections
+        .update(
+            table_name.to_string(),
+            id.to_string(),
+            json!({ "views": { "__op": "Unknown" } }),
+        )
+        .await;
+    assert!(result.is_err());
+
+    // concurrent increments don't lose writes
+    let updates = (0..20).map(|_| {
+        let collections = collections.clone();
+        let id = id.to_string();
+        tokio::spawn(async move {
+            collections
+                .update(
+                    table_name.to_string(),
+                    id,
+                    json!({ "views": { "__op": "Increment" } }),
+                )
+                .await
+                .unwrap();
+        })
+    });
+
+    for update in updates.collect::<Vec<_>>() {
+        update.await.unwrap();
+    }
+
+    let row = collections
+        .get(
+            table_name.to_string(),
+            id.to_string(),
+            Projection::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(row["views"].as_f64(), Some(34.0));
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use vivalaakam_seattle_collection::{value_to_string, Collections, QueryOptions, Storage};
+
+#[derive(Serialize, Deserialize)]
+struct CollectionResponse {
+    id: String,
+    name: String,
+    age: i32,
+}
+
+pub const TABLES: &[&str] = &["CollectionOrder"];
+
+pub async fn collection_order<T: Storage + Clone>(storage: T) {
+    let table_name = "CollectionOrder".to_string();
+
+    let collections = Collections::new(storage).await;
+
+    for i in [3, 1, 4, 0, 2] {
+        let result = collections
+            .insert(
+                table_name.to_string(),
+                json!({ "name": format!("test_{}", i % 2), "age": 10 + i }),
+            )
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    let list = |options: QueryOptions| {
+        let collections = collections.clone();
+        let table_name = table_name.to_string();
+
+        async move {
+            let result = collections.list(table_name, json!({}), options).await;
+
+            assert!(result.is_ok());
+
+            result
+                .unwrap()
+                .into_iter()
+                .map(|row| serde_json::from_str(&value_to_string(row)).unwrap())
+                .collect::<Vec<CollectionResponse>>()
+        }
+    };
+
+    // without options
+
+    let rows = list(QueryOptions::default()).await;
+
+    assert_eq!(rows.len(), 5);
+
+    // order by several keys
+
+    let rows = list(QueryOptions {
+        order: QueryOptions::parse_order("name,-age"),
+        ..Default::default()
+    })
+    .await;
+
+    let ages = rows.iter().map(|row| row.age).collect::<Vec<_>>();
+
+    assert_eq!(ages, vec![14, 12, 10, 13, 11]);
+    assert_eq!(rows[0].name, "test_0");
+
+    // limit and skip
+
+    let rows = list(QueryOptions {
+        order: QueryOptions::parse_order("-age"),
+        limit: Some(2),
+        skip: Some(1),
+        ..Default::default()
+    })
+    .await;
+
+    let ages = rows.iter().map(|row| row.age).collect::<Vec<_>>();
+
+    assert_eq!(ages, vec![13, 12]);
+
+    // skip only
+
+    let rows = list(QueryOptions {
+        order: QueryOptions::parse_order("age"),
+        skip: Some(3),
+        ..Default::default()
+    })
+    .await;
+
+    let ages = rows.iter().map(|row| row.age).collect::<Vec<_>>();
+
+    assert_eq!(ages, vec![13, 14]);
+}
@@ -0,0 +1,115 @@
+use serde_json::json;
+
+use vivalaakam_seattle_collection::{
+    Collections, FieldType, Pointer, Projection, QueryOptions, Storage,
+};
+
+pub const TABLES: &[&str] = &["PointerPosts", "PointerUsers", "PointerCompanies"];
+
+pub async fn collection_pointer<T: Storage>(storage: T) {
+    let collections = Collections::new(storage).await;
+
+    let company = collections
+        .insert("PointerCompanies".to_string(), json!({ "name": "acme" }))
+        .await
+        .unwrap();
+
+    let company = Pointer::new("PointerCompanies", company["id"].as_str().unwrap());
+
+    let author = collections
+        .insert(
+            "PointerUsers".to_string(),
+            json!({ "name": "john", "company": company.to_value() }),
+        )
+        .await
+        .unwrap();
+
+    let author = Pointer::new("PointerUsers", author["id"].as_str().unwrap());
+
+    for title in ["first", "second"] {
+        collections
+            .insert(
+                "PointerPosts".to_string(),
+                json!({ "title": title, "author": author.to_value() }),
+            )
+            .await
+            .unwrap();
+    }
+
+    let schema = collections.schema("PointerPosts".to_string()).unwrap();
+    let field = schema
+        .fields
+        .iter()
+        .find(|field| field.name == "author")
+        .unwrap();
+
+    assert_eq!(
+        field.field_type,
+        FieldType::Pointer {
+            target: "PointerUsers".to_string()
+        }
+    );
+
+    // a pointer into another collection doesn't fit the field
+    let result = collections
+        .insert(
+            "PointerPosts".to_string(),
+            json!({ "title": "third", "author": company.to_value() }),
+        )
+        .await;
+
+    assert!(result.is_err());
+
+    let posts = collections
+        .list(
+            "PointerPosts".to_string(),
+            json!({}),
+            QueryOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(posts.len(), 2);
+    assert_eq!(posts[0]["author"], author.to_value());
+
+    let posts = collections
+        .list_with_include(
+            "PointerPosts".to_string(),
+            json!({ "author": author.object_id }),
+            QueryOptions::default(),
+            &["author.company".to_string()],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(posts.len(), 2);
+
+    for post in &posts {
+        assert_eq!(post["author"]["__type"], "Object");
+        assert_eq!(post["author"]["name"], "john");
+        assert_eq!(post["author"]["company"]["name"], "acme");
+    }
+
+    let post = collections
+        .get_with_include(
+            "PointerPosts".to_string(),
+            posts[0]["id"].as_str().unwrap().to_string(),
+            Projection::default(),
+            &["author".to_string()],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(post["author"]["name"], "john");
+    assert_eq!(post["author"]["company"], company.to_value());
+
+    let count = collections
+        .count(
+            "PointerPosts".to_string(),
+            json!({ "author": author.to_value() }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(count, 2);
+}
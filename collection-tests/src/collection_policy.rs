@@ -0,0 +1,121 @@
+use serde_json::json;
+
+use vivalaakam_seattle_collection::{
+    CollectionError, CollectionField, Collections, FieldType, SchemaPolicy, Storage,
+};
+
+pub const TABLES: &[&str] = &["CollectionPolicy"];
+
+pub async fn collection_policy<T: Storage + Clone>(storage: T) {
+    let table_name = "CollectionPolicy";
+
+    let collections = Collections::new(storage.clone()).await;
+
+    let fields = vec![CollectionField {
+        name: "name".to_string(),
+        field_type: FieldType::String,
+        default: None,
+        required: None,
+        unique: None,
+        on_delete: None,
+    }];
+
+    // strict schemas change only with the master key
+    let result = collections
+        .create_schema(
+            table_name.to_string(),
+            fields.clone(),
+            Some(SchemaPolicy::Strict),
+            false,
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(CollectionError::SchemaAccessDenied { .. })
+    ));
+
+    collections
+        .create_schema(
+            table_name.to_string(),
+            fields,
+            Some(SchemaPolicy::Strict),
+            true,
+        )
+        .await
+        .unwrap();
+
+    let result = collections
+        .insert(table_name.to_string(), json!({ "name": "test", "age": 10 }))
+        .await;
+
+    match result {
+        Err(CollectionError::UnknownFields { fields, .. }) => {
+            assert_eq!(fields, vec!["age".to_string()])
+        }
+        result => panic!("unexpected result {result:?}"),
+    }
+
+    // the policy survives a reload from storage
+    let collections = Collections::new(storage).await;
+
+    let schema = collections.schema(table_name.to_string()).unwrap();
+    assert_eq!(schema.policy, Some(SchemaPolicy::Strict));
+
+    let result = collections
+        .insert(table_name.to_string(), json!({ "name": "test", "age": 10 }))
+        .await;
+    assert!(result.is_err());
+
+    let row = collections
+        .insert(table_name.to_string(), json!({ "name": "test" }))
+        .await
+        .unwrap();
+    assert_eq!(row["name"], "test");
+
+    let schema = collections
+        .set_policy(table_name.to_string(), Some(SchemaPolicy::AdminOnly), false)
+        .await;
+    assert!(matches!(
+        schema,
+        Err(CollectionError::SchemaAccessDenied { .. })
+    ));
+
+    let result = collections
+        .insert_field(
+            table_name.to_string(),
+            CollectionField {
+                name: "age".to_string(),
+                field_type: FieldType::Number,
+                default: None,
+                required: None,
+                unique: None,
+                on_delete: None,
+            },
+            false,
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(CollectionError::SchemaAccessDenied { .. })
+    ));
+
+    let result = collections
+        .set_policy(table_name.to_string(), None, false)
+        .await;
+    assert!(matches!(
+        result,
+        Err(CollectionError::SchemaAccessDenied { .. })
+    ));
+
+    let schema = collections
+        .set_policy(table_name.to_string(), None, true)
+        .await
+        .unwrap();
+    assert_eq!(schema.policy, None);
+
+    let row = collections
+        .insert(table_name.to_string(), json!({ "name": "test", "age": 10 }))
+        .await
+        .unwrap();
+    assert_eq!(row["age"].as_f64(), Some(10.0));
+}
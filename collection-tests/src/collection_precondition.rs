@@ -0,0 +1,75 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use serde_json::json;
+
+use vivalaakam_seattle_collection::{CollectionError, Collections, Precondition, Storage};
+
+pub const TABLES: &[&str] = &["CollectionPrecondition"];
+
+pub async fn collection_precondition<T: Storage>(storage: T) {
+    let table_name = "CollectionPrecondition";
+
+    let collections = Collections::new(storage).await;
+
+    let created = collections
+        .insert(table_name.to_string(), json!({ "name": "test" }))
+        .await
+        .unwrap();
+
+    let id = created["id"].as_str().unwrap().to_string();
+    let stale = Precondition::if_match(&Precondition::etag(&created).unwrap()).unwrap();
+
+    // keep updated_at apart from the insert
+    sleep(Duration::from_millis(5));
+
+    let updated = collections
+        .update_with_precondition(
+            table_name.to_string(),
+            id.to_string(),
+            json!({ "name": "first" }),
+            stale.clone(),
+            false,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(updated["name"], "first");
+
+    let result = collections
+        .update_with_precondition(
+            table_name.to_string(),
+            id.to_string(),
+            json!({ "name": "second" }),
+            stale.clone(),
+            false,
+        )
+        .await;
+
+    assert_eq!(
+        result,
+        Err(CollectionError::Conflict {
+            collection: table_name.to_string(),
+            id: id.to_string()
+        })
+    );
+
+    let result = collections
+        .delete_with_precondition(table_name.to_string(), id.to_string(), stale)
+        .await;
+
+    assert!(matches!(result, Err(CollectionError::Conflict { .. })));
+
+    let fresh = Precondition::if_match(&Precondition::etag(&updated).unwrap()).unwrap();
+
+    collections
+        .delete_with_precondition(table_name.to_string(), id.to_string(), fresh)
+        .await
+        .unwrap();
+
+    let result = collections
+        .get(table_name.to_string(), id.to_string(), Default::default())
+        .await;
+
+    assert!(result.is_err());
+}
@@ -0,0 +1,72 @@
+use serde_json::{json, Value};
+
+use vivalaakam_seattle_collection::{Collections, Projection, QueryOptions, Storage};
+
+fn keys(value: &Value) -> Vec<String> {
+    let mut keys = value
+        .as_object()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    keys.sort();
+    keys
+}
+
+pub const TABLES: &[&str] = &["CollectionProjection"];
+
+pub async fn collection_projection<T: Storage>(storage: T) {
+    let table_name = "CollectionProjection";
+
+    let collections = Collections::new(storage).await;
+
+    let created = collections
+        .insert(
+            table_name.to_string(),
+            json!({ "name": "test", "age": 10, "blob": { "large": [1, 2, 3] } }),
+        )
+        .await
+        .unwrap();
+
+    let row_id = created["id"].as_str().unwrap().to_string();
+
+    // get with keys
+
+    let result = collections
+        .get(
+            table_name.to_string(),
+            row_id.to_string(),
+            Projection {
+                keys: Some(vec!["name".to_string()]),
+                exclude_keys: None,
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(keys(&result), vec!["id", "name"]);
+    assert_eq!(result["name"], "test");
+
+    // list with exclude keys
+
+    let result = collections
+        .list(
+            table_name.to_string(),
+            json!({ "blob.large": { "$size": 3 } }),
+            QueryOptions {
+                projection: Projection {
+                    keys: None,
+                    exclude_keys: Some(vec!["blob".to_string(), "id".to_string()]),
+                },
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(result.len(), 1);
+    assert_eq!(
+        keys(&result[0]),
+        vec!["age", "created_at", "id", "name", "updated_at"]
+    );
+}
@@ -0,0 +1,186 @@
+use serde_json::json;
+
+use vivalaakam_seattle_collection::{
+    CollectionError, Collections, FieldType, Pointer, QueryOptions, Storage,
+};
+
+pub const TABLES: &[&str] = &["_Join:tags:RelationPosts", "RelationPosts", "RelationTags"];
+
+pub async fn collection_relation<T: Storage>(storage: T) {
+    let collections = Collections::new(storage).await;
+
+    let mut tags = vec![];
+    for name in ["rust", "sql", "web"] {
+        let tag = collections
+            .insert("RelationTags".to_string(), json!({ "name": name }))
+            .await
+            .unwrap();
+
+        tags.push(Pointer::new("RelationTags", tag["id"].as_str().unwrap()));
+    }
+
+    let post = collections
+        .insert(
+            "RelationPosts".to_string(),
+            json!({
+                "title": "first",
+                "tags": {
+                    "__op": "AddRelation",
+                    "objects": [tags[0].to_value(), tags[1].to_value()]
+                }
+            }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(post["tags"], Pointer::relation_value("RelationTags"));
+
+    let post = Pointer::new("RelationPosts", post["id"].as_str().unwrap());
+
+    collections
+        .insert(
+            "RelationPosts".to_string(),
+            json!({
+                "title": "second",
+                "tags": { "__op": "AddRelation", "objects": [tags[1].to_value()] }
+            }),
+        )
+        .await
+        .unwrap();
+
+    let schema = collections.schema("RelationPosts".to_string()).unwrap();
+    let field = schema
+        .fields
+        .iter()
+        .find(|field| field.name == "tags")
+        .unwrap();
+
+    assert_eq!(
+        field.field_type,
+        FieldType::Relation {
+            target: "RelationTags".to_string()
+        }
+    );
+
+    // relations only change through operations
+    let result = collections
+        .update(
+            "RelationPosts".to_string(),
+            post.object_id.to_string(),
+            json!({ "tags": [tags[2].to_value()] }),
+        )
+        .await;
+
+    assert!(result.is_err());
+
+    collections
+        .update(
+            "RelationPosts".to_string(),
+            post.object_id.to_string(),
+            json!({
+                "tags": {
+                    "__op": "RemoveRelation",
+                    "objects": [tags[0].to_value()]
+                }
+            }),
+        )
+        .await
+        .unwrap();
+
+    collections
+        .update(
+            "RelationPosts".to_string(),
+            post.object_id.to_string(),
+            json!({
+                "tags": { "__op": "AddRelation", "objects": [tags[2].to_value()] }
+            }),
+        )
+        .await
+        .unwrap();
+
+    let mut related = collections
+        .list(
+            "RelationTags".to_string(),
+            json!({ "$relatedTo": { "object": post.to_value(), "key": "tags" } }),
+            QueryOptions::default(),
+        )
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|tag| tag["name"].as_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    related.sort();
+
+    assert_eq!(related, vec!["sql", "web"]);
+
+    // the key has to name a relation of the object's schema to the queried collection
+    for (collection_name, key) in [
+        (
+            "RelationTags",
+            r#"tags:RelationPosts" WHERE FALSE) OR TRUE OR "id" IN (SELECT "relatedId" FROM "_Join:tags"#,
+        ),
+        ("RelationTags", "title"),
+        ("RelationPosts", "tags"),
+    ] {
+        let result = collections
+            .list(
+                collection_name.to_string(),
+                json!({ "$relatedTo": { "object": post.to_value(), "key": key } }),
+                QueryOptions::default(),
+            )
+            .await;
+
+        assert!(matches!(result, Err(CollectionError::InvalidQuery { .. })));
+    }
+
+    // the join table answers membership only
+    for query in [
+        json!({ "tags": { "$ne": tags[0].to_value() } }),
+        json!({ "tags": { "$nin": [tags[0].to_value()] } }),
+        json!({ "tags": { "$exists": true } }),
+        json!({ "$or": [{ "tags": { "$gt": tags[0].to_value() } }, { "title": "first" }] }),
+    ] {
+        let result = collections
+            .list("RelationPosts".to_string(), query, QueryOptions::default())
+            .await;
+
+        assert!(matches!(result, Err(CollectionError::InvalidQuery { .. })));
+    }
+
+    let count = collections
+        .count(
+            "RelationPosts".to_string(),
+            json!({ "tags": tags[1].to_value() }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(count, 2);
+
+    let posts = collections
+        .list(
+            "RelationPosts".to_string(),
+            json!({ "tags": { "$in": [tags[0].to_value(), tags[2].to_value()] } }),
+            QueryOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0]["title"], "first");
+
+    collections
+        .delete("RelationPosts".to_string(), post.object_id.to_string())
+        .await
+        .unwrap();
+
+    let count = collections
+        .count(
+            "RelationTags".to_string(),
+            json!({ "$relatedTo": { "object": post.to_value(), "key": "tags" } }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(count, 0);
+}
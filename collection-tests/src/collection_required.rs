@@ -1,18 +1,10 @@
-use std::env;
-
-use dotenv::dotenv;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tracing::{debug, info};
-use tracing_subscriber::filter::LevelFilter;
-
-use collection::CollectionField;
-use collection::FieldType;
-use collection::{Collections, Storage};
-use collection_postgres::StorePostgresql;
-use helpers::cleanup_table::cleanup_table;
 
-mod helpers;
+use vivalaakam_seattle_collection::CollectionField;
+use vivalaakam_seattle_collection::FieldType;
+use vivalaakam_seattle_collection::{Collections, Storage};
 
 #[derive(Serialize, Deserialize)]
 pub struct CollectionResponse {
@@ -20,25 +12,12 @@ pub struct CollectionResponse {
     name: String,
 }
 
-#[tokio::test]
-async fn collection_required() {
-    dotenv().ok();
-
-    tracing_subscriber::fmt()
-        .with_max_level(LevelFilter::DEBUG)
-        .with_test_writer()
-        .init();
-
-    let database_url =
-        env::var("POSTGRES_DATABASE_URL").expect("POSTGRES_DATABASE_URL must be set");
-
-    let instance = StorePostgresql::new(database_url.as_str()).await;
-
-    let table_name = "Collection1".to_string();
+pub const TABLES: &[&str] = &["CollectionRequired"];
 
-    cleanup_table(instance.get_pool(), &table_name).await;
+pub async fn collection_required<T: Storage>(storage: T) {
+    let table_name = "CollectionRequired".to_string();
 
-    let collections = Collections::new(instance).await;
+    let collections = Collections::new(storage).await;
 
     let collection = collections
         .storage
@@ -49,6 +28,8 @@ async fn collection_required() {
                 field_type: FieldType::String,
                 default: None,
                 required: Some(true),
+                unique: None,
+                on_delete: None,
             }],
         )
         .await
@@ -60,11 +41,11 @@ async fn collection_required() {
 
     debug!("created_default {created_default:?}");
 
-    assert_eq!(created_default.is_err(), true);
+    assert!(created_default.is_err());
 
     assert_eq!(
         format!("{created_default:?}"),
-        r#"Err(ValidateFields { collection: "Collection1", fields: ["name"] })"#
+        r#"Err(ValidateFields { collection: "CollectionRequired", fields: ["name"] })"#
     );
 
     let created_exists = collections
@@ -76,7 +57,7 @@ async fn collection_required() {
         )
         .await;
 
-    assert_eq!(created_exists.is_ok(), true);
+    assert!(created_exists.is_ok());
 
     let created_exists = created_exists.unwrap();
 
@@ -0,0 +1,156 @@
+use serde_json::json;
+
+use vivalaakam_seattle_collection::{
+    CollectionField, Collections, FieldType, Projection, QueryOptions, Storage,
+};
+
+pub const TABLES: &[&str] = &["TimeStampEvents", "TimeStampReminders"];
+
+pub async fn collection_timestamp<T: Storage>(storage: T) {
+    let collections = Collections::new(storage).await;
+
+    let table_name = "TimeStampEvents".to_string();
+
+    let event = collections
+        .insert(
+            table_name.to_string(),
+            json!({
+                "name": "tagged",
+                "starts_at": { "__type": "TimeStamp", "value": "2024-03-01T12:30:00Z" }
+            }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        event["starts_at"],
+        json!({ "__type": "TimeStamp", "value": "2024-03-01T12:30:00+00:00" })
+    );
+
+    let schema = collections.schema(table_name.to_string()).unwrap();
+    let field = schema
+        .fields
+        .iter()
+        .find(|field| field.name == "starts_at")
+        .unwrap();
+
+    assert_eq!(field.field_type, FieldType::TimeStamp);
+
+    let event = collections
+        .insert(
+            table_name.to_string(),
+            json!({ "name": "string", "starts_at": "2024-03-02T14:30:00+02:00" }),
+        )
+        .await
+        .unwrap();
+
+    let event = collections
+        .get(
+            table_name.to_string(),
+            event["id"].as_str().unwrap().to_string(),
+            Projection::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        event["starts_at"],
+        json!({ "__type": "TimeStamp", "value": "2024-03-02T12:30:00+00:00" })
+    );
+
+    // epoch milliseconds
+    collections
+        .insert(
+            table_name.to_string(),
+            json!({ "name": "epoch", "starts_at": 1709469000000i64 }),
+        )
+        .await
+        .unwrap();
+
+    collections
+        .insert(
+            table_name.to_string(),
+            json!({ "name": "parse", "starts_at": { "__type": "Date", "iso": "2024-03-04T12:30:00.000Z" } }),
+        )
+        .await
+        .unwrap();
+
+    let result = collections
+        .insert(
+            table_name.to_string(),
+            json!({ "name": "invalid", "starts_at": "yesterday" }),
+        )
+        .await;
+
+    assert!(result.is_err());
+
+    let events = collections
+        .list(
+            table_name.to_string(),
+            json!({ "starts_at": { "$gt": "2024-03-01T12:30:00Z", "$lte": 1709469000000i64 } }),
+            QueryOptions {
+                order: QueryOptions::parse_order("starts_at"),
+                ..QueryOptions::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    let names = events
+        .iter()
+        .map(|event| event["name"].clone())
+        .collect::<Vec<_>>();
+
+    assert_eq!(names, vec![json!("string"), json!("epoch")]);
+
+    let count = collections
+        .count(
+            table_name.to_string(),
+            json!({ "starts_at": { "$gte": { "__type": "TimeStamp", "value": "2024-03-03T12:30:00Z" } } }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(count, 2);
+
+    let count = collections
+        .count(
+            table_name.to_string(),
+            json!({ "starts_at": "2024-03-01T14:30:00+02:00" }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(count, 1);
+
+    // declared fields accept the same inputs
+    collections
+        .create_schema(
+            "TimeStampReminders".to_string(),
+            vec![CollectionField {
+                name: "remind_at".to_string(),
+                field_type: FieldType::TimeStamp,
+                default: None,
+                required: None,
+                unique: None,
+                on_delete: None,
+            }],
+            None,
+            false,
+        )
+        .await
+        .unwrap();
+
+    let reminder = collections
+        .insert(
+            "TimeStampReminders".to_string(),
+            json!({ "remind_at": 1709296200000i64 }),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        reminder["remind_at"],
+        json!({ "__type": "TimeStamp", "value": "2024-03-01T12:30:00+00:00" })
+    );
+}
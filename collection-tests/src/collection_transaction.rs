@@ -0,0 +1,81 @@
+use serde_json::json;
+
+use vivalaakam_seattle_collection::{Collections, Storage};
+
+pub const TABLES: &[&str] = &["CollectionTransaction"];
+
+pub async fn collection_transaction<T: Storage>(storage: T) {
+    let table_name = "CollectionTransaction";
+
+    let collections = Collections::new(storage).await;
+
+    collections
+        .insert(table_name.to_string(), json!({ "name": "a" }))
+        .await
+        .unwrap();
+
+    // rolled back writes and schema changes are discarded
+
+    let transaction = collections.begin().await.unwrap();
+
+    transaction
+        .insert(table_name.to_string(), json!({ "name": "b", "title": "b" }))
+        .await
+        .unwrap();
+
+    transaction
+        .bulk_update(
+            table_name.to_string(),
+            json!({ "name": "a" }),
+            json!({ "name": "c" }),
+        )
+        .await
+        .unwrap();
+
+    let count = transaction
+        .count(table_name.to_string(), json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(count, 2);
+
+    collections.rollback(transaction).await.unwrap();
+
+    let count = collections
+        .count(table_name.to_string(), json!({ "name": "a" }))
+        .await
+        .unwrap();
+
+    assert_eq!(count, 1);
+
+    let count = collections
+        .count(table_name.to_string(), json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(count, 1);
+
+    let schema = collections.schema(table_name.to_string()).unwrap();
+    assert!(!schema.fields.iter().any(|field| field.name == "title"));
+
+    // committed writes become visible
+
+    let transaction = collections.begin().await.unwrap();
+
+    transaction
+        .insert(table_name.to_string(), json!({ "name": "b", "title": "b" }))
+        .await
+        .unwrap();
+
+    collections.commit(transaction).await.unwrap();
+
+    let count = collections
+        .count(table_name.to_string(), json!({ "title": "b" }))
+        .await
+        .unwrap();
+
+    assert_eq!(count, 1);
+
+    let schema = collections.schema(table_name.to_string()).unwrap();
+    assert!(schema.fields.iter().any(|field| field.name == "title"));
+}
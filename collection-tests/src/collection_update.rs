@@ -1,16 +1,7 @@
-use std::env;
-
-use dotenv::dotenv;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tracing::info;
-use tracing_subscriber::filter::LevelFilter;
-
-use collection::{value_to_string, Collections};
-use collection_postgres::StorePostgresql;
-use helpers::cleanup_table::cleanup_table;
 
-mod helpers;
+use vivalaakam_seattle_collection::{value_to_string, Collections, Projection, Storage};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct CollectionResponse {
@@ -19,25 +10,12 @@ pub struct CollectionResponse {
     age: i32,
 }
 
-#[tokio::test]
-async fn collections_update() {
-    dotenv().ok();
-
-    tracing_subscriber::fmt()
-        .with_max_level(LevelFilter::DEBUG)
-        .with_test_writer()
-        .init();
-
-    let database_url =
-        env::var("POSTGRES_DATABASE_URL").expect("POSTGRES_DATABASE_URL must be set");
-
-    let instance = StorePostgresql::new(database_url.as_str()).await;
+pub const TABLES: &[&str] = &["CollectionUpdate"];
 
-    let table_name = "Collection1".to_string();
+pub async fn collection_update<T: Storage>(storage: T) {
+    let table_name = "CollectionUpdate".to_string();
 
-    cleanup_table(instance.get_pool(), &table_name).await;
-
-    let collections = Collections::new(instance).await;
+    let collections = Collections::new(storage).await;
 
     let created = collections
         .insert(
@@ -49,12 +27,10 @@ async fn collections_update() {
         )
         .await;
 
-    assert_eq!(created.is_ok(), true);
+    assert!(created.is_ok());
 
     let created = value_to_string(created.unwrap());
 
-    info!("created: {created}");
-
     let row = serde_json::from_str::<CollectionResponse>(&created).unwrap();
 
     assert_eq!(row.name, "test");
@@ -72,12 +48,10 @@ async fn collections_update() {
         )
         .await;
 
-    assert_eq!(updated.is_ok(), true);
+    assert!(updated.is_ok());
 
     let updated = value_to_string(updated.unwrap());
 
-    info!("updated: {updated}");
-
     let row = serde_json::from_str::<CollectionResponse>(updated.as_str()).unwrap();
 
     assert_eq!(row.name, "test2");
@@ -86,10 +60,14 @@ async fn collections_update() {
     // check
 
     let check = collections
-        .get(table_name.to_string(), row.id.to_string())
+        .get(
+            table_name.to_string(),
+            row.id.to_string(),
+            Projection::default(),
+        )
         .await;
 
-    assert_eq!(check.is_ok(), true);
+    assert!(check.is_ok());
 
     let check = value_to_string(check.unwrap());
 
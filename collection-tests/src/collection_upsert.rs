@@ -0,0 +1,165 @@
+use serde_json::json;
+
+use vivalaakam_seattle_collection::{
+    CollectionError, CollectionField, Collections, FieldType, Storage,
+};
+
+pub const TABLES: &[&str] = &["CollectionUpsert", "CollectionUpsertMissing"];
+
+pub async fn collection_upsert<T: Storage>(storage: T) {
+    let table_name = "CollectionUpsert";
+
+    let collections = Collections::new(storage).await;
+
+    // keys other than the id are declared unique through the schema
+    collections
+        .create_schema(
+            table_name.to_string(),
+            vec![CollectionField {
+                name: "name".to_string(),
+                field_type: FieldType::String,
+                default: None,
+                required: None,
+                unique: Some(true),
+                on_delete: None,
+            }],
+            None,
+            true,
+        )
+        .await
+        .unwrap();
+
+    // by id
+    let created = collections
+        .upsert(
+            table_name.to_string(),
+            json!({ "id": "first", "name": "test", "age": 10 }),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(created["id"], "first");
+    assert_eq!(created["name"], "test");
+
+    let updated = collections
+        .upsert(
+            table_name.to_string(),
+            json!({ "id": "first", "age": 11 }),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(updated["id"], "first");
+    assert_eq!(updated["name"], "test");
+    assert_eq!(updated["age"].as_f64(), Some(11.0));
+    assert_eq!(updated["created_at"], created["created_at"]);
+
+    // operations start from the stored value
+    for visits in [1.0, 2.0] {
+        let updated = collections
+            .upsert(
+                table_name.to_string(),
+                json!({ "id": "first", "visits": { "__op": "Increment", "amount": 1 } }),
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(updated["visits"].as_f64(), Some(visits));
+        assert_eq!(updated["age"].as_f64(), Some(11.0));
+    }
+
+    // by a unique key
+    let created = collections
+        .upsert(
+            table_name.to_string(),
+            json!({ "name": "external", "age": 20 }),
+            Some("name".to_string()),
+        )
+        .await
+        .unwrap();
+
+    let updated = collections
+        .upsert(
+            table_name.to_string(),
+            json!({ "name": "external", "age": 21 }),
+            Some("name".to_string()),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(updated["id"], created["id"]);
+    assert_eq!(updated["age"].as_f64(), Some(21.0));
+
+    let count = collections
+        .count(table_name.to_string(), json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(count, 2);
+
+    // unique values can't repeat
+    let result = collections
+        .insert(table_name.to_string(), json!({ "name": "external" }))
+        .await;
+
+    assert!(result.is_err());
+
+    let result = collections
+        .update(
+            table_name.to_string(),
+            "first".to_string(),
+            json!({ "name": "external" }),
+        )
+        .await;
+
+    assert!(result.is_err());
+
+    let count = collections
+        .count(table_name.to_string(), json!({ "name": "external" }))
+        .await
+        .unwrap();
+
+    assert_eq!(count, 1);
+
+    // only unique fields can be keys, a rejected upsert leaves the schema alone
+    let result = collections
+        .upsert(
+            table_name.to_string(),
+            json!({ "age": 20, "nickname": "al" }),
+            Some("age".to_string()),
+        )
+        .await;
+
+    assert!(matches!(result, Err(CollectionError::InvalidQuery { .. })));
+
+    let schema = collections.schema(table_name.to_string()).unwrap();
+    assert!(schema.get_field(&"nickname".to_string()).is_none());
+
+    let result = collections
+        .upsert(
+            "CollectionUpsertMissing".to_string(),
+            json!({ "sku": "a1" }),
+            Some("sku".to_string()),
+        )
+        .await;
+
+    assert!(matches!(result, Err(CollectionError::InvalidQuery { .. })));
+    assert!(collections
+        .schema("CollectionUpsertMissing".to_string())
+        .is_err());
+
+    let result = collections
+        .upsert(table_name.to_string(), json!({ "age": 30 }), None)
+        .await;
+
+    assert_eq!(
+        result,
+        Err(CollectionError::RequiredFields {
+            collection: table_name.to_string(),
+            fields: vec!["id".to_string()]
+        })
+    );
+}
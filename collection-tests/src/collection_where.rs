@@ -1,15 +1,7 @@
-use std::env;
-
-use dotenv::dotenv;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tracing_subscriber::filter::LevelFilter;
-
-use collection::{value_to_string, Collections};
-use collection_postgres::StorePostgresql;
-use helpers::cleanup_table::cleanup_table;
 
-mod helpers;
+use vivalaakam_seattle_collection::{value_to_string, Collections, QueryOptions, Storage};
 
 #[derive(Serialize, Deserialize)]
 struct CollectionResponse {
@@ -18,25 +10,12 @@ struct CollectionResponse {
     age: i32,
 }
 
-#[tokio::test]
-async fn collection_where() {
-    dotenv().ok();
-
-    tracing_subscriber::fmt()
-        .with_max_level(LevelFilter::DEBUG)
-        .with_test_writer()
-        .init();
-
-    let database_url =
-        env::var("POSTGRES_DATABASE_URL").expect("POSTGRES_DATABASE_URL must be set");
-
-    let instance = StorePostgresql::new(database_url.as_str()).await;
+pub const TABLES: &[&str] = &["CollectionWhereEq"];
 
+pub async fn collection_where<T: Storage>(storage: T) {
     let table_name = "CollectionWhereEq".to_string();
 
-    cleanup_table(instance.get_pool(), &table_name).await;
-
-    let collections = Collections::new(instance).await;
+    let collections = Collections::new(storage).await;
 
     let mut rows = vec![];
 
@@ -48,7 +27,7 @@ async fn collection_where() {
             )
             .await;
 
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
 
         let result = value_to_string(result.unwrap());
 
@@ -57,7 +36,7 @@ async fn collection_where() {
         rows.push(row);
     }
 
-    let test_row_0 = rows.get(0).unwrap();
+    let test_row_0 = rows.first().unwrap();
     let test_row_1 = rows.get(1).unwrap();
 
     // test eq
@@ -68,10 +47,11 @@ async fn collection_where() {
         .list(
             table_name.to_string(),
             serde_json::from_str(query_string.as_str()).unwrap(),
+            QueryOptions::default(),
         )
         .await;
 
-    assert_eq!(result_eq.is_ok(), true);
+    assert!(result_eq.is_ok());
 
     let result_eq = result_eq.unwrap();
     assert_eq!(result_eq.len(), 1);
@@ -81,7 +61,7 @@ async fn collection_where() {
         .map(|row| row.get("id").unwrap().as_str().unwrap().to_string())
         .collect::<Vec<String>>();
 
-    assert_eq!(rows_eq.get(0).unwrap(), &test_row_0.id);
+    assert_eq!(rows_eq.first().unwrap(), &test_row_0.id);
 
     // test neq
 
@@ -91,10 +71,11 @@ async fn collection_where() {
         .list(
             table_name.to_string(),
             serde_json::from_str(query_string.as_str()).unwrap(),
+            QueryOptions::default(),
         )
         .await;
 
-    assert_eq!(result_ne.is_ok(), true);
+    assert!(result_ne.is_ok());
 
     let result_ne = result_ne.unwrap();
     assert_eq!(result_ne.len(), 4);
@@ -104,7 +85,7 @@ async fn collection_where() {
         .map(|row| row.get("id").unwrap().as_str().unwrap().to_string())
         .collect::<Vec<String>>();
 
-    assert_eq!(rows_ne.contains(&test_row_0.id), false);
+    assert!(!rows_ne.contains(&test_row_0.id));
 
     // test in
 
@@ -117,10 +98,11 @@ async fn collection_where() {
         .list(
             table_name.to_string(),
             serde_json::from_str(query_string.as_str()).unwrap(),
+            QueryOptions::default(),
         )
         .await;
 
-    assert_eq!(result_in.is_ok(), true);
+    assert!(result_in.is_ok());
 
     let result_in = result_in.unwrap();
     assert_eq!(result_in.len(), 2);
@@ -130,8 +112,19 @@ async fn collection_where() {
         .map(|row| row.get("id").unwrap().as_str().unwrap().to_string())
         .collect::<Vec<String>>();
 
-    assert_eq!(rows_in.contains(&test_row_0.id), true);
-    assert_eq!(rows_in.contains(&test_row_1.id), true);
+    assert!(rows_in.contains(&test_row_0.id));
+    assert!(rows_in.contains(&test_row_1.id));
+
+    // test count
+
+    let count_in = collections
+        .count(
+            table_name.to_string(),
+            serde_json::from_str(query_string.as_str()).unwrap(),
+        )
+        .await;
+
+    assert_eq!(count_in, Ok(2));
 
     // test nin
 
@@ -144,10 +137,11 @@ async fn collection_where() {
         .list(
             table_name.to_string(),
             serde_json::from_str(query_string.as_str()).unwrap(),
+            QueryOptions::default(),
         )
         .await;
 
-    assert_eq!(result_nin.is_ok(), true);
+    assert!(result_nin.is_ok());
 
     let result_nin = result_nin.unwrap();
     assert_eq!(result_nin.len(), 3);
@@ -157,6 +151,21 @@ async fn collection_where() {
         .map(|row| row.get("id").unwrap().as_str().unwrap().to_string())
         .collect::<Vec<String>>();
 
-    assert_eq!(rows_nin.contains(&test_row_0.id), false);
-    assert_eq!(rows_nin.contains(&test_row_1.id), false);
+    assert!(!rows_nin.contains(&test_row_0.id));
+    assert!(!rows_nin.contains(&test_row_1.id));
+
+    // test gte / lt
+
+    let result_range = collections
+        .list(
+            table_name.to_string(),
+            json!({ "age": { "$gte": 11, "$lt": 13 } }),
+            QueryOptions::default(),
+        )
+        .await;
+
+    assert!(result_range.is_ok());
+
+    let result_range = result_range.unwrap();
+    assert_eq!(result_range.len(), 2);
 }
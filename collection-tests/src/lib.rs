@@ -0,0 +1,76 @@
+// storage conformance suite, every provider runs it through `storage_tests!`
+
+pub mod collection_array;
+pub mod collection_bulk;
+pub mod collection_default;
+pub mod collection_delete;
+pub mod collection_fields;
+pub mod collection_get;
+pub mod collection_guard;
+pub mod collection_implicit;
+pub mod collection_insert;
+pub mod collection_logical;
+pub mod collection_match;
+pub mod collection_nested;
+pub mod collection_numeric;
+pub mod collection_on_delete;
+pub mod collection_operations;
+pub mod collection_order;
+pub mod collection_pointer;
+pub mod collection_policy;
+pub mod collection_precondition;
+pub mod collection_projection;
+pub mod collection_relation;
+pub mod collection_required;
+pub mod collection_timestamp;
+pub mod collection_transaction;
+pub mod collection_update;
+pub mod collection_upsert;
+pub mod collection_where;
+
+// `$storage` is an async fn receiving the tables a test creates, referencing ones first,
+// and returning a storage without them
+#[macro_export]
+macro_rules! storage_tests {
+    ($storage:path) => {
+        $crate::storage_tests!(
+            $storage;
+            collection_array,
+            collection_bulk,
+            collection_default,
+            collection_delete,
+            collection_fields,
+            collection_get,
+            collection_guard,
+            collection_implicit,
+            collection_insert,
+            collection_logical,
+            collection_match,
+            collection_nested,
+            collection_numeric,
+            collection_on_delete,
+            collection_operations,
+            collection_order,
+            collection_pointer,
+            collection_policy,
+            collection_precondition,
+            collection_projection,
+            collection_relation,
+            collection_required,
+            collection_timestamp,
+            collection_transaction,
+            collection_update,
+            collection_upsert,
+            collection_where,
+        );
+    };
+    ($storage:path; $($name:ident),* $(,)?) => {
+        $(
+            #[tokio::test]
+            async fn $name() {
+                let storage = $storage($crate::$name::TABLES).await;
+                $crate::$name::$name(storage).await;
+            }
+        )*
+    };
+}
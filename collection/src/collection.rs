@@ -1,10 +1,13 @@
 use std::collections::HashMap;
 
 use chrono::{DateTime, Utc};
+use serde::Serialize;
 use serde_json::{Map, Value};
 
 use crate::collection_field::CollectionField;
+use crate::field_operation::FieldOperation;
 use crate::field_type::FieldType;
+use crate::schema_policy::SchemaPolicy;
 use crate::CollectionError;
 
 const ID_FIELD: &str = "id";
@@ -13,12 +16,13 @@ const UPDATED_AT_FIELD: &str = "updated_at";
 
 const SKIP_FIELDS: [&str; 3] = [ID_FIELD, CREATED_AT_FIELD, UPDATED_AT_FIELD];
 
-#[derive(Clone, Default)]
+#[derive(Clone, Default, PartialEq, Serialize)]
 pub struct Collection {
     pub name: String,
     pub fields: Vec<CollectionField>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub policy: Option<SchemaPolicy>,
 }
 
 impl Collection {
@@ -26,6 +30,16 @@ impl Collection {
         self.fields.iter().find(|f| f.name == *key)
     }
 
+    pub fn get_nested_field(&self, key: &str) -> Option<(&CollectionField, Vec<String>)> {
+        let (name, path) = key.split_once('.')?;
+        let field = self.get_field(&name.to_string())?;
+
+        (field.field_type == FieldType::Object).then(|| {
+            let path = path.split('.').map(|part| part.to_string()).collect();
+            (field, path)
+        })
+    }
+
     pub fn get_new_fields(&self, data: &Value) -> Vec<CollectionField> {
         let exists = self
             .fields
@@ -39,11 +53,20 @@ impl Collection {
             .filter(|(key, value)| {
                 !exists.contains(key) && !SKIP_FIELDS.contains(&key.as_str()) && !value.is_null()
             })
-            .map(|(key, value)| CollectionField {
-                name: key.to_string(),
-                default: None,
-                required: None,
-                field_type: value.clone().into(),
+            .filter_map(|(key, value)| {
+                let field_type = match FieldOperation::from_value(value) {
+                    Some(operation) => operation.ok()?.field_type()?,
+                    None => value.clone().into(),
+                };
+
+                Some(CollectionField {
+                    name: key.to_string(),
+                    default: None,
+                    required: None,
+                    unique: None,
+                    on_delete: None,
+                    field_type,
+                })
             })
             .collect::<Vec<_>>()
     }
@@ -65,14 +88,16 @@ impl Collection {
             .as_object()
             .unwrap()
             .iter()
-            .map(|(key, value)| {
-                exists
+            .filter_map(|(key, value)| match FieldOperation::from_value(value) {
+                Some(Ok(operation)) => exists
+                    .get(key)
+                    .and_then(|field| (!operation.is_maybe_exists(field)).then_some(key)),
+                Some(Err(_)) => Some(key),
+                None => exists
                     .get(key)
-                    .map(|field| (!field.is_maybe_exists(value)).then_some(key))
-                    .unwrap()
+                    .and_then(|field| (!field.is_maybe_exists(value)).then_some(key)),
             })
-            .filter(|key| key.is_some())
-            .map(|key| key.unwrap().to_string())
+            .map(|key| key.to_string())
             .collect::<Vec<_>>();
 
         fields
@@ -97,9 +122,9 @@ impl Collection {
                 }
             })
             .filter(|f| {
-                data.get(f.name.to_string())
-                    .unwrap_or(&Value::Null)
-                    .is_null()
+                let value = data.get(f.name.to_string()).unwrap_or(&Value::Null);
+                value.is_null()
+                    || FieldOperation::from_value(value) == Some(Ok(FieldOperation::Delete))
             })
             .map(|field| field.name.to_string())
             .collect::<Vec<_>>();
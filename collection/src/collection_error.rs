@@ -16,6 +16,21 @@ pub enum CollectionError {
         collection: String,
         fields: Vec<String>,
     },
+    #[error("Invalid query: {collection} - {error}")]
+    InvalidQuery { collection: String, error: String },
+    #[error("Unknown fields: {collection} - {fields:?}")]
+    UnknownFields {
+        collection: String,
+        fields: Vec<String>,
+    },
+    #[error("Schema changes require the master key: {collection}")]
+    SchemaAccessDenied { collection: String },
+    #[error("Value was changed by another writer: {collection} - {id}")]
+    Conflict { collection: String, id: String },
+    #[error("Value is referenced by other data: {collection} - {id}")]
+    Referenced { collection: String, id: String },
+    #[error("Invalid reference {reference}: {error}")]
+    InvalidReference { reference: String, error: String },
     #[error("Required field data: {collection} - {fields:?}")]
     RequiredFields {
         collection: String,
@@ -1,11 +1,15 @@
-use crate::FieldType;
+use crate::{FieldType, OnDelete};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct CollectionField {
     pub name: String,
     pub field_type: FieldType,
     pub default: Option<Value>,
     pub required: Option<bool>,
+    // no two rows share a value, upserts may match on it
+    pub unique: Option<bool>,
+    // pointer fields only
+    pub on_delete: Option<OnDelete>,
 }
@@ -2,16 +2,30 @@ use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 use anyhow::Result;
-use serde_json::Value;
+use serde_json::{json, Value};
 
 use crate::collection_error::CollectionError;
-use crate::where_attr::Where;
-use crate::{Collection, Storage};
+use crate::include::{include_paths, values_at_path};
+use crate::pointer::Pointer;
+use crate::precondition::Precondition;
+use crate::projection::Projection;
+use crate::query_options::QueryOptions;
+use crate::schema_policy::SchemaPolicy;
+use crate::where_query::WhereQuery;
+use crate::{
+    Collection, CollectionField, FieldOperation, FieldType, Storage, StorageError,
+    MAX_DECIMAL_PRECISION,
+};
+
+const ID_FIELD: &str = "id";
+const SYSTEM_FIELDS: [&str; 3] = [ID_FIELD, "created_at", "updated_at"];
 
 #[derive(Clone)]
 pub struct Collections<T> {
     pub collections: Arc<Mutex<HashMap<String, Collection>>>,
     pub storage: T,
+    pub policy: SchemaPolicy,
+    pub implicit_collections: bool,
 }
 
 impl<T> Collections<T>
@@ -28,6 +42,14 @@ where
             .unwrap()
             .insert(key.to_string(), value);
     }
+
+    pub fn unset_collection(&self, key: &String) {
+        self.collections.lock().unwrap().remove(key);
+    }
+
+    pub fn collection_policy(&self, collection: &Collection) -> SchemaPolicy {
+        collection.policy.unwrap_or(self.policy)
+    }
 }
 
 impl<T> Collections<T>
@@ -45,17 +67,141 @@ where
         Self {
             collections: Arc::new(Mutex::new(HashMap::from_iter(collections))),
             storage,
+            policy: SchemaPolicy::default(),
+            implicit_collections: true,
         }
     }
 
+    pub fn with_policy(mut self, policy: SchemaPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_implicit_collections(mut self, implicit_collections: bool) -> Self {
+        self.implicit_collections = implicit_collections;
+        self
+    }
+
     pub fn get_storage(&self) -> &T {
         &self.storage
     }
 
+    // schema changes made inside the transaction stay in its own cache until commit
+    pub async fn begin(&self) -> Result<Self, CollectionError> {
+        let storage = self
+            .storage
+            .begin()
+            .await
+            .map_err(|error| CollectionError::StorageError { error })?;
+
+        let collections = self.collections.lock().unwrap().clone();
+
+        Ok(Self {
+            collections: Arc::new(Mutex::new(collections)),
+            storage,
+            policy: self.policy,
+            implicit_collections: self.implicit_collections,
+        })
+    }
+
+    pub async fn commit(&self, transaction: Self) -> Result<(), CollectionError> {
+        transaction
+            .storage
+            .commit()
+            .await
+            .map_err(|error| CollectionError::StorageError { error })?;
+
+        let collections = self
+            .storage
+            .get_collections()
+            .await
+            .map_err(|error| CollectionError::StorageError { error })?
+            .into_iter()
+            .map(|collection| (collection.name.clone(), collection));
+
+        *self.collections.lock().unwrap() = HashMap::from_iter(collections);
+
+        Ok(())
+    }
+
+    pub async fn rollback(&self, transaction: Self) -> Result<(), CollectionError> {
+        transaction
+            .storage
+            .rollback()
+            .await
+            .map_err(|error| CollectionError::StorageError { error })
+    }
+
+    fn new_fields(
+        &self,
+        collection: &Collection,
+        data: &Value,
+        master: bool,
+    ) -> Result<Vec<CollectionField>, CollectionError> {
+        let fields = collection.get_new_fields(data);
+
+        if fields.is_empty()
+            || self
+                .collection_policy(collection)
+                .allows_implicit_fields(master)
+        {
+            return Ok(fields);
+        }
+
+        Err(CollectionError::UnknownFields {
+            collection: collection.name.to_string(),
+            fields: fields.into_iter().map(|field| field.name).collect(),
+        })
+    }
+
+    async fn insert_new_fields(
+        &self,
+        collection: Collection,
+        data: &Value,
+        master: bool,
+    ) -> Result<Collection, CollectionError> {
+        let fields = self.new_fields(&collection, data, master)?;
+
+        if fields.is_empty() {
+            return Ok(collection);
+        }
+
+        let mut collection = collection;
+
+        for field in fields {
+            collection = self
+                .storage
+                .insert_field_to_collection(&collection, field)
+                .await
+                .map_err(|error| CollectionError::StorageError { error })?;
+        }
+
+        self.set_collection(&collection.name.to_string(), collection.clone());
+
+        Ok(collection)
+    }
+
     pub async fn insert(
         &self,
         collection_name: String,
         data: Value,
+    ) -> Result<Value, CollectionError> {
+        self.insert_with_access(collection_name, data, false).await
+    }
+
+    pub async fn insert_as_master(
+        &self,
+        collection_name: String,
+        data: Value,
+    ) -> Result<Value, CollectionError> {
+        self.insert_with_access(collection_name, data, true).await
+    }
+
+    async fn insert_with_access(
+        &self,
+        collection_name: String,
+        data: Value,
+        master: bool,
     ) -> Result<Value, CollectionError> {
         if !data.is_object() {
             return Err(CollectionError::CollectionInputData {
@@ -63,47 +209,132 @@ where
             });
         }
 
-        let mut collection = self.get_collection(&collection_name);
+        let data = FieldOperation::apply_to_empty(data);
 
-        if collection.is_none() {
-            let schema = self
-                .storage
-                .create_collection(collection_name.to_string(), vec![])
-                .await;
+        let collection = self
+            .collection_for_insert(&collection_name, &data, master)
+            .await?;
 
-            if schema.is_err() {
-                return Err(CollectionError::StorageError {
-                    error: schema.err().unwrap(),
-                });
+        collection.validate(&data)?;
+
+        let data = collection.default_values(data);
+
+        collection.required_values(&data, false)?;
+
+        self.storage
+            .insert_data_into_collection(&collection, data)
+            .await
+            .map_err(|error| CollectionError::StorageError { error })
+    }
+
+    async fn collection_for_insert(
+        &self,
+        collection_name: &String,
+        data: &Value,
+        master: bool,
+    ) -> Result<Collection, CollectionError> {
+        let collection = match self.get_collection(collection_name) {
+            Some(collection) => collection,
+            None if !self.implicit_collections => {
+                return Err(CollectionError::CollectionNotFound {
+                    collection: collection_name.to_string(),
+                })
             }
+            None => {
+                let collection = Collection {
+                    name: collection_name.to_string(),
+                    ..Default::default()
+                };
 
-            let mut coll = schema.unwrap();
+                self.new_fields(&collection, data, master)?;
 
-            for field in coll.get_new_fields(&data) {
-                coll = self
+                let collection = self
                     .storage
-                    .insert_field_to_collection(&coll, field)
+                    .create_collection(collection_name.to_string(), vec![])
                     .await
-                    .unwrap();
+                    .map_err(|error| CollectionError::StorageError { error })?;
+
+                self.set_collection(collection_name, collection.clone());
+
+                collection
             }
+        };
+
+        self.insert_new_fields(collection, data, master).await
+    }
+
+    pub async fn upsert(
+        &self,
+        collection_name: String,
+        data: Value,
+        key: Option<String>,
+    ) -> Result<Value, CollectionError> {
+        self.upsert_with_access(collection_name, data, key, false)
+            .await
+    }
 
-            self.set_collection(&collection_name, coll);
+    pub async fn upsert_as_master(
+        &self,
+        collection_name: String,
+        data: Value,
+        key: Option<String>,
+    ) -> Result<Value, CollectionError> {
+        self.upsert_with_access(collection_name, data, key, true)
+            .await
+    }
 
-            collection = self.get_collection(&collection_name);
+    // inserts the document or updates the row with the same key, `id` or a unique field
+    async fn upsert_with_access(
+        &self,
+        collection_name: String,
+        data: Value,
+        key: Option<String>,
+        master: bool,
+    ) -> Result<Value, CollectionError> {
+        if !data.is_object() {
+            return Err(CollectionError::CollectionInputData {
+                collection: collection_name,
+            });
+        }
+
+        let key = key.unwrap_or(ID_FIELD.to_string());
+
+        // checked on the existing schema, a rejected upsert must not create fields
+        let unique = key == ID_FIELD
+            || self
+                .get_collection(&collection_name)
+                .and_then(|collection| collection.get_field(&key).cloned())
+                .is_some_and(|field| field.unique.unwrap_or_default());
+
+        if !unique {
+            return Err(CollectionError::InvalidQuery {
+                collection: collection_name,
+                error: format!("upsert key {key} must be a unique field"),
+            });
         }
 
-        let collection = collection.ok_or(CollectionError::CollectionNotFound {
-            collection: collection_name,
-        })?;
+        let collection = self
+            .collection_for_insert(&collection_name, &data, master)
+            .await?;
 
         collection.validate(&data)?;
 
-        let data = collection.default_values(data);
+        // operations start from an empty value on insert and from the stored one on update
+        let insert = FieldOperation::apply_to_empty(data.clone());
 
-        collection.required_values(&data, false)?;
+        if insert.get(&key).is_none_or(Value::is_null) {
+            return Err(CollectionError::RequiredFields {
+                collection: collection_name,
+                fields: vec![key],
+            });
+        }
+
+        let insert = collection.default_values(insert);
+
+        collection.required_values(&insert, false)?;
 
         self.storage
-            .insert_data_into_collection(&collection, data)
+            .upsert_data_into_collection(&collection, insert, &key, &data)
             .await
             .map_err(|error| CollectionError::StorageError { error })
     }
@@ -113,6 +344,40 @@ where
         collection_name: String,
         collection_id: String,
         data: Value,
+    ) -> Result<Value, CollectionError> {
+        self.update_with_precondition(
+            collection_name,
+            collection_id,
+            data,
+            Precondition::default(),
+            false,
+        )
+        .await
+    }
+
+    pub async fn update_as_master(
+        &self,
+        collection_name: String,
+        collection_id: String,
+        data: Value,
+    ) -> Result<Value, CollectionError> {
+        self.update_with_precondition(
+            collection_name,
+            collection_id,
+            data,
+            Precondition::default(),
+            true,
+        )
+        .await
+    }
+
+    pub async fn update_with_precondition(
+        &self,
+        collection_name: String,
+        collection_id: String,
+        data: Value,
+        precondition: Precondition,
+        master: bool,
     ) -> Result<Value, CollectionError> {
         if !data.is_object() {
             return Err(CollectionError::CollectionInputData {
@@ -120,64 +385,288 @@ where
             });
         }
 
-        let mut collection =
+        let collection =
             self.get_collection(&collection_name)
                 .ok_or(CollectionError::CollectionNotFound {
                     collection: collection_name.to_string(),
                 })?;
 
-        let fields = collection.get_new_fields(&data);
+        self.validate_guard(&collection, &precondition)?;
 
-        if !fields.is_empty() {
-            let mut coll = collection.clone();
+        let collection = self.insert_new_fields(collection, &data, master).await?;
 
-            for field in fields {
-                coll = self
-                    .storage
-                    .insert_field_to_collection(&coll, field)
-                    .await
-                    .unwrap();
-            }
+        collection.validate(&data)?;
+
+        collection.required_values(&data, true)?;
 
-            self.set_collection(&collection_name, coll);
+        self.storage
+            .update_data_into_collection(&collection, collection_id, data, &precondition)
+            .await
+            .map_err(Self::write_error)
+    }
 
-            collection = self.get_collection(&collection_name).ok_or_else(|| {
-                CollectionError::CollectionNotFound {
-                    collection: collection_name.to_string(),
-                }
+    pub async fn delete(
+        &self,
+        collection_name: String,
+        collection_id: String,
+    ) -> Result<Value, CollectionError> {
+        self.delete_with_precondition(collection_name, collection_id, Precondition::default())
+            .await
+    }
+
+    pub async fn delete_with_precondition(
+        &self,
+        collection_name: String,
+        collection_id: String,
+        precondition: Precondition,
+    ) -> Result<Value, CollectionError> {
+        let collection =
+            self.get_collection(&collection_name)
+                .ok_or(CollectionError::CollectionNotFound {
+                    collection: collection_name,
+                })?;
+
+        self.validate_guard(&collection, &precondition)?;
+
+        self.storage
+            .delete_data_from_collection(&collection, collection_id, &precondition)
+            .await
+            .map_err(Self::write_error)
+    }
+
+    fn parse_query(
+        &self,
+        collection: &Collection,
+        query: &Value,
+    ) -> Result<WhereQuery, CollectionError> {
+        let collection_query =
+            WhereQuery::parse(query).map_err(|error| CollectionError::InvalidQuery {
+                collection: collection.name.to_string(),
+                error,
             })?;
+
+        self.validate_query(collection, &collection_query)?;
+
+        Ok(collection_query)
+    }
+
+    // a filter that matches every row or names unknown fields would
+    // silently touch the whole collection
+    fn parse_bulk_query(
+        &self,
+        collection: &Collection,
+        query: &Value,
+    ) -> Result<WhereQuery, CollectionError> {
+        let collection_query = self.parse_query(collection, query)?;
+
+        let invalid = |error: String| CollectionError::InvalidQuery {
+            collection: collection.name.to_string(),
+            error,
+        };
+
+        if collection_query.is_empty() {
+            return Err(invalid("where must not be empty".to_string()));
+        }
+
+        let unknown = Self::unknown_query_keys(collection, &collection_query);
+
+        if !unknown.is_empty() {
+            return Err(invalid(format!("unknown fields {unknown:?}")));
+        }
+
+        Ok(collection_query)
+    }
+
+    // a guard on a field the schema doesn't have can never hold
+    fn validate_guard(
+        &self,
+        collection: &Collection,
+        precondition: &Precondition,
+    ) -> Result<(), CollectionError> {
+        self.validate_query(collection, &precondition.query)?;
+
+        let unknown = Self::unknown_query_keys(collection, &precondition.query);
+
+        unknown
+            .is_empty()
+            .then_some(())
+            .ok_or_else(|| CollectionError::InvalidQuery {
+                collection: collection.name.to_string(),
+                error: format!("unknown fields {unknown:?}"),
+            })
+    }
+
+    // keys matching neither a field nor a path inside an object field
+    fn unknown_query_keys(collection: &Collection, query: &WhereQuery) -> Vec<String> {
+        let mut keys = query
+            .fields
+            .keys()
+            .filter(|key| {
+                collection.get_field(key).is_none() && collection.get_nested_field(key).is_none()
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for query in query.and.iter().chain(&query.or).chain(&query.nor) {
+            keys.extend(Self::unknown_query_keys(collection, query));
+        }
+
+        keys.sort();
+        keys
+    }
+
+    // `$relatedTo` names a join table, so it has to be a relation of a known schema
+    // pointing at this collection. relation fields are matched through the same tables
+    fn validate_query(
+        &self,
+        collection: &Collection,
+        query: &WhereQuery,
+    ) -> Result<(), CollectionError> {
+        if let Some(related_to) = &query.related_to {
+            let relation = FieldType::Relation {
+                target: collection.name.to_string(),
+            };
+
+            let is_relation = self
+                .get_collection(&related_to.object.class_name)
+                .is_some_and(|owner| {
+                    owner
+                        .get_field(&related_to.key)
+                        .is_some_and(|field| field.field_type == relation)
+                });
+
+            if !is_relation {
+                return Err(CollectionError::InvalidQuery {
+                    collection: collection.name.to_string(),
+                    error: format!(
+                        "{} of {} is not a relation to {}",
+                        related_to.key, related_to.object.class_name, collection.name
+                    ),
+                });
+            }
         }
 
+        for (key, value) in &query.fields {
+            let is_relation = collection
+                .get_field(key)
+                .is_some_and(|field| field.field_type.is_relation());
+
+            if is_relation && !value.is_membership() {
+                return Err(CollectionError::InvalidQuery {
+                    collection: collection.name.to_string(),
+                    error: format!("relation {key} only supports $eq and $in"),
+                });
+            }
+        }
+
+        query
+            .and
+            .iter()
+            .chain(&query.or)
+            .chain(&query.nor)
+            .try_for_each(|query| self.validate_query(collection, query))
+    }
+
+    fn write_error(error: StorageError) -> CollectionError {
+        match error {
+            StorageError::Conflict { collection, id } => {
+                CollectionError::Conflict { collection, id }
+            }
+            StorageError::Referenced { collection, id } => {
+                CollectionError::Referenced { collection, id }
+            }
+            error => CollectionError::StorageError { error },
+        }
+    }
+
+    pub async fn bulk_update(
+        &self,
+        collection_name: String,
+        query: Value,
+        data: Value,
+    ) -> Result<u64, CollectionError> {
+        self.bulk_update_with_access(collection_name, query, data, false)
+            .await
+    }
+
+    pub async fn bulk_update_as_master(
+        &self,
+        collection_name: String,
+        query: Value,
+        data: Value,
+    ) -> Result<u64, CollectionError> {
+        self.bulk_update_with_access(collection_name, query, data, true)
+            .await
+    }
+
+    async fn bulk_update_with_access(
+        &self,
+        collection_name: String,
+        query: Value,
+        data: Value,
+        master: bool,
+    ) -> Result<u64, CollectionError> {
+        if !data.is_object() {
+            return Err(CollectionError::CollectionInputData {
+                collection: collection_name,
+            });
+        }
+
+        let collection =
+            self.get_collection(&collection_name)
+                .ok_or(CollectionError::CollectionNotFound {
+                    collection: collection_name.to_string(),
+                })?;
+
+        let collection_query = self.parse_bulk_query(&collection, &query)?;
+
+        let collection = self.insert_new_fields(collection, &data, master).await?;
+
         collection.validate(&data)?;
 
         collection.required_values(&data, true)?;
 
         self.storage
-            .update_data_into_collection(&collection, collection_id, data)
+            .bulk_update_data_into_collection(&collection, collection_query, data)
             .await
             .map_err(|error| CollectionError::StorageError { error })
     }
-    pub async fn delete(
+
+    pub async fn bulk_delete(
         &self,
         collection_name: String,
-        collection_id: String,
-    ) -> Result<Value, CollectionError> {
+        query: Value,
+    ) -> Result<u64, CollectionError> {
         let collection =
             self.get_collection(&collection_name)
                 .ok_or(CollectionError::CollectionNotFound {
                     collection: collection_name,
                 })?;
 
+        let collection_query = self.parse_bulk_query(&collection, &query)?;
+
         self.storage
-            .delete_data_from_collection(&collection, collection_id)
+            .bulk_delete_data_from_collection(&collection, collection_query)
             .await
-            .map_err(|error| CollectionError::StorageError { error })
+            .map_err(Self::write_error)
     }
 
     pub async fn get(
         &self,
         collection_name: String,
         collection_id: String,
+        projection: Projection,
+    ) -> Result<Value, CollectionError> {
+        self.get_with_include(collection_name, collection_id, projection, &[])
+            .await
+    }
+
+    pub async fn get_with_include(
+        &self,
+        collection_name: String,
+        collection_id: String,
+        projection: Projection,
+        include: &[String],
     ) -> Result<Value, CollectionError> {
         let collection =
             self.get_collection(&collection_name)
@@ -185,16 +674,33 @@ where
                     collection: collection_name,
                 })?;
 
-        self.storage
-            .get_data_from_collection(&collection, collection_id)
+        let mut row = self
+            .storage
+            .get_data_from_collection(&collection, collection_id, &projection)
             .await
-            .map_err(|error| CollectionError::StorageError { error })
+            .map_err(|error| CollectionError::StorageError { error })?;
+
+        self.include_pointers(&mut row, include).await?;
+
+        Ok(row)
     }
 
     pub async fn list(
         &self,
         collection_name: String,
         query: Value,
+        options: QueryOptions,
+    ) -> Result<Vec<Value>, CollectionError> {
+        self.list_with_include(collection_name, query, options, &[])
+            .await
+    }
+
+    pub async fn list_with_include(
+        &self,
+        collection_name: String,
+        query: Value,
+        options: QueryOptions,
+        include: &[String],
     ) -> Result<Vec<Value>, CollectionError> {
         let collection =
             self.get_collection(&collection_name)
@@ -202,33 +708,329 @@ where
                     collection: collection_name,
                 })?;
 
-        let fields = query
-            .as_object()
-            .unwrap()
-            .iter()
-            .map(|(key, value)| {
-                if !value.is_object() {
-                    (
-                        key.to_string(),
-                        Where {
-                            eq: Some(value.clone()),
-                            ..Default::default()
-                        },
-                    )
-                } else {
-                    (
-                        key.to_string(),
-                        serde_json::from_value(value.clone()).unwrap(),
-                    )
+        let collection_query = self.parse_query(&collection, &query)?;
+
+        let rows = self
+            .storage
+            .list_data_from_collection(&collection, collection_query, &options)
+            .await
+            .map_err(|error| CollectionError::StorageError { error })?;
+
+        let mut rows = Value::Array(rows);
+        self.include_pointers(&mut rows, include).await?;
+
+        match rows {
+            Value::Array(rows) => Ok(rows),
+            _ => Ok(vec![]),
+        }
+    }
+
+    // one lookup per include path and target collection, however many rows point there
+    async fn include_pointers(
+        &self,
+        rows: &mut Value,
+        include: &[String],
+    ) -> Result<(), CollectionError> {
+        for path in include_paths(include) {
+            let mut targets: HashMap<String, Vec<String>> = HashMap::new();
+
+            for pointer in values_at_path(rows, &path)
+                .into_iter()
+                .filter_map(|value| Pointer::from_value(value))
+            {
+                targets
+                    .entry(pointer.class_name)
+                    .or_default()
+                    .push(pointer.object_id);
+            }
+
+            let mut objects = HashMap::new();
+
+            for (class_name, ids) in targets {
+                let Some(collection) = self.get_collection(&class_name) else {
+                    continue;
+                };
+
+                let query =
+                    WhereQuery::parse(&json!({ ID_FIELD: { "$in": ids } })).map_err(|error| {
+                        CollectionError::InvalidQuery {
+                            collection: class_name.to_string(),
+                            error,
+                        }
+                    })?;
+
+                let values = self
+                    .storage
+                    .list_data_from_collection(&collection, query, &QueryOptions::default())
+                    .await
+                    .map_err(|error| CollectionError::StorageError { error })?;
+
+                for value in values {
+                    if let Some(id) = value.get(ID_FIELD).and_then(|id| id.as_str()) {
+                        objects.insert((class_name.to_string(), id.to_string()), value);
+                    }
                 }
-            })
-            .collect::<Vec<_>>();
+            }
+
+            // dangling pointers are left as they are
+            for value in values_at_path(rows, &path) {
+                let Some(pointer) = Pointer::from_value(value) else {
+                    continue;
+                };
 
-        let collection_query = HashMap::from_iter(fields);
+                let key = (
+                    pointer.class_name.to_string(),
+                    pointer.object_id.to_string(),
+                );
+
+                if let Some(object) = objects.get(&key) {
+                    *value = pointer.object_value(object.clone());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn count(
+        &self,
+        collection_name: String,
+        query: Value,
+    ) -> Result<i64, CollectionError> {
+        let collection =
+            self.get_collection(&collection_name)
+                .ok_or(CollectionError::CollectionNotFound {
+                    collection: collection_name,
+                })?;
+
+        let collection_query = self.parse_query(&collection, &query)?;
 
         self.storage
-            .list_data_from_collection(&collection, collection_query)
+            .count_data_from_collection(&collection, collection_query)
             .await
             .map_err(|error| CollectionError::StorageError { error })
     }
+
+    pub fn schemas(&self) -> Vec<Collection> {
+        let mut schemas = self
+            .collections
+            .lock()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        schemas.sort_by(|left, right| left.name.cmp(&right.name));
+        schemas
+    }
+
+    pub fn schema(&self, collection_name: String) -> Result<Collection, CollectionError> {
+        self.get_collection(&collection_name)
+            .ok_or(CollectionError::CollectionNotFound {
+                collection: collection_name,
+            })
+    }
+
+    fn check_schema_access(
+        &self,
+        collection_name: &str,
+        policy: SchemaPolicy,
+        master: bool,
+    ) -> Result<(), CollectionError> {
+        policy.allows_schema_changes(master).then_some(()).ok_or(
+            CollectionError::SchemaAccessDenied {
+                collection: collection_name.to_string(),
+            },
+        )
+    }
+
+    pub async fn create_schema(
+        &self,
+        collection_name: String,
+        fields: Vec<CollectionField>,
+        policy: Option<SchemaPolicy>,
+        master: bool,
+    ) -> Result<Collection, CollectionError> {
+        self.check_schema_access(&collection_name, self.policy, master)?;
+        self.check_schema_access(&collection_name, policy.unwrap_or(self.policy), master)?;
+
+        // without implicit collections new ones are the master's call
+        if !self.implicit_collections && !master {
+            return Err(CollectionError::SchemaAccessDenied {
+                collection: collection_name,
+            });
+        }
+
+        if self.get_collection(&collection_name).is_some() {
+            return Err(CollectionError::StorageError {
+                error: StorageError::CollectionCreate {
+                    collection: collection_name,
+                },
+            });
+        }
+
+        Self::validate_schema_fields(&collection_name, &fields)?;
+
+        let mut collection = self
+            .storage
+            .create_collection(collection_name.to_string(), fields)
+            .await
+            .map_err(|error| CollectionError::StorageError { error })?;
+
+        if policy.is_some() {
+            collection = self
+                .storage
+                .set_collection_policy(&collection, policy)
+                .await
+                .map_err(|error| CollectionError::StorageError { error })?;
+        }
+
+        self.set_collection(&collection_name, collection.clone());
+
+        Ok(collection)
+    }
+
+    pub async fn set_policy(
+        &self,
+        collection_name: String,
+        policy: Option<SchemaPolicy>,
+        master: bool,
+    ) -> Result<Collection, CollectionError> {
+        let collection = self.schema(collection_name.to_string())?;
+
+        self.check_schema_access(
+            &collection_name,
+            self.collection_policy(&collection),
+            master,
+        )?;
+        self.check_schema_access(&collection_name, policy.unwrap_or(self.policy), master)?;
+
+        let collection = self
+            .storage
+            .set_collection_policy(&collection, policy)
+            .await
+            .map_err(|error| CollectionError::StorageError { error })?;
+
+        self.set_collection(&collection_name, collection.clone());
+
+        Ok(collection)
+    }
+
+    pub async fn insert_field(
+        &self,
+        collection_name: String,
+        field: CollectionField,
+        master: bool,
+    ) -> Result<Collection, CollectionError> {
+        let collection = self.schema(collection_name.to_string())?;
+
+        self.check_schema_access(
+            &collection_name,
+            self.collection_policy(&collection),
+            master,
+        )?;
+
+        Self::validate_schema_fields(&collection_name, std::slice::from_ref(&field))?;
+
+        let collection = self
+            .storage
+            .insert_field_to_collection(&collection, field)
+            .await
+            .map_err(|error| CollectionError::StorageError { error })?;
+
+        self.set_collection(&collection_name, collection.clone());
+
+        Ok(collection)
+    }
+
+    pub async fn remove_field(
+        &self,
+        collection_name: String,
+        field_name: String,
+        master: bool,
+    ) -> Result<Collection, CollectionError> {
+        let collection = self.schema(collection_name.to_string())?;
+
+        self.check_schema_access(
+            &collection_name,
+            self.collection_policy(&collection),
+            master,
+        )?;
+
+        let field = collection
+            .get_field(&field_name)
+            .filter(|field| !SYSTEM_FIELDS.contains(&field.name.as_str()))
+            .cloned()
+            .ok_or(CollectionError::ValidateFields {
+                collection: collection_name.to_string(),
+                fields: vec![field_name],
+            })?;
+
+        let collection = self
+            .storage
+            .remove_field_from_collection(&collection, field)
+            .await
+            .map_err(|error| CollectionError::StorageError { error })?;
+
+        self.set_collection(&collection_name, collection.clone());
+
+        Ok(collection)
+    }
+
+    pub async fn remove_schema(
+        &self,
+        collection_name: String,
+        master: bool,
+    ) -> Result<Value, CollectionError> {
+        let collection = self.schema(collection_name.to_string())?;
+
+        self.check_schema_access(
+            &collection_name,
+            self.collection_policy(&collection),
+            master,
+        )?;
+
+        self.storage
+            .remove_collection(&collection)
+            .await
+            .map_err(|error| CollectionError::StorageError { error })?;
+
+        self.unset_collection(&collection_name);
+
+        Ok(Value::Null)
+    }
+
+    fn validate_schema_fields(
+        collection_name: &str,
+        fields: &[CollectionField],
+    ) -> Result<(), CollectionError> {
+        let invalid = fields
+            .iter()
+            .filter(|field| {
+                field.name.is_empty()
+                    || field.name.contains(['.', '"'])
+                    || !field
+                        .field_type
+                        .is_maybe_exists(field.default.as_ref().unwrap_or(&Value::Null))
+                    || (field.on_delete.is_some()
+                        && !matches!(field.field_type, FieldType::Pointer { .. }))
+                    || matches!(field.field_type, FieldType::Decimal { precision, scale }
+                        if precision == 0 || precision > MAX_DECIMAL_PRECISION || scale > precision)
+                    || (field.unique.unwrap_or_default()
+                        && matches!(
+                            field.field_type,
+                            FieldType::Array | FieldType::Object | FieldType::Relation { .. }
+                        ))
+            })
+            .map(|field| field.name.to_string())
+            .collect::<Vec<_>>();
+
+        invalid
+            .is_empty()
+            .then_some(())
+            .ok_or(CollectionError::ValidateFields {
+                collection: collection_name.to_string(),
+                fields: invalid,
+            })
+    }
 }
@@ -0,0 +1,126 @@
+use std::fmt;
+
+use rust_decimal::RoundingStrategy;
+use serde_json::{json, Value};
+
+const TYPE_KEY: &str = "__type";
+const DECIMAL_TYPE: &str = "Decimal";
+
+// digits a 96 bit mantissa can hold
+pub const MAX_DECIMAL_PRECISION: u32 = 28;
+
+// exact decimal number, json numbers can't carry it without loss
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Decimal(pub rust_decimal::Decimal);
+
+impl Decimal {
+    // accepts `-12.50`, `1e-7` and the like, anything beyond 28 digits is rejected
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = match value.contains(['e', 'E']) {
+            true => rust_decimal::Decimal::from_scientific(value),
+            false => rust_decimal::Decimal::from_str_exact(value),
+        };
+
+        value.ok().map(Self)
+    }
+
+    // numbers, strings and `{"__type": "Decimal", "value": "..."}`
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::Number(number) => Self::parse(&number.to_string()),
+            Value::String(value) => Self::parse(value),
+            Value::Object(object) if object.get(TYPE_KEY) == Some(&json!(DECIMAL_TYPE)) => object
+                .get("value")
+                .and_then(|v| v.as_str())
+                .and_then(Self::parse),
+            _ => None,
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        json!({
+            TYPE_KEY: DECIMAL_TYPE,
+            "value": self.to_string(),
+        })
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.0.scale()
+    }
+
+    // digits left of the point
+    pub fn integer_digits(&self) -> u32 {
+        let integer = self.0.trunc().abs();
+
+        match integer.is_zero() {
+            true => 0,
+            false => integer.to_string().len() as u32,
+        }
+    }
+
+    // rounds half away from zero to exactly `scale` digits, as postgres numeric does
+    pub fn round(&self, scale: u32) -> Self {
+        let mut value = self
+            .0
+            .round_dp_with_strategy(scale, RoundingStrategy::MidpointAwayFromZero);
+        value.rescale(scale);
+
+        if value.is_zero() {
+            value.set_sign_positive(true);
+        }
+
+        Self(value)
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::Decimal;
+
+    #[test]
+    fn parse_and_round() {
+        let value = Decimal::parse("0012.345").unwrap();
+        assert_eq!(value.to_string(), "12.345");
+        assert_eq!(value.round(2).to_string(), "12.35");
+        assert_eq!(value.round(5).to_string(), "12.34500");
+        assert_eq!(value.integer_digits(), 2);
+        assert_eq!(
+            Decimal::parse("-9.995").unwrap().round(2).to_string(),
+            "-10.00"
+        );
+        assert_eq!(
+            Decimal::parse("-0.001").unwrap().round(2).to_string(),
+            "0.00"
+        );
+        assert_eq!(Decimal::parse("0.05").unwrap().integer_digits(), 0);
+        assert_eq!(Decimal::parse("1e-7").unwrap().to_string(), "0.0000001");
+        assert_eq!(Decimal::parse("1.5E3").unwrap().to_string(), "1500");
+        assert!(Decimal::parse("12a").is_none());
+        assert!(Decimal::parse(".").is_none());
+
+        // huge exponents and digit counts are rejected without expanding them
+        assert!(Decimal::parse("1e2147483647").is_none());
+        assert!(Decimal::parse("1e300000000").is_none());
+        assert!(Decimal::parse(&"9".repeat(40)).is_none());
+
+        assert!(Decimal::parse("-2").unwrap() < Decimal::parse("-1.5").unwrap());
+        assert!(Decimal::parse("10.01").unwrap() > Decimal::parse("9.999").unwrap());
+        assert_eq!(Decimal::parse("1.50"), Decimal::parse("1.5"));
+
+        let value = Decimal::from_value(&json!({"__type": "Decimal", "value": "99.90"})).unwrap();
+        assert_eq!(
+            value.to_value(),
+            json!({"__type": "Decimal", "value": "99.90"})
+        );
+        assert_eq!(Decimal::from_value(&json!(0.1)).unwrap().to_string(), "0.1");
+        assert!(Decimal::from_value(&json!(1e300)).is_none());
+    }
+}
@@ -0,0 +1,251 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Number, Value};
+
+use crate::decimal::Decimal;
+use crate::pointer::Pointer;
+use crate::FieldType;
+
+const OPERATION_KEY: &str = "__op";
+
+fn default_amount() -> Number {
+    Number::from(1)
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "__op")]
+pub enum FieldOperation {
+    Increment {
+        // kept as written, integers and decimals add without going through a float
+        #[serde(default = "default_amount")]
+        amount: Number,
+    },
+    Add {
+        objects: Vec<Value>,
+    },
+    AddUnique {
+        objects: Vec<Value>,
+    },
+    Remove {
+        objects: Vec<Value>,
+    },
+    Delete,
+    AddRelation {
+        objects: Vec<Value>,
+    },
+    RemoveRelation {
+        objects: Vec<Value>,
+    },
+}
+
+impl FieldOperation {
+    pub fn is_operation(value: &Value) -> bool {
+        value.get(OPERATION_KEY).is_some()
+    }
+
+    // `None` for plain values, `Some(Err)` for malformed operations
+    pub fn from_value(value: &Value) -> Option<Result<Self, String>> {
+        Self::is_operation(value)
+            .then(|| serde_json::from_value(value.clone()).map_err(|error| error.to_string()))
+    }
+
+    pub fn field_type(&self) -> Option<FieldType> {
+        match self {
+            FieldOperation::Increment { .. } => Some(FieldType::Number),
+            FieldOperation::Add { .. }
+            | FieldOperation::AddUnique { .. }
+            | FieldOperation::Remove { .. } => Some(FieldType::Array),
+            FieldOperation::Delete => None,
+            FieldOperation::AddRelation { objects }
+            | FieldOperation::RemoveRelation { objects } => objects
+                .iter()
+                .find_map(Pointer::from_value)
+                .map(|pointer| FieldType::Relation {
+                    target: pointer.class_name,
+                }),
+        }
+    }
+
+    pub fn is_maybe_exists(&self, field_type: &FieldType) -> bool {
+        match (self, field_type) {
+            (
+                FieldOperation::AddRelation { objects }
+                | FieldOperation::RemoveRelation { objects },
+                FieldType::Relation { target },
+            ) => objects.iter().all(|object| {
+                Pointer::from_value(object).is_some_and(|pointer| pointer.class_name == *target)
+            }),
+            (FieldOperation::Delete, FieldType::Relation { .. }) => false,
+            (FieldOperation::Increment { amount }, FieldType::Integer | FieldType::BigInt) => {
+                FieldType::integer_value(&Value::Number(amount.clone())).is_some()
+            }
+            (FieldOperation::Increment { amount }, FieldType::Decimal { .. }) => {
+                Decimal::from_value(&Value::Number(amount.clone())).is_some()
+            }
+            (FieldOperation::Increment { .. }, field_type) => field_type.is_numeric(),
+            _ => self
+                .field_type()
+                .is_none_or(|operation_type| operation_type == *field_type),
+        }
+    }
+
+    pub fn is_relation(&self) -> bool {
+        matches!(
+            self,
+            FieldOperation::AddRelation { .. } | FieldOperation::RemoveRelation { .. }
+        )
+    }
+
+    // ids of the pointers passed to AddRelation and RemoveRelation
+    pub fn relation_ids(&self) -> Vec<String> {
+        match self {
+            FieldOperation::AddRelation { objects }
+            | FieldOperation::RemoveRelation { objects } => objects
+                .iter()
+                .filter_map(Pointer::from_value)
+                .map(|pointer| pointer.object_id)
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    pub fn unique_objects(objects: &[Value]) -> Vec<Value> {
+        objects.iter().fold(vec![], |mut unique, object| {
+            if !unique.contains(object) {
+                unique.push(object.clone());
+            }
+            unique
+        })
+    }
+
+    pub fn apply(&self, current: &Value) -> Value {
+        let mut elements = current.as_array().cloned().unwrap_or_default();
+
+        match self {
+            FieldOperation::Increment { amount } => {
+                let amount = Value::Number(amount.clone());
+                let integer = match current {
+                    Value::Null => Some(0),
+                    current => current.as_i64(),
+                };
+
+                if let Some(sum) = integer
+                    .zip(amount.as_i64())
+                    .and_then(|(current, amount)| current.checked_add(amount))
+                {
+                    return json!(sum);
+                }
+
+                // stored decimals are tagged objects
+                if let Some(sum) = current
+                    .is_object()
+                    .then(|| Decimal::from_value(current))
+                    .flatten()
+                    .zip(Decimal::from_value(&amount))
+                    .and_then(|(current, amount)| current.0.checked_add(amount.0))
+                {
+                    return Decimal(sum).to_value();
+                }
+
+                json!(current.as_f64().unwrap_or_default() + amount.as_f64().unwrap_or_default())
+            }
+            FieldOperation::Add { objects } => {
+                elements.extend(objects.iter().cloned());
+                Value::Array(elements)
+            }
+            FieldOperation::AddUnique { objects } => {
+                for object in Self::unique_objects(objects) {
+                    if !elements.contains(&object) {
+                        elements.push(object);
+                    }
+                }
+                Value::Array(elements)
+            }
+            FieldOperation::Remove { objects } => {
+                elements.retain(|element| !objects.contains(element));
+                Value::Array(elements)
+            }
+            FieldOperation::Delete => Value::Null,
+            FieldOperation::AddRelation { .. } => {
+                for id in self.relation_ids() {
+                    let id = Value::String(id);
+                    if !elements.contains(&id) {
+                        elements.push(id);
+                    }
+                }
+                Value::Array(elements)
+            }
+            FieldOperation::RemoveRelation { .. } => {
+                let ids = self.relation_ids();
+                elements.retain(|element| {
+                    !element
+                        .as_str()
+                        .is_some_and(|id| ids.contains(&id.to_string()))
+                });
+                Value::Array(elements)
+            }
+        }
+    }
+
+    // resolves operations against missing values, used for inserts.
+    // relation operations are left to the storage, which keeps relations apart from rows
+    pub fn apply_to_empty(data: Value) -> Value {
+        match data {
+            Value::Object(data) => {
+                Value::Object(Map::from_iter(data.into_iter().map(|(key, value)| {
+                    match Self::from_value(&value) {
+                        Some(Ok(operation)) if !operation.is_relation() => {
+                            (key, operation.apply(&Value::Null))
+                        }
+                        _ => (key, value),
+                    }
+                })))
+            }
+            data => data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::FieldOperation;
+
+    #[test]
+    fn apply_operations() {
+        let increment = FieldOperation::from_value(&json!({"__op": "Increment"}))
+            .unwrap()
+            .unwrap();
+        assert_eq!(increment.apply(&json!(2)), json!(3));
+        assert_eq!(increment.apply(&json!(2.5)), json!(3.5));
+        assert_eq!(increment.apply(&json!(null)), json!(1));
+        assert_eq!(
+            increment.apply(&json!(9007199254740993i64)),
+            json!(9007199254740994i64)
+        );
+
+        let increment = FieldOperation::from_value(&json!({"__op": "Increment", "amount": 0.001}))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            increment.apply(&json!({"__type": "Decimal", "value": "1234567890123456.001"})),
+            json!({"__type": "Decimal", "value": "1234567890123456.002"})
+        );
+
+        let add_unique =
+            FieldOperation::from_value(&json!({"__op": "AddUnique", "objects": [1, 2, 2, 3]}))
+                .unwrap()
+                .unwrap();
+        assert_eq!(add_unique.apply(&json!([3, 4])), json!([3, 4, 1, 2]));
+
+        let remove = FieldOperation::from_value(&json!({"__op": "Remove", "objects": [1]}))
+            .unwrap()
+            .unwrap();
+        assert_eq!(remove.apply(&json!([1, 2, 1])), json!([2]));
+
+        assert!(FieldOperation::from_value(&json!({"__op": "Unknown"}))
+            .unwrap()
+            .is_err());
+        assert!(FieldOperation::from_value(&json!({"name": "value"})).is_none());
+    }
+}
@@ -1,36 +1,102 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-#[derive(Clone, Serialize, Deserialize, Eq, PartialEq)]
+use crate::decimal::{Decimal, MAX_DECIMAL_PRECISION};
+use crate::pointer::Pointer;
+use crate::time_stamp::TimeStamp;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub enum FieldType {
     String,
     Number,
+    // 32 bit
+    Integer,
+    // 64 bit
+    BigInt,
+    // `precision` digits in total, up to 28, `scale` of them after the point.
+    // inferred decimals keep the scale of the first value
+    Decimal { precision: u32, scale: u32 },
     Boolean,
     Array,
     Object,
     TimeStamp,
+    Pointer { target: String },
+    // kept in a join table, rows only carry a marker
+    Relation { target: String },
 }
 
 impl FieldType {
     pub fn is_maybe_exists(&self, value: &Value) -> bool {
         match self {
-            FieldType::String | FieldType::TimeStamp => value.is_string() || value.is_null(),
+            FieldType::String => value.is_string() || value.is_null(),
+            FieldType::TimeStamp => TimeStamp::from_value(value).is_some() || value.is_null(),
             FieldType::Number => value.is_number() || value.is_null(),
+            FieldType::Integer => {
+                Self::integer_value(value).is_some_and(|v| i32::try_from(v).is_ok())
+                    || value.is_null()
+            }
+            FieldType::BigInt => Self::integer_value(value).is_some() || value.is_null(),
+            FieldType::Decimal { precision, scale } => {
+                Decimal::from_value(value)
+                    .is_some_and(|v| v.round(*scale).integer_digits() + scale <= *precision)
+                    || value.is_null()
+            }
             FieldType::Boolean => value.is_boolean() || value.is_null(),
             FieldType::Array => value.is_array() || value.is_null(),
             FieldType::Object => value.is_object() || value.is_null(),
+            FieldType::Pointer { target } => {
+                Pointer::from_value(value).is_some_and(|pointer| pointer.class_name == *target)
+                    || value.is_null()
+            }
+            // relations change through AddRelation and RemoveRelation only
+            FieldType::Relation { .. } => value.is_null(),
         }
     }
+
+    // integral floats like `2.0` count as integers
+    pub fn integer_value(value: &Value) -> Option<i64> {
+        value.as_i64().or_else(|| {
+            value
+                .as_f64()
+                .filter(|v| v.fract() == 0.0 && v.abs() < i64::MAX as f64)
+                .map(|v| v as i64)
+        })
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            FieldType::Number | FieldType::Integer | FieldType::BigInt | FieldType::Decimal { .. }
+        )
+    }
+
+    pub fn is_relation(&self) -> bool {
+        matches!(self, FieldType::Relation { .. })
+    }
 }
 
 impl From<Value> for FieldType {
     fn from(value: Value) -> Self {
         match value {
             Value::String(_) => FieldType::String,
-            Value::Number(_) => FieldType::Number,
+            Value::Number(number) => match number.as_i64() {
+                Some(v) if i32::try_from(v).is_ok() => FieldType::Integer,
+                Some(_) => FieldType::BigInt,
+                None => FieldType::Number,
+            },
             Value::Bool(_) => FieldType::Boolean,
             Value::Array(_) => FieldType::Array,
-            Value::Object(_) => FieldType::Object,
+            Value::Object(_) if TimeStamp::is_tagged(&value) => FieldType::TimeStamp,
+            Value::Object(_) => match (Pointer::from_value(&value), Decimal::from_value(&value)) {
+                (Some(pointer), _) => FieldType::Pointer {
+                    target: pointer.class_name,
+                },
+                (_, Some(decimal)) => FieldType::Decimal {
+                    precision: MAX_DECIMAL_PRECISION,
+                    scale: decimal.scale(),
+                },
+                _ => FieldType::Object,
+            },
             Value::Null => FieldType::Object,
         }
     }
@@ -0,0 +1,73 @@
+use serde_json::Value;
+
+// `author.company` needs `author` resolved first, so parents come before their children
+pub fn include_paths(include: &[String]) -> Vec<Vec<String>> {
+    let mut paths = include
+        .iter()
+        .flat_map(|path| {
+            let parts = path
+                .split('.')
+                .map(|part| part.trim().to_string())
+                .collect::<Vec<_>>();
+
+            (1..=parts.len())
+                .map(|len| parts[..len].to_vec())
+                .collect::<Vec<_>>()
+        })
+        .filter(|path| path.iter().all(|part| !part.is_empty()))
+        .collect::<Vec<_>>();
+
+    paths.sort_by_key(|path| path.len());
+    paths.dedup();
+    paths
+}
+
+// arrays along the path are walked element by element
+pub fn values_at_path<'a>(value: &'a mut Value, path: &[String]) -> Vec<&'a mut Value> {
+    match (value, path.split_first()) {
+        (Value::Array(values), _) => values
+            .iter_mut()
+            .flat_map(|value| values_at_path(value, path))
+            .collect(),
+        (value, None) => vec![value],
+        (Value::Object(object), Some((key, path))) => match object.get_mut(key) {
+            Some(value) => values_at_path(value, path),
+            None => vec![],
+        },
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{include_paths, values_at_path};
+
+    #[test]
+    fn include_paths_test() {
+        let paths = include_paths(&["author.company".to_string(), "editor".to_string()]);
+
+        assert_eq!(
+            paths,
+            vec![
+                vec!["author".to_string()],
+                vec!["editor".to_string()],
+                vec!["author".to_string(), "company".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn values_at_path_test() {
+        let mut value = json!([
+            {"author": {"company": 1}},
+            {"author": [{"company": 2}, {"name": "a"}]},
+        ]);
+
+        let path = vec!["author".to_string(), "company".to_string()];
+        let values = values_at_path(&mut value, &path);
+
+        assert_eq!(values, vec![&mut json!(1), &mut json!(2)]);
+    }
+}
@@ -2,20 +2,41 @@ pub use crate::collection::Collection;
 pub use crate::collection_error::CollectionError;
 pub use crate::collection_field::CollectionField;
 pub use crate::collections::Collections;
+pub use crate::decimal::{Decimal, MAX_DECIMAL_PRECISION};
+pub use crate::field_operation::FieldOperation;
 pub use crate::field_type::FieldType;
 pub use crate::make_id::make_id;
+pub use crate::on_delete::OnDelete;
+pub use crate::pointer::Pointer;
+pub use crate::precondition::Precondition;
+pub use crate::projection::Projection;
+pub use crate::query_options::{OrderBy, OrderDirection, QueryOptions};
+pub use crate::schema_policy::SchemaPolicy;
 pub use crate::storage::Storage;
 pub use crate::storage_error::StorageError;
+pub use crate::time_stamp::TimeStamp;
 pub use crate::value_to_string::value_to_string;
 pub use crate::where_attr::Where;
+pub use crate::where_query::{RelatedTo, WhereQuery};
 
 mod collection;
 mod collection_error;
 mod collection_field;
 mod collections;
+mod decimal;
+mod field_operation;
 mod field_type;
+mod include;
 mod make_id;
+mod on_delete;
+mod pointer;
+mod precondition;
+mod projection;
+mod query_options;
+mod schema_policy;
 mod storage;
 mod storage_error;
+mod time_stamp;
 mod value_to_string;
 mod where_attr;
+mod where_query;
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+// what happens to pointers when the object they point to is deleted
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnDelete {
+    // the delete fails while the object is referenced
+    Restrict,
+    // referencing objects are deleted too
+    Cascade,
+    // referencing pointers are cleared
+    SetNull,
+}
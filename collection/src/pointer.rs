@@ -0,0 +1,80 @@
+use serde_json::{json, Value};
+
+const TYPE_KEY: &str = "__type";
+const POINTER_TYPE: &str = "Pointer";
+const OBJECT_TYPE: &str = "Object";
+const RELATION_TYPE: &str = "Relation";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pointer {
+    pub class_name: String,
+    pub object_id: String,
+}
+
+impl Pointer {
+    pub fn new(class_name: &str, object_id: &str) -> Self {
+        Self {
+            class_name: class_name.to_string(),
+            object_id: object_id.to_string(),
+        }
+    }
+
+    // `{"__type": "Pointer", "className": ..., "objectId": ...}`
+    pub fn from_value(value: &Value) -> Option<Self> {
+        if value.get(TYPE_KEY)?.as_str()? != POINTER_TYPE {
+            return None;
+        }
+
+        Some(Self::new(
+            value.get("className")?.as_str()?,
+            value.get("objectId")?.as_str()?,
+        ))
+    }
+
+    pub fn to_value(&self) -> Value {
+        json!({
+            TYPE_KEY: POINTER_TYPE,
+            "className": self.class_name,
+            "objectId": self.object_id,
+        })
+    }
+
+    // stands in for relation fields in rows
+    pub fn relation_value(class_name: &str) -> Value {
+        json!({
+            TYPE_KEY: RELATION_TYPE,
+            "className": class_name,
+        })
+    }
+
+    // the row a pointer resolves to on include
+    pub fn object_value(&self, row: Value) -> Value {
+        match row {
+            Value::Object(mut object) => {
+                object.insert(TYPE_KEY.to_string(), json!(OBJECT_TYPE));
+                object.insert("className".to_string(), json!(self.class_name));
+                Value::Object(object)
+            }
+            row => row,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::Pointer;
+
+    #[test]
+    fn pointer_value() {
+        let value = json!({"__type": "Pointer", "className": "Users", "objectId": "u1"});
+        let pointer = Pointer::from_value(&value).unwrap();
+
+        assert_eq!(pointer, Pointer::new("Users", "u1"));
+        assert_eq!(pointer.to_value(), value);
+
+        assert!(Pointer::from_value(&json!({"className": "Users", "objectId": "u1"})).is_none());
+        assert!(Pointer::from_value(&json!("u1")).is_none());
+    }
+}
@@ -0,0 +1,93 @@
+use chrono::{DateTime, TimeZone, Utc};
+use serde_json::{Map, Value};
+
+use crate::WhereQuery;
+
+const UPDATED_AT_FIELD: &str = "updated_at";
+
+// conditions the stored row has to satisfy for a write to apply
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Precondition {
+    pub updated_at: Option<DateTime<Utc>>,
+    pub query: WhereQuery,
+}
+
+impl Precondition {
+    pub fn is_empty(&self) -> bool {
+        self.updated_at.is_none() && self.query.is_empty()
+    }
+
+    // `If-Match` value, `*` matches any existing row
+    pub fn if_match(etag: &str) -> Option<Self> {
+        let etag = etag.trim();
+
+        if etag == "*" {
+            return Some(Self::default());
+        }
+
+        let micros = etag
+            .trim_start_matches("W/")
+            .trim_matches('"')
+            .parse::<i64>()
+            .ok()?;
+
+        Some(Self {
+            updated_at: Some(Utc.timestamp_micros(micros).single()?),
+            ..Default::default()
+        })
+    }
+
+    pub fn etag(row: &Value) -> Option<String> {
+        row.as_object()
+            .and_then(Self::updated_at)
+            .map(|updated_at| format!(r#""{}""#, updated_at.timestamp_micros()))
+    }
+
+    // the where guard depends on the backend and is checked there
+    pub fn matches_etag(&self, row: &Map<String, Value>) -> bool {
+        self.updated_at.is_none_or(|expected| {
+            Self::updated_at(row).is_some_and(|updated_at| {
+                updated_at.timestamp_micros() == expected.timestamp_micros()
+            })
+        })
+    }
+
+    fn updated_at(row: &Map<String, Value>) -> Option<DateTime<Utc>> {
+        let value = row.get(UPDATED_AT_FIELD)?;
+        let value = value.get("value").unwrap_or(value).as_str()?;
+
+        DateTime::parse_from_rfc3339(value)
+            .ok()
+            .map(|value| value.with_timezone(&Utc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::Precondition;
+
+    #[test]
+    fn etag_round_trip() {
+        let row = json!({
+            "updated_at": { "__type": "TimeStamp", "value": "2024-01-02T03:04:05.123456+00:00" }
+        });
+
+        let etag = Precondition::etag(&row).unwrap();
+        assert_eq!(etag, r#""1704164645123456""#);
+
+        let precondition = Precondition::if_match(&etag).unwrap();
+        assert!(precondition.matches_etag(row.as_object().unwrap()));
+        assert!(!precondition.matches_etag(
+            json!({
+                "updated_at": { "__type": "TimeStamp", "value": "2024-01-02T03:04:05.123457+00:00" }
+            })
+            .as_object()
+            .unwrap()
+        ));
+
+        assert_eq!(Precondition::if_match("*"), Some(Precondition::default()));
+        assert_eq!(Precondition::if_match("nope"), None);
+    }
+}
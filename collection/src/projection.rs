@@ -0,0 +1,95 @@
+use crate::collection::Collection;
+
+const ID_FIELD: &str = "id";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Projection {
+    pub keys: Option<Vec<String>>,
+    pub exclude_keys: Option<Vec<String>>,
+}
+
+impl Projection {
+    pub fn parse_keys(keys: &str) -> Vec<String> {
+        keys.split(',')
+            .map(|key| key.trim())
+            .filter(|key| !key.is_empty())
+            .map(|key| key.to_string())
+            .collect()
+    }
+
+    pub fn apply(&self, collection: &Collection) -> Collection {
+        let fields = collection
+            .fields
+            .iter()
+            .filter(|field| {
+                let included = self
+                    .keys
+                    .as_ref()
+                    .is_none_or(|keys| keys.contains(&field.name));
+                let excluded = self
+                    .exclude_keys
+                    .as_ref()
+                    .is_some_and(|keys| keys.contains(&field.name));
+
+                field.name == ID_FIELD || (included && !excluded)
+            })
+            .cloned()
+            .collect();
+
+        Collection {
+            fields,
+            ..collection.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Collection, CollectionField, FieldType, Projection};
+
+    #[test]
+    fn projection_apply_test() {
+        let collection = Collection {
+            name: "test".to_string(),
+            fields: ["id", "name", "age", "blob"]
+                .into_iter()
+                .map(|name| CollectionField {
+                    name: name.to_string(),
+                    field_type: FieldType::String,
+                    default: None,
+                    required: None,
+                    unique: None,
+                    on_delete: None,
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let field_names = |projection: Projection| {
+            projection
+                .apply(&collection)
+                .fields
+                .into_iter()
+                .map(|field| field.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(field_names(Projection::default()).len(), 4);
+
+        assert_eq!(
+            field_names(Projection {
+                keys: Some(Projection::parse_keys("name, unknown,")),
+                exclude_keys: None,
+            }),
+            vec!["id", "name"]
+        );
+
+        assert_eq!(
+            field_names(Projection {
+                keys: None,
+                exclude_keys: Some(Projection::parse_keys("blob,id")),
+            }),
+            vec!["id", "name", "age"]
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use std::str::FromStr;
+
+use crate::projection::Projection;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OrderDirection {
+    Asc,
+    Desc,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OrderBy {
+    pub field: String,
+    pub direction: OrderDirection,
+}
+
+impl FromStr for OrderBy {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+
+        let (field, direction) = match value.strip_prefix('-') {
+            Some(field) => (field, OrderDirection::Desc),
+            None => (value, OrderDirection::Asc),
+        };
+
+        if field.is_empty() {
+            return Err(());
+        }
+
+        Ok(OrderBy {
+            field: field.to_string(),
+            direction,
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryOptions {
+    pub order: Vec<OrderBy>,
+    pub limit: Option<usize>,
+    pub skip: Option<usize>,
+    pub projection: Projection,
+}
+
+impl QueryOptions {
+    pub fn parse_order(order: &str) -> Vec<OrderBy> {
+        order
+            .split(',')
+            .filter_map(|key| key.parse().ok())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::query_options::{OrderBy, OrderDirection, QueryOptions};
+
+    #[test]
+    fn parse_order_test() {
+        let order = QueryOptions::parse_order("-created_at,name,,");
+
+        assert_eq!(
+            order,
+            vec![
+                OrderBy {
+                    field: "created_at".to_string(),
+                    direction: OrderDirection::Desc,
+                },
+                OrderBy {
+                    field: "name".to_string(),
+                    direction: OrderDirection::Asc,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaPolicy {
+    // unknown fields are added to the schema on write
+    #[default]
+    Open,
+    // unknown fields are rejected, the schema changes only through the schema api with the
+    // master key
+    Strict,
+    // only requests with the master key may change the schema, implicitly or not
+    AdminOnly,
+}
+
+impl SchemaPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SchemaPolicy::Open => "open",
+            SchemaPolicy::Strict => "strict",
+            SchemaPolicy::AdminOnly => "admin_only",
+        }
+    }
+
+    // fields added by a write that mentions them
+    pub fn allows_implicit_fields(&self, master: bool) -> bool {
+        match self {
+            SchemaPolicy::Open => true,
+            SchemaPolicy::Strict => false,
+            SchemaPolicy::AdminOnly => master,
+        }
+    }
+
+    // explicit schema api calls: creating, dropping, fields and policy
+    pub fn allows_schema_changes(&self, master: bool) -> bool {
+        match self {
+            SchemaPolicy::Open => true,
+            SchemaPolicy::Strict | SchemaPolicy::AdminOnly => master,
+        }
+    }
+}
+
+impl FromStr for SchemaPolicy {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "open" => Ok(SchemaPolicy::Open),
+            "strict" => Ok(SchemaPolicy::Strict),
+            "admin_only" => Ok(SchemaPolicy::AdminOnly),
+            _ => Err(()),
+        }
+    }
+}
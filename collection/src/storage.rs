@@ -1,16 +1,25 @@
-use std::collections::HashMap;
-
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::Value;
 
 use crate::collection::Collection;
 use crate::collection_field::CollectionField;
+use crate::precondition::Precondition;
+use crate::projection::Projection;
+use crate::query_options::QueryOptions;
+use crate::schema_policy::SchemaPolicy;
 use crate::storage_error::StorageError;
-use crate::where_attr::Where;
+use crate::where_query::WhereQuery;
 
 #[async_trait]
 pub trait Storage {
+    // a copy of the storage whose writes are applied on `commit`
+    async fn begin(&self) -> Result<Self, StorageError>
+    where
+        Self: Sized;
+    async fn commit(&self) -> Result<(), StorageError>;
+    async fn rollback(&self) -> Result<(), StorageError>;
+
     async fn get_collections(&self) -> Result<Vec<Collection>, StorageError>;
     async fn get_collection(&self, collection_name: String) -> Result<Collection, StorageError>;
     async fn create_collection(
@@ -19,6 +28,11 @@ pub trait Storage {
         fields: Vec<CollectionField>,
     ) -> Result<Collection, StorageError>;
     async fn remove_collection(&self, collection: &Collection) -> Result<(), StorageError>;
+    async fn set_collection_policy(
+        &self,
+        collection: &Collection,
+        policy: Option<SchemaPolicy>,
+    ) -> Result<Collection, StorageError>;
     async fn insert_field_to_collection(
         &self,
         collection: &Collection,
@@ -35,25 +49,53 @@ pub trait Storage {
         collection: &Collection,
         data: Value,
     ) -> Result<Value, StorageError>;
+    // inserts `data`, or applies `update` to the row holding the same `key`
+    async fn upsert_data_into_collection(
+        &self,
+        collection: &Collection,
+        data: Value,
+        key: &str,
+        update: &Value,
+    ) -> Result<Value, StorageError>;
     async fn update_data_into_collection(
         &self,
         collection: &Collection,
         collection_id: String,
         data: Value,
+        precondition: &Precondition,
     ) -> Result<Value, StorageError>;
     async fn delete_data_from_collection(
         &self,
         collection: &Collection,
         collection_id: String,
+        precondition: &Precondition,
     ) -> Result<Value, StorageError>;
+    async fn bulk_update_data_into_collection(
+        &self,
+        collection: &Collection,
+        query: WhereQuery,
+        data: Value,
+    ) -> Result<u64, StorageError>;
+    async fn bulk_delete_data_from_collection(
+        &self,
+        collection: &Collection,
+        query: WhereQuery,
+    ) -> Result<u64, StorageError>;
     async fn get_data_from_collection(
         &self,
         collection: &Collection,
         collection_id: String,
+        projection: &Projection,
     ) -> Result<Value, StorageError>;
     async fn list_data_from_collection(
         &self,
         collection: &Collection,
-        query: HashMap<String, Where>,
+        query: WhereQuery,
+        options: &QueryOptions,
     ) -> Result<Vec<Value>, StorageError>;
+    async fn count_data_from_collection(
+        &self,
+        collection: &Collection,
+        query: WhereQuery,
+    ) -> Result<i64, StorageError>;
 }
@@ -19,6 +19,12 @@ pub enum StorageError {
     CollectionFieldRemove { collection: String, field: String },
     #[error("Value not found {collection} : {id}")]
     ValueNotFound { collection: String, id: String },
+    #[error("Value {collection} : {id} was changed")]
+    Conflict { collection: String, id: String },
+    #[error("Value {collection} : {id} is referenced")]
+    Referenced { collection: String, id: String },
+    #[error("Transaction failed: {err}")]
+    Transaction { err: String },
     #[error("Value not found {collection} : {err}")]
     DBErr { collection: String, err: String },
 }
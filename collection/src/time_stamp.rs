@@ -0,0 +1,78 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde_json::{json, Value};
+
+use crate::FieldType;
+
+const TYPE_KEY: &str = "__type";
+const TIME_STAMP_TYPE: &str = "TimeStamp";
+const DATE_TYPE: &str = "Date";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeStamp(pub DateTime<Utc>);
+
+impl TimeStamp {
+    pub fn is_tagged(value: &Value) -> bool {
+        matches!(
+            value.get(TYPE_KEY).and_then(|v| v.as_str()),
+            Some(TIME_STAMP_TYPE | DATE_TYPE)
+        )
+    }
+
+    // RFC 3339 strings, epoch milliseconds, `{"__type": "TimeStamp", "value": ...}`
+    // and `{"__type": "Date", "iso": ...}`
+    pub fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(value) => DateTime::parse_from_rfc3339(value)
+                .ok()
+                .map(|v| Self(v.with_timezone(&Utc))),
+            Value::Number(_) => FieldType::integer_value(value)
+                .and_then(NaiveDateTime::from_timestamp_millis)
+                .map(|v| Self(v.and_utc())),
+            Value::Object(object) if Self::is_tagged(value) => object
+                .get("value")
+                .or_else(|| object.get("iso"))
+                .and_then(Self::from_value),
+            _ => None,
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        json!({
+            TYPE_KEY: TIME_STAMP_TYPE,
+            "value": self.0.to_rfc3339(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::TimeStamp;
+
+    #[test]
+    fn parse_inputs() {
+        let expected = TimeStamp::from_value(&json!("2024-03-01T12:30:00Z")).unwrap();
+
+        assert_eq!(
+            TimeStamp::from_value(&json!("2024-03-01T14:30:00+02:00")),
+            Some(expected)
+        );
+        assert_eq!(
+            TimeStamp::from_value(&json!(1709296200000i64)),
+            Some(expected)
+        );
+        assert_eq!(
+            TimeStamp::from_value(&json!({"__type": "TimeStamp", "value": "2024-03-01T12:30:00Z"})),
+            Some(expected)
+        );
+        assert_eq!(
+            TimeStamp::from_value(&json!({"__type": "Date", "iso": "2024-03-01T12:30:00.000Z"})),
+            Some(expected)
+        );
+        assert_eq!(TimeStamp::from_value(&expected.to_value()), Some(expected));
+
+        assert!(TimeStamp::from_value(&json!("yesterday")).is_none());
+        assert!(TimeStamp::from_value(&json!({"value": "2024-03-01T12:30:00Z"})).is_none());
+    }
+}
@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-#[derive(Serialize, Deserialize, Default)]
+use crate::field_type::FieldType;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct Where {
     #[serde(rename = "$eq")]
     pub eq: Option<Value>,
@@ -19,4 +21,77 @@ pub struct Where {
     pub in_: Option<Vec<Value>>,
     #[serde(rename = "$nin")]
     pub nin: Option<Vec<Value>>,
+    #[serde(rename = "$exists")]
+    pub exists: Option<bool>,
+    #[serde(rename = "$regex")]
+    pub regex: Option<String>,
+    // "i" makes $regex, $startsWith, $endsWith and $text case-insensitive
+    #[serde(rename = "$options")]
+    pub options: Option<String>,
+    #[serde(rename = "$startsWith")]
+    pub starts_with: Option<String>,
+    #[serde(rename = "$endsWith")]
+    pub ends_with: Option<String>,
+    #[serde(rename = "$text")]
+    pub text: Option<String>,
+    #[serde(rename = "$all")]
+    pub all: Option<Vec<Value>>,
+    #[serde(rename = "$containedBy")]
+    pub contained_by: Option<Vec<Value>>,
+    #[serde(rename = "$contains")]
+    pub contains: Option<Value>,
+    #[serde(rename = "$size")]
+    pub size: Option<usize>,
+}
+
+impl Where {
+    pub fn case_insensitive(&self) -> bool {
+        self.options
+            .as_ref()
+            .map(|options| options.contains('i'))
+            .unwrap_or_default()
+    }
+
+    // relations live in a join table, which only answers `$eq` and `$in`
+    pub fn is_membership(&self) -> bool {
+        Where {
+            eq: None,
+            in_: None,
+            ..self.clone()
+        } == Where::default()
+    }
+
+    // type of the compared value, used for fields without schema such as nested paths
+    pub fn value_type(&self) -> Option<FieldType> {
+        if self.regex.is_some()
+            || self.starts_with.is_some()
+            || self.ends_with.is_some()
+            || self.text.is_some()
+        {
+            return Some(FieldType::String);
+        }
+
+        if self.all.is_some()
+            || self.contained_by.is_some()
+            || self.contains.is_some()
+            || self.size.is_some()
+        {
+            return Some(FieldType::Array);
+        }
+
+        [&self.eq, &self.ne, &self.gt, &self.lt, &self.gte, &self.lte]
+            .into_iter()
+            .flatten()
+            .chain(self.in_.iter().flatten())
+            .chain(self.nin.iter().flatten())
+            .find(|value| !value.is_null())
+            .map(|value| match value.clone().into() {
+                // tagged values inside nested objects are plain json
+                FieldType::Pointer { .. } | FieldType::Decimal { .. } | FieldType::TimeStamp => {
+                    FieldType::Object
+                }
+                FieldType::Integer | FieldType::BigInt => FieldType::Number,
+                value_type => value_type,
+            })
+    }
 }
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+use serde_json::Value;
+
+use crate::pointer::Pointer;
+use crate::where_attr::Where;
+
+const AND_OPERATOR: &str = "$and";
+const OR_OPERATOR: &str = "$or";
+const NOR_OPERATOR: &str = "$nor";
+const RELATED_TO_OPERATOR: &str = "$relatedTo";
+
+// objects linked to `object` through its relation field `key`
+#[derive(Clone, Debug, PartialEq)]
+pub struct RelatedTo {
+    pub object: Pointer,
+    pub key: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WhereQuery {
+    pub fields: HashMap<String, Where>,
+    pub and: Vec<WhereQuery>,
+    pub or: Vec<WhereQuery>,
+    pub nor: Vec<WhereQuery>,
+    pub related_to: Option<RelatedTo>,
+}
+
+impl WhereQuery {
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+            && self.and.is_empty()
+            && self.or.is_empty()
+            && self.nor.is_empty()
+            && self.related_to.is_none()
+    }
+
+    pub fn parse(query: &Value) -> Result<Self, String> {
+        let query = query
+            .as_object()
+            .ok_or_else(|| format!("where must be an object: {query}"))?;
+
+        let mut result = WhereQuery::default();
+
+        for (key, value) in query {
+            match key.as_str() {
+                AND_OPERATOR => result.and = Self::parse_list(key, value)?,
+                OR_OPERATOR => result.or = Self::parse_list(key, value)?,
+                NOR_OPERATOR => result.nor = Self::parse_list(key, value)?,
+                RELATED_TO_OPERATOR => result.related_to = Some(Self::parse_related_to(value)?),
+                _ if key.starts_with('$') => return Err(format!("unknown operator {key}")),
+                _ => {
+                    let field: Where = if value.is_object() {
+                        serde_json::from_value(value.clone())
+                            .map_err(|err| format!("{key}: {err}"))?
+                    } else {
+                        Where {
+                            eq: Some(value.clone()),
+                            ..Default::default()
+                        }
+                    };
+
+                    // backends disagree on what a broken pattern does, so reject it up front
+                    if let Some(regex) = &field.regex {
+                        Regex::new(regex).map_err(|_| format!("{key}: invalid $regex {regex}"))?;
+                    }
+
+                    result.fields.insert(key.to_string(), field);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn parse_related_to(value: &Value) -> Result<RelatedTo, String> {
+        let object = value
+            .get("object")
+            .and_then(Pointer::from_value)
+            .ok_or_else(|| format!("{RELATED_TO_OPERATOR} object must be a pointer"))?;
+
+        let key = value
+            .get("key")
+            .and_then(|key| key.as_str())
+            .ok_or_else(|| format!("{RELATED_TO_OPERATOR} key must be a string"))?;
+
+        Ok(RelatedTo {
+            object,
+            key: key.to_string(),
+        })
+    }
+
+    fn parse_list(key: &str, value: &Value) -> Result<Vec<WhereQuery>, String> {
+        match value.as_array() {
+            Some(list) if !list.is_empty() => list.iter().map(Self::parse).collect(),
+            _ => Err(format!("{key} must be a non empty array")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::WhereQuery;
+
+    #[test]
+    fn parse_where_query_test() {
+        let query = WhereQuery::parse(&json!({
+            "name": "test",
+            "$or": [
+                { "age": { "$gt": 10 } },
+                { "$and": [{ "age": 5 }, { "name": { "$ne": "a" } }] }
+            ],
+            "$nor": [{ "age": 1 }]
+        }))
+        .unwrap();
+
+        assert_eq!(query.fields.len(), 1);
+        assert_eq!(query.or.len(), 2);
+        assert_eq!(query.or[1].and.len(), 2);
+        assert_eq!(query.nor.len(), 1);
+        assert!(query.and.is_empty());
+
+        assert!(WhereQuery::parse(&json!({ "$or": [] })).is_err());
+        assert!(WhereQuery::parse(&json!({ "$xor": [{ "age": 1 }] })).is_err());
+        assert!(WhereQuery::parse(&json!({ "age": { "$in": 1 } })).is_err());
+        assert!(WhereQuery::parse(&json!({ "name": { "$regex": "(" } })).is_err());
+
+        let query = WhereQuery::parse(&json!({
+            "$relatedTo": {
+                "object": { "__type": "Pointer", "className": "Posts", "objectId": "p1" },
+                "key": "tags"
+            }
+        }))
+        .unwrap();
+
+        let related_to = query.related_to.unwrap();
+        assert_eq!(related_to.object.object_id, "p1");
+        assert_eq!(related_to.key, "tags");
+
+        assert!(WhereQuery::parse(&json!({ "$relatedTo": { "key": "tags" } })).is_err());
+    }
+}
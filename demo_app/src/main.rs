@@ -5,8 +5,9 @@ use actix_web::{web, App as WebApp, HttpServer};
 use dotenv::dotenv;
 use tracing::level_filters::LevelFilter;
 use tracing_subscriber::fmt::format;
-use vivalaakam_seattle_collection::Collections;
+use vivalaakam_seattle_collection::{Collections, SchemaPolicy, Storage};
 use vivalaakam_seattle_collection_postgres::StorePostgresql;
+use vivalaakam_seattle_collection_sqlite::StoreSqlite;
 
 use vivalaakam_seattle_store::{routes, App};
 
@@ -35,24 +36,47 @@ async fn main() -> std::io::Result<()> {
         },
     ));
 
-    let database_url =
-        env::var("POSTGRES_DATABASE_URL").expect("POSTGRES_DATABASE_URL must be set");
+    let database_url = env::var("DATABASE_URL")
+        .or_else(|_| env::var("POSTGRES_DATABASE_URL"))
+        .expect("DATABASE_URL must be set");
 
-    let instance = StorePostgresql::new(database_url.as_str()).await;
+    if database_url.starts_with("sqlite:") {
+        serve(StoreSqlite::new(database_url.as_str()).await).await
+    } else {
+        serve(StorePostgresql::new(database_url.as_str()).await).await
+    }
+}
+
+async fn serve<T>(instance: T) -> std::io::Result<()>
+where
+    T: Storage + Clone + Send + 'static,
+{
+    let policy = env::var("SCHEMA_POLICY")
+        .ok()
+        .and_then(|policy| SchemaPolicy::from_str(policy.as_str()).ok())
+        .unwrap_or_default();
+
+    let implicit_collections =
+        env::var("IMPLICIT_COLLECTIONS").unwrap_or("true".to_string()) != "false";
 
-    let collections = Collections::new(instance).await;
+    let collections = Collections::new(instance)
+        .await
+        .with_policy(policy)
+        .with_implicit_collections(implicit_collections);
 
+    // schema routes answer only to the master code
     let app = App::new(
         collections,
         env::var("SECRET_CODE").expect("SECRET_CODE must be set"),
-    );
+    )
+    .with_master_code(env::var("MASTER_CODE").expect("MASTER_CODE must be set"));
 
     let app_port = env::var("PORT").unwrap_or(String::from("8080"));
 
     HttpServer::new(move || {
         WebApp::new()
             .app_data(web::Data::new(app.clone()))
-            .configure(routes::config::<StorePostgresql>)
+            .configure(routes::config::<T>)
     })
     .bind(format!("0.0.0.0:{app_port}"))
     .expect("panic")
@@ -4,6 +4,7 @@ use vivalaakam_seattle_collection::{Collections, Storage};
 pub struct App<T> {
     collections: Collections<T>,
     secret_code: String,
+    master_code: Option<String>,
 }
 
 impl<T> App<T>
@@ -14,14 +15,24 @@ where
         Self {
             collections,
             secret_code,
+            master_code: None,
         }
     }
 
+    pub fn with_master_code(mut self, master_code: String) -> Self {
+        self.master_code = Some(master_code);
+        self
+    }
+
     pub fn get_collections(&self) -> &Collections<T> {
         &self.collections
     }
 
     pub fn is_valid(&self, code: &str) -> bool {
-        self.secret_code.eq(code)
+        self.secret_code.eq(code) || self.is_master(code)
+    }
+
+    pub fn is_master(&self, code: &str) -> bool {
+        self.master_code.as_deref() == Some(code)
     }
 }
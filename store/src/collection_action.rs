@@ -1,68 +1,360 @@
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Map, Value};
 
-use vivalaakam_seattle_collection::{CollectionError, Storage};
+use vivalaakam_seattle_collection::{
+    CollectionError, Collections, Precondition, Projection, QueryOptions, Storage, WhereQuery,
+};
 
-use crate::App;
+const REFERENCE_KEY: &str = "$ref";
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 #[serde(tag = "action")]
 pub enum CollectionAction {
     Create {
         collection: String,
         data: Value,
     },
+    Upsert {
+        collection: String,
+        data: Value,
+        key: Option<String>,
+    },
     Update {
         collection: String,
         identifier: String,
         data: Value,
+        #[serde(default, rename = "ifMatch")]
+        if_match: Option<String>,
+        #[serde(default, rename = "where")]
+        where_query: Option<Value>,
     },
     Delete {
         collection: String,
         identifier: String,
+        #[serde(default, rename = "ifMatch")]
+        if_match: Option<String>,
     },
     Get {
         collection: String,
         identifier: String,
+        keys: Option<Vec<String>>,
+        #[serde(rename = "excludeKeys")]
+        exclude_keys: Option<Vec<String>>,
+        #[serde(default)]
+        include: Vec<String>,
+    },
+    List {
+        collection: String,
+        #[serde(default, rename = "where")]
+        where_query: Option<Value>,
+        order: Option<String>,
+        limit: Option<usize>,
+        skip: Option<usize>,
+        keys: Option<Vec<String>>,
+        #[serde(rename = "excludeKeys")]
+        exclude_keys: Option<Vec<String>>,
+        #[serde(default)]
+        include: Vec<String>,
+    },
+    Count {
+        collection: String,
+        #[serde(default, rename = "where")]
+        where_query: Option<Value>,
     },
 }
 
 impl CollectionAction {
-    pub async fn perform<T>(&self, app: &App<T>) -> Result<Value, CollectionError>
+    pub async fn perform<T>(
+        &self,
+        collections: &Collections<T>,
+        master: bool,
+    ) -> Result<Value, CollectionError>
     where
         T: Storage,
     {
         match self {
+            CollectionAction::Create { collection, data } if master => {
+                collections
+                    .insert_as_master(collection.to_string(), data.clone())
+                    .await
+            }
             CollectionAction::Create { collection, data } => {
-                app.get_collections()
+                collections
                     .insert(collection.to_string(), data.clone())
                     .await
             }
+            CollectionAction::Upsert {
+                collection,
+                data,
+                key,
+            } if master => {
+                collections
+                    .upsert_as_master(collection.to_string(), data.clone(), key.clone())
+                    .await
+            }
+            CollectionAction::Upsert {
+                collection,
+                data,
+                key,
+            } => {
+                collections
+                    .upsert(collection.to_string(), data.clone(), key.clone())
+                    .await
+            }
             CollectionAction::Update {
                 collection,
                 identifier,
                 data,
+                if_match,
+                where_query,
             } => {
-                app.get_collections()
-                    .update(collection.to_string(), identifier.to_string(), data.clone())
+                let mut precondition = Self::precondition(collection, identifier, if_match)?;
+
+                if let Some(query) = where_query {
+                    precondition.query = WhereQuery::parse(query).map_err(|error| {
+                        CollectionError::InvalidQuery {
+                            collection: collection.to_string(),
+                            error,
+                        }
+                    })?;
+                }
+
+                collections
+                    .update_with_precondition(
+                        collection.to_string(),
+                        identifier.to_string(),
+                        data.clone(),
+                        precondition,
+                        master,
+                    )
                     .await
             }
             CollectionAction::Delete {
                 collection,
                 identifier,
+                if_match,
             } => {
-                app.get_collections()
-                    .delete(collection.to_string(), identifier.to_string())
+                let precondition = Self::precondition(collection, identifier, if_match)?;
+
+                collections
+                    .delete_with_precondition(
+                        collection.to_string(),
+                        identifier.to_string(),
+                        precondition,
+                    )
                     .await
             }
             CollectionAction::Get {
                 collection,
                 identifier,
+                keys,
+                exclude_keys,
+                include,
+            } => {
+                let projection = Projection {
+                    keys: keys.clone(),
+                    exclude_keys: exclude_keys.clone(),
+                };
+
+                collections
+                    .get_with_include(
+                        collection.to_string(),
+                        identifier.to_string(),
+                        projection,
+                        include,
+                    )
+                    .await
+            }
+            CollectionAction::List {
+                collection,
+                where_query,
+                order,
+                limit,
+                skip,
+                keys,
+                exclude_keys,
+                include,
             } => {
-                app.get_collections()
-                    .get(collection.to_string(), identifier.to_string())
+                let options = QueryOptions {
+                    order: order
+                        .as_deref()
+                        .map(QueryOptions::parse_order)
+                        .unwrap_or_default(),
+                    limit: *limit,
+                    skip: *skip,
+                    projection: Projection {
+                        keys: keys.clone(),
+                        exclude_keys: exclude_keys.clone(),
+                    },
+                };
+
+                collections
+                    .list_with_include(
+                        collection.to_string(),
+                        Self::where_query(where_query),
+                        options,
+                        include,
+                    )
                     .await
+                    .map(Value::Array)
             }
+            CollectionAction::Count {
+                collection,
+                where_query,
+            } => collections
+                .count(collection.to_string(), Self::where_query(where_query))
+                .await
+                .map(|count| json!({ "count": count })),
         }
     }
+
+    // replaces a `"$0.id"` identifier and `{"$ref": "0.id"}` objects in data and where with
+    // values of earlier results, plain strings are stored as they are
+    pub fn resolve_references(
+        &self,
+        results: &[Result<Value, CollectionError>],
+    ) -> Result<Self, CollectionError> {
+        let mut action = self.clone();
+
+        match &mut action {
+            CollectionAction::Create { data, .. } | CollectionAction::Upsert { data, .. } => {
+                *data = resolve_value(data.take(), results)?;
+            }
+            CollectionAction::Update {
+                identifier,
+                data,
+                where_query,
+                ..
+            } => {
+                *identifier = resolve_identifier(identifier, results)?;
+                *data = resolve_value(data.take(), results)?;
+                resolve_where(where_query, results)?;
+            }
+            CollectionAction::Delete { identifier, .. }
+            | CollectionAction::Get { identifier, .. } => {
+                *identifier = resolve_identifier(identifier, results)?;
+            }
+            CollectionAction::List { where_query, .. }
+            | CollectionAction::Count { where_query, .. } => {
+                resolve_where(where_query, results)?;
+            }
+        }
+
+        Ok(action)
+    }
+
+    fn where_query(where_query: &Option<Value>) -> Value {
+        where_query
+            .clone()
+            .unwrap_or_else(|| Value::Object(Map::new()))
+    }
+
+    // an unparsable etag can never match the stored row
+    fn precondition(
+        collection: &str,
+        identifier: &str,
+        if_match: &Option<String>,
+    ) -> Result<Precondition, CollectionError> {
+        match if_match {
+            Some(etag) => Precondition::if_match(etag).ok_or(CollectionError::Conflict {
+                collection: collection.to_string(),
+                id: identifier.to_string(),
+            }),
+            None => Ok(Precondition::default()),
+        }
+    }
+}
+
+fn resolve_identifier(
+    identifier: &str,
+    results: &[Result<Value, CollectionError>],
+) -> Result<String, CollectionError> {
+    let path = match identifier.strip_prefix('$') {
+        Some(path) if is_reference(path) => path,
+        _ => return Ok(identifier.to_string()),
+    };
+
+    match resolve_reference(path, results)? {
+        Value::String(identifier) => Ok(identifier),
+        value => Err(CollectionError::InvalidReference {
+            reference: path.to_string(),
+            error: format!("expected a string identifier, found {value}"),
+        }),
+    }
+}
+
+fn resolve_where(
+    where_query: &mut Option<Value>,
+    results: &[Result<Value, CollectionError>],
+) -> Result<(), CollectionError> {
+    if let Some(query) = where_query {
+        *query = resolve_value(query.take(), results)?;
+    }
+
+    Ok(())
+}
+
+fn resolve_value(
+    value: Value,
+    results: &[Result<Value, CollectionError>],
+) -> Result<Value, CollectionError> {
+    match value {
+        Value::Object(object) => match object.get(REFERENCE_KEY) {
+            Some(Value::String(path)) if object.len() == 1 => resolve_reference(path, results),
+            _ => object
+                .into_iter()
+                .map(|(key, value)| Ok((key, resolve_value(value, results)?)))
+                .collect::<Result<Map<_, _>, _>>()
+                .map(Value::Object),
+        },
+        Value::Array(values) => values
+            .into_iter()
+            .map(|value| resolve_value(value, results))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        value => Ok(value),
+    }
+}
+
+// `<index>.<path>`, so identifiers that merely start with `$` stay untouched
+fn is_reference(path: &str) -> bool {
+    path.split_once('.')
+        .is_some_and(|(index, field)| index.parse::<usize>().is_ok() && !field.is_empty())
+}
+
+fn resolve_reference(
+    path: &str,
+    results: &[Result<Value, CollectionError>],
+) -> Result<Value, CollectionError> {
+    let invalid = |error: String| CollectionError::InvalidReference {
+        reference: path.to_string(),
+        error,
+    };
+
+    let (index, fields) = path
+        .split_once('.')
+        .ok_or_else(|| invalid("expected <action>.<field>".to_string()))?;
+
+    let index = index
+        .parse::<usize>()
+        .map_err(|_| invalid(format!("{index} is not an action index")))?;
+
+    let result = match results.get(index) {
+        Some(Ok(result)) => result,
+        Some(Err(error)) => return Err(invalid(format!("action {index} failed: {error}"))),
+        None => return Err(invalid(format!("action {index} was not performed yet"))),
+    };
+
+    fields
+        .split('.')
+        .try_fold(result, |value, field| match value {
+            Value::Array(values) => field.parse::<usize>().ok().and_then(|i| values.get(i)),
+            value => value.get(field),
+        })
+        .cloned()
+        .ok_or_else(|| {
+            invalid(format!(
+                "{fields} not found in the result of action {index}"
+            ))
+        })
 }
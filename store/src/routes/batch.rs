@@ -1,33 +1,91 @@
 use actix_http::header;
 use actix_web::{web, HttpResponse};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use serde_json::{json, Value};
 
-use vivalaakam_seattle_collection::{value_to_string, Storage};
+use vivalaakam_seattle_collection::{value_to_string, CollectionError, Collections, Storage};
 
 use crate::collection_action::CollectionAction;
+use crate::routes::perform_result;
 use crate::App;
 
 #[derive(Serialize, Deserialize)]
 pub struct BatchRequest {
     pub requests: Vec<CollectionAction>,
+    #[serde(default)]
+    pub transaction: bool,
 }
 
-pub async fn batch<T>(data: web::Json<BatchRequest>, app: web::Data<App<T>>) -> HttpResponse
+// all or nothing: the first failed action rolls back the whole batch
+async fn perform_transaction<T>(
+    collections: &Collections<T>,
+    requests: &[CollectionAction],
+    master: bool,
+) -> Result<Vec<Value>, CollectionError>
 where
     T: Storage,
 {
+    let transaction = collections.begin().await?;
+
+    let mut results = vec![];
+
+    for row in requests {
+        let result = match row.resolve_references(&results) {
+            Ok(row) => row.perform(&transaction, master).await,
+            Err(error) => Err(error),
+        };
+
+        if let Err(error) = result {
+            collections.rollback(transaction).await?;
+            return Err(error);
+        }
+
+        results.push(result);
+    }
+
+    collections.commit(transaction).await?;
+
+    Ok(results.into_iter().flatten().collect())
+}
+
+pub async fn batch<T>(
+    data: web::Json<BatchRequest>,
+    auth: BearerAuth,
+    app: web::Data<App<T>>,
+) -> HttpResponse
+where
+    T: Storage,
+{
+    let master = app.is_master(auth.token());
+
+    if data.transaction {
+        return perform_result(
+            perform_transaction(app.get_collections(), &data.requests, master)
+                .await
+                .map(|results| json!({ "results": results })),
+        );
+    }
+
     let mut results = vec![];
 
     for row in &data.requests {
-        let res = match row.perform(&app).await {
-            Ok(data) => data,
-            Err(error) => json!(error),
+        let res = match row.resolve_references(&results) {
+            Ok(row) => row.perform(app.get_collections(), master).await,
+            Err(error) => Err(error),
         };
 
         results.push(res);
     }
 
+    let results = results
+        .into_iter()
+        .map(|res| match res {
+            Ok(data) => data,
+            Err(error) => json!(error),
+        })
+        .collect::<Vec<_>>();
+
     HttpResponse::Ok()
         .insert_header((header::CONTENT_TYPE, "application/json"))
         .body(value_to_string(json!({ "results": results })))
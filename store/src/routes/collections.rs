@@ -1,56 +1,92 @@
 use actix_web::http::header;
-use actix_web::{web, HttpResponse};
-use serde::{Deserialize, Serialize};
-use serde_json::{Map, Value};
+use actix_web::{web, HttpRequest, HttpResponse};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
 use tracing::debug;
 
-use vivalaakam_seattle_collection::{value_to_string, CollectionError, Storage};
+use vivalaakam_seattle_collection::{CollectionError, Projection, QueryOptions, Storage};
 
 use crate::collection_action::CollectionAction;
+use crate::routes::{perform_result, perform_row_result};
 use crate::App;
 
-fn perform_result<T>(result: Result<T, CollectionError>) -> HttpResponse
-where
-    T: Serialize,
-{
-    match result {
-        Ok(data) => HttpResponse::Ok()
-            .insert_header((header::CONTENT_TYPE, "application/json"))
-            .body(value_to_string(data)),
-        Err(CollectionError::CollectionNotFound { collection }) => {
-            HttpResponse::NotFound().json(collection)
-        }
-        Err(error) => HttpResponse::BadRequest().json(error),
-    }
+fn if_match(req: &HttpRequest) -> Option<String> {
+    req.headers()
+        .get(header::IF_MATCH)
+        .map(|value| value.to_str().unwrap_or_default().to_string())
+}
+
+// a body that isn't json is reported instead of failing the worker
+fn request_data(collection_name: &str, data: &[u8]) -> Result<Value, CollectionError> {
+    serde_json::from_slice(data).map_err(|_| CollectionError::CollectionInputData {
+        collection: collection_name.to_string(),
+    })
 }
 
 #[derive(Debug, Deserialize)]
 pub struct CollectionQuery {
     #[serde(rename = "where")]
     pub where_param: Option<String>,
+    pub order: Option<String>,
+    pub limit: Option<usize>,
+    pub skip: Option<usize>,
+    pub count: Option<u8>,
+    pub keys: Option<String>,
+    #[serde(rename = "excludeKeys")]
+    pub exclude_keys: Option<String>,
+    pub include: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CollectionUpdateQuery {
+    #[serde(rename = "where")]
+    pub where_param: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CollectionGetQuery {
+    pub keys: Option<String>,
+    #[serde(rename = "excludeKeys")]
+    pub exclude_keys: Option<String>,
+    pub include: Option<String>,
 }
 
 pub async fn collection_get<T>(
     path: web::Path<(String, String)>,
+    query: web::Query<CollectionGetQuery>,
+    auth: BearerAuth,
     app: web::Data<App<T>>,
 ) -> HttpResponse
 where
     T: Storage,
 {
-    debug!("collection_get {path:?}");
+    debug!("collection_get {path:?} {query:?}");
     let (collection_name, collection_id) = path.into_inner();
 
     let action = CollectionAction::Get {
         collection: collection_name,
         identifier: collection_id,
+        keys: query.keys.as_deref().map(Projection::parse_keys),
+        exclude_keys: query.exclude_keys.as_deref().map(Projection::parse_keys),
+        include: query
+            .include
+            .as_deref()
+            .map(Projection::parse_keys)
+            .unwrap_or_default(),
     };
 
-    perform_result(action.perform(&app).await)
+    perform_row_result(
+        action
+            .perform(app.get_collections(), app.is_master(auth.token()))
+            .await,
+    )
 }
 
 pub async fn collection_create<T>(
     path: web::Path<String>,
     data: web::Bytes,
+    auth: BearerAuth,
     app: web::Data<App<T>>,
 ) -> HttpResponse
 where
@@ -59,16 +95,27 @@ where
     debug!("collection_create {path:?}");
     let collection_name = path.into_inner();
 
+    let data = match request_data(&collection_name, &data) {
+        Ok(data) => data,
+        Err(error) => return perform_result::<Value>(Err(error)),
+    };
+
     let action = CollectionAction::Create {
         collection: collection_name.to_string(),
-        data: serde_json::from_slice(&data).unwrap(),
+        data,
     };
 
-    perform_result(action.perform(&app).await)
+    perform_row_result(
+        action
+            .perform(app.get_collections(), app.is_master(auth.token()))
+            .await,
+    )
 }
 
 pub async fn collection_delete<T>(
     path: web::Path<(String, String)>,
+    req: HttpRequest,
+    auth: BearerAuth,
     app: web::Data<App<T>>,
 ) -> HttpResponse
 where
@@ -80,29 +127,129 @@ where
     let action = CollectionAction::Delete {
         collection: collection_name,
         identifier: collection_id,
+        if_match: if_match(&req),
     };
 
-    perform_result(action.perform(&app).await)
+    perform_result(
+        action
+            .perform(app.get_collections(), app.is_master(auth.token()))
+            .await,
+    )
 }
 
 pub async fn collection_update<T>(
     path: web::Path<(String, String)>,
+    query: web::Query<CollectionUpdateQuery>,
     data: web::Bytes,
+    req: HttpRequest,
+    auth: BearerAuth,
     app: web::Data<App<T>>,
 ) -> HttpResponse
 where
     T: Storage,
 {
-    debug!("collection_update {path:?}");
+    debug!("collection_update {path:?} {query:?}");
     let (collection_name, collection_id) = path.into_inner();
 
+    let data = match request_data(&collection_name, &data) {
+        Ok(data) => data,
+        Err(error) => return perform_result::<Value>(Err(error)),
+    };
+
     let action = CollectionAction::Update {
         collection: collection_name,
         identifier: collection_id,
-        data: serde_json::from_slice(&data).unwrap(),
+        data,
+        if_match: if_match(&req),
+        // a guard that isn't json is reported by the where parser
+        where_query: query.where_param.as_ref().map(|where_param| {
+            serde_json::from_str(where_param).unwrap_or(Value::String(where_param.to_string()))
+        }),
     };
 
-    perform_result(action.perform(&app).await)
+    perform_row_result(
+        action
+            .perform(app.get_collections(), app.is_master(auth.token()))
+            .await,
+    )
+}
+
+// a missing where must not touch the whole collection
+fn bulk_query(
+    collection_name: &str,
+    where_param: &Option<String>,
+) -> Result<Value, CollectionError> {
+    let where_param = where_param
+        .as_ref()
+        .ok_or_else(|| CollectionError::InvalidQuery {
+            collection: collection_name.to_string(),
+            error: "where is required".to_string(),
+        })?;
+
+    serde_json::from_str(where_param).map_err(|error| CollectionError::InvalidQuery {
+        collection: collection_name.to_string(),
+        error: error.to_string(),
+    })
+}
+
+pub async fn collection_bulk_update<T>(
+    path: web::Path<String>,
+    query: web::Query<CollectionUpdateQuery>,
+    data: web::Bytes,
+    auth: BearerAuth,
+    app: web::Data<App<T>>,
+) -> HttpResponse
+where
+    T: Storage,
+{
+    debug!("collection_bulk_update {path:?} {query:?}");
+    let collection_name = path.into_inner();
+
+    let where_query = match bulk_query(&collection_name, &query.where_param) {
+        Ok(where_query) => where_query,
+        Err(error) => return perform_result::<Value>(Err(error)),
+    };
+
+    let data = match request_data(&collection_name, &data) {
+        Ok(data) => data,
+        Err(error) => return perform_result::<Value>(Err(error)),
+    };
+
+    let result = if app.is_master(auth.token()) {
+        app.get_collections()
+            .bulk_update_as_master(collection_name, where_query, data)
+            .await
+    } else {
+        app.get_collections()
+            .bulk_update(collection_name, where_query, data)
+            .await
+    };
+
+    perform_result(result.map(|count| json!({ "count": count })))
+}
+
+pub async fn collection_bulk_delete<T>(
+    path: web::Path<String>,
+    query: web::Query<CollectionUpdateQuery>,
+    app: web::Data<App<T>>,
+) -> HttpResponse
+where
+    T: Storage,
+{
+    debug!("collection_bulk_delete {path:?} {query:?}");
+    let collection_name = path.into_inner();
+
+    let where_query = match bulk_query(&collection_name, &query.where_param) {
+        Ok(where_query) => where_query,
+        Err(error) => return perform_result::<Value>(Err(error)),
+    };
+
+    let result = app
+        .get_collections()
+        .bulk_delete(collection_name, where_query)
+        .await;
+
+    perform_result(result.map(|count| json!({ "count": count })))
 }
 
 pub async fn collection_query<T>(
@@ -116,13 +263,64 @@ where
     debug!("collection_query {path:?} {query:?}");
     let collection_name = path.into_inner();
 
-    let query = query
-        .where_param
-        .as_ref()
-        .map(|v| serde_json::from_str(v.as_str()).unwrap())
-        .unwrap_or(Value::Object(Map::new()));
+    let where_query = match query.where_param.as_ref().map(|v| serde_json::from_str(v)) {
+        Some(Ok(where_query)) => where_query,
+        Some(Err(error)) => {
+            return perform_result::<Value>(Err(CollectionError::InvalidQuery {
+                collection: collection_name,
+                error: error.to_string(),
+            }))
+        }
+        None => Value::Object(Map::new()),
+    };
+
+    let options = QueryOptions {
+        order: query
+            .order
+            .as_ref()
+            .map(|order| QueryOptions::parse_order(order))
+            .unwrap_or_default(),
+        limit: query.limit,
+        skip: query.skip,
+        projection: Projection {
+            keys: query.keys.as_deref().map(Projection::parse_keys),
+            exclude_keys: query.exclude_keys.as_deref().map(Projection::parse_keys),
+        },
+    };
+
+    let include = query
+        .include
+        .as_deref()
+        .map(Projection::parse_keys)
+        .unwrap_or_default();
+
+    if query.count != Some(1) {
+        let result = app
+            .get_collections()
+            .list_with_include(collection_name, where_query, options, &include)
+            .await;
+
+        return perform_result(result);
+    }
+
+    let count = match app
+        .get_collections()
+        .count(collection_name.to_string(), where_query.clone())
+        .await
+    {
+        Ok(count) => count,
+        Err(error) => return perform_result::<Value>(Err(error)),
+    };
+
+    if options.limit == Some(0) {
+        return perform_result(Ok(json!({ "count": count })));
+    }
 
-    let result = app.get_collections().list(collection_name, query).await;
+    let result = app
+        .get_collections()
+        .list_with_include(collection_name, where_query, options, &include)
+        .await
+        .map(|results| json!({ "results": results, "count": count }));
 
     perform_result(result)
 }
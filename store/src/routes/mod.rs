@@ -1,12 +1,50 @@
-use actix_web::web;
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::{web, HttpResponse};
 use actix_web_httpauth::middleware::HttpAuthentication;
+use serde::Serialize;
+use serde_json::Value;
 
-use vivalaakam_seattle_collection::Storage;
+use vivalaakam_seattle_collection::{value_to_string, CollectionError, Precondition, Storage};
 
 use crate::validator::validator;
 
 mod batch;
 mod collections;
+mod schemas;
+
+pub(crate) fn perform_result<T>(result: Result<T, CollectionError>) -> HttpResponse
+where
+    T: Serialize,
+{
+    match result {
+        Ok(data) => HttpResponse::Ok()
+            .insert_header((header::CONTENT_TYPE, "application/json"))
+            .body(value_to_string(data)),
+        Err(CollectionError::CollectionNotFound { collection }) => {
+            HttpResponse::NotFound().json(collection)
+        }
+        Err(error @ CollectionError::SchemaAccessDenied { .. }) => {
+            HttpResponse::Forbidden().json(error)
+        }
+        Err(error @ CollectionError::Conflict { .. }) => {
+            HttpResponse::PreconditionFailed().json(error)
+        }
+        Err(error @ CollectionError::Referenced { .. }) => HttpResponse::Conflict().json(error),
+        Err(error) => HttpResponse::BadRequest().json(error),
+    }
+}
+
+// single rows carry an etag for conditional writes
+pub(crate) fn perform_row_result(result: Result<Value, CollectionError>) -> HttpResponse {
+    let etag = result.as_ref().ok().and_then(Precondition::etag);
+    let mut response = perform_result(result);
+
+    if let Some(etag) = etag.and_then(|etag| HeaderValue::from_str(&etag).ok()) {
+        response.headers_mut().insert(header::ETAG, etag);
+    }
+
+    response
+}
 
 pub fn config<T>(conf: &mut web::ServiceConfig)
 where
@@ -15,6 +53,25 @@ where
     let scope = web::scope("/api")
         .wrap(HttpAuthentication::bearer(validator::<T>))
         .service(web::resource("/batch").route(web::post().to(batch::batch::<T>)))
+        .service(
+            web::resource("/schemas")
+                .route(web::get().to(schemas::schema_list::<T>))
+                .route(web::post().to(schemas::schema_create::<T>)),
+        )
+        .service(
+            web::resource("/schemas/{collection}")
+                .route(web::get().to(schemas::schema_get::<T>))
+                .route(web::put().to(schemas::schema_update::<T>))
+                .route(web::delete().to(schemas::schema_delete::<T>)),
+        )
+        .service(
+            web::resource("/schemas/{collection}/fields")
+                .route(web::post().to(schemas::schema_field_create::<T>)),
+        )
+        .service(
+            web::resource("/schemas/{collection}/fields/{field}")
+                .route(web::delete().to(schemas::schema_field_delete::<T>)),
+        )
         .service(
             web::resource("/collections/{collection}/{object_id}")
                 .route(web::get().to(collections::collection_get::<T>))
@@ -24,7 +81,9 @@ where
         .service(
             web::resource("/collections/{collection}")
                 .route(web::get().to(collections::collection_query::<T>))
-                .route(web::post().to(collections::collection_create::<T>)),
+                .route(web::post().to(collections::collection_create::<T>))
+                .route(web::put().to(collections::collection_bulk_update::<T>))
+                .route(web::delete().to(collections::collection_bulk_delete::<T>)),
         );
 
     conf.service(scope);
@@ -0,0 +1,169 @@
+use actix_web::{web, HttpResponse};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use serde::Deserialize;
+use tracing::debug;
+
+use vivalaakam_seattle_collection::{CollectionError, CollectionField, SchemaPolicy, Storage};
+
+use crate::routes::perform_result;
+use crate::App;
+
+#[derive(Debug, Deserialize)]
+pub struct SchemaRequest {
+    pub name: String,
+    #[serde(default)]
+    pub fields: Vec<CollectionField>,
+    pub policy: Option<SchemaPolicy>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SchemaPolicyRequest {
+    pub policy: Option<SchemaPolicy>,
+}
+
+// schema changes need the master code whatever the schema policy says
+fn require_master<T>(
+    app: &App<T>,
+    auth: &BearerAuth,
+    collection_name: &str,
+) -> Result<(), CollectionError>
+where
+    T: Storage,
+{
+    app.is_master(auth.token())
+        .then_some(())
+        .ok_or(CollectionError::SchemaAccessDenied {
+            collection: collection_name.to_string(),
+        })
+}
+
+pub async fn schema_list<T>(app: web::Data<App<T>>) -> HttpResponse
+where
+    T: Storage,
+{
+    debug!("schema_list");
+
+    perform_result(Ok(app.get_collections().schemas()))
+}
+
+pub async fn schema_get<T>(path: web::Path<String>, app: web::Data<App<T>>) -> HttpResponse
+where
+    T: Storage,
+{
+    debug!("schema_get {path:?}");
+    let collection_name = path.into_inner();
+
+    perform_result(app.get_collections().schema(collection_name))
+}
+
+pub async fn schema_create<T>(
+    data: web::Json<SchemaRequest>,
+    auth: BearerAuth,
+    app: web::Data<App<T>>,
+) -> HttpResponse
+where
+    T: Storage,
+{
+    debug!("schema_create {data:?}");
+    let data = data.into_inner();
+
+    if let Err(error) = require_master(&app, &auth, &data.name) {
+        return perform_result::<()>(Err(error));
+    }
+
+    perform_result(
+        app.get_collections()
+            .create_schema(data.name, data.fields, data.policy, true)
+            .await,
+    )
+}
+
+pub async fn schema_delete<T>(
+    path: web::Path<String>,
+    auth: BearerAuth,
+    app: web::Data<App<T>>,
+) -> HttpResponse
+where
+    T: Storage,
+{
+    debug!("schema_delete {path:?}");
+    let collection_name = path.into_inner();
+
+    if let Err(error) = require_master(&app, &auth, &collection_name) {
+        return perform_result::<()>(Err(error));
+    }
+
+    perform_result(
+        app.get_collections()
+            .remove_schema(collection_name, true)
+            .await,
+    )
+}
+
+pub async fn schema_update<T>(
+    path: web::Path<String>,
+    data: web::Json<SchemaPolicyRequest>,
+    auth: BearerAuth,
+    app: web::Data<App<T>>,
+) -> HttpResponse
+where
+    T: Storage,
+{
+    debug!("schema_update {path:?} {data:?}");
+    let collection_name = path.into_inner();
+
+    if let Err(error) = require_master(&app, &auth, &collection_name) {
+        return perform_result::<()>(Err(error));
+    }
+
+    perform_result(
+        app.get_collections()
+            .set_policy(collection_name, data.into_inner().policy, true)
+            .await,
+    )
+}
+
+pub async fn schema_field_create<T>(
+    path: web::Path<String>,
+    data: web::Json<CollectionField>,
+    auth: BearerAuth,
+    app: web::Data<App<T>>,
+) -> HttpResponse
+where
+    T: Storage,
+{
+    debug!("schema_field_create {path:?}");
+    let collection_name = path.into_inner();
+
+    if let Err(error) = require_master(&app, &auth, &collection_name) {
+        return perform_result::<()>(Err(error));
+    }
+
+    perform_result(
+        app.get_collections()
+            .insert_field(collection_name, data.into_inner(), true)
+            .await,
+    )
+}
+
+pub async fn schema_field_delete<T>(
+    path: web::Path<(String, String)>,
+    auth: BearerAuth,
+    app: web::Data<App<T>>,
+) -> HttpResponse
+where
+    T: Storage,
+{
+    debug!("schema_field_delete {path:?}");
+    let (collection_name, field_name) = path.into_inner();
+
+    if let Err(error) = require_master(&app, &auth, &collection_name) {
+        return perform_result::<()>(Err(error));
+    }
+
+    perform_result(
+        app.get_collections()
+            .remove_field(collection_name, field_name, true)
+            .await,
+    )
+}
@@ -14,6 +14,11 @@ pub enum CollectionAction {
         collection: String,
         data: Value,
     },
+    Upsert {
+        collection: String,
+        data: Value,
+        key: Option<String>,
+    },
     Update {
         collection: String,
         identifier: String,
@@ -26,12 +31,30 @@ pub enum CollectionAction {
     Get {
         collection: String,
         identifier: String,
+        keys: Option<Vec<String>>,
+        #[serde(rename = "excludeKeys")]
+        exclude_keys: Option<Vec<String>>,
+    },
+    List {
+        collection: String,
+        #[serde(rename = "where")]
+        where_query: Option<Value>,
+        order: Option<String>,
+        limit: Option<usize>,
+        keys: Option<Vec<String>>,
+    },
+    Count {
+        collection: String,
+        #[serde(rename = "where")]
+        where_query: Option<Value>,
     },
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct BatchRequest {
     pub requests: Vec<CollectionAction>,
+    #[serde(default)]
+    pub transaction: bool,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -44,13 +67,36 @@ pub async fn batch_request<T1>(
     data: Vec<CollectionAction>,
     secret_code: &String,
 ) -> Result<BatchResponse, ErrorResponse>
+where
+    T1: Service<Request, Response = ServiceResponse, Error = Error>,
+{
+    send_batch_request(web_app, data, false, secret_code).await
+}
+
+pub async fn transaction_request<T1>(
+    web_app: &T1,
+    data: Vec<CollectionAction>,
+    secret_code: &String,
+) -> Result<BatchResponse, ErrorResponse>
+where
+    T1: Service<Request, Response = ServiceResponse, Error = Error>,
+{
+    send_batch_request(web_app, data, true, secret_code).await
+}
+
+async fn send_batch_request<T1>(
+    web_app: &T1,
+    data: Vec<CollectionAction>,
+    transaction: bool,
+    secret_code: &String,
+) -> Result<BatchResponse, ErrorResponse>
 where
     T1: Service<Request, Response = ServiceResponse, Error = Error>,
 {
     let req = test::TestRequest::post()
-        .uri(&format!("/api/batch"))
+        .uri("/api/batch")
         .insert_header(("authorization", format!("Bearer {secret_code}")))
-        .set_json(json!({ "requests": data }))
+        .set_json(json!({ "requests": data, "transaction": transaction }))
         .to_request();
 
     let resp = web_app.call(req).await.unwrap();
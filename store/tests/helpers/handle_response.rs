@@ -20,7 +20,10 @@ pub async fn handle_response<T: de::DeserializeOwned>(
         | http::StatusCode::BAD_REQUEST
         | http::StatusCode::FORBIDDEN => {
             let row = to_bytes(response.into_body()).await.unwrap();
-            let err: ErrorResponse = serde_json::from_slice(&row).unwrap();
+            let err: ErrorResponse =
+                serde_json::from_slice(&row).unwrap_or_else(|_| ErrorResponse {
+                    error: String::from_utf8_lossy(&row).to_string(),
+                });
 
             Err(err)
         }
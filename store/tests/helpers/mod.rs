@@ -1,8 +1,13 @@
+#![allow(dead_code)]
+
 pub mod batch_request;
 pub mod collection_response;
 pub mod create_request;
 pub mod delete_request;
 pub mod error_response;
+pub mod get_query_request;
 pub mod get_request;
 pub mod handle_response;
+pub mod query_request;
+pub mod schema_request;
 pub mod update_request;
@@ -0,0 +1,31 @@
+use actix_http::body::to_bytes;
+use actix_http::{Method, Request, StatusCode};
+use actix_web::dev::{Service, ServiceResponse};
+use actix_web::{test, Error};
+use serde_json::Value;
+
+pub async fn schema_request<T1>(
+    web_app: &T1,
+    method: Method,
+    path: &str,
+    data: Option<Value>,
+    secret_code: &String,
+) -> (StatusCode, Value)
+where
+    T1: Service<Request, Response = ServiceResponse, Error = Error>,
+{
+    let mut req = test::TestRequest::default()
+        .method(method)
+        .uri(&format!("/api/schemas{path}"))
+        .insert_header(("authorization", format!("Bearer {secret_code}")));
+
+    if let Some(data) = data {
+        req = req.set_json(data);
+    }
+
+    let resp = web_app.call(req.to_request()).await.unwrap();
+    let status = resp.status();
+    let body = to_bytes(resp.into_body()).await.unwrap();
+
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
@@ -3,12 +3,11 @@ use std::env;
 use actix_web::{test, web, App as WebApp};
 use dotenv::dotenv;
 use serde_json::json;
-use tracing::info;
 use tracing_subscriber::filter::LevelFilter;
 
-use store::{routes, App};
 use vivalaakam_seattle_collection::{Collection, Collections, Storage};
 use vivalaakam_seattle_collection_postgres::StorePostgresql;
+use vivalaakam_seattle_store::{routes, App};
 
 use crate::helpers::batch_request::{batch_request, CollectionAction};
 use crate::helpers::collection_response::CollectionResponse;
@@ -74,7 +73,7 @@ async fn store_batch() {
 
     let first_row = match rows
         .results
-        .get(0)
+        .first()
         .map(|v| serde_json::from_value::<CollectionResponse>(v.clone()))
     {
         Some(Ok(row)) => {
@@ -83,8 +82,7 @@ async fn store_batch() {
             row.id
         }
         _ => {
-            assert!(false);
-            "".to_string()
+            panic!("unexpected batch result");
         }
     };
 
@@ -99,8 +97,7 @@ async fn store_batch() {
             row.id
         }
         _ => {
-            assert!(false);
-            "".to_string()
+            panic!("unexpected batch result");
         }
     };
 
@@ -115,8 +112,7 @@ async fn store_batch() {
             row.id
         }
         _ => {
-            assert!(false);
-            "".to_string()
+            panic!("unexpected batch result");
         }
     };
 
@@ -129,8 +125,7 @@ async fn store_batch() {
             assert_eq!(row.age, 30);
         }
         Err(err) => {
-            info!("err = {err:?}");
-            assert!(false);
+            panic!("err = {err:?}");
         }
     }
 
@@ -144,8 +139,7 @@ async fn store_batch() {
             assert_eq!(row.age, 31);
         }
         Err(err) => {
-            info!("err = {err:?}");
-            assert!(false);
+            panic!("err = {err:?}");
         }
     }
 
@@ -166,6 +160,8 @@ async fn store_batch() {
         CollectionAction::Get {
             collection: table_name.to_string(),
             identifier: first_row,
+            keys: None,
+            exclude_keys: None,
         },
     ];
 
@@ -176,14 +172,14 @@ async fn store_batch() {
 
     match rows
         .results
-        .get(0)
+        .first()
         .map(|v| serde_json::from_value::<CollectionResponse>(v.clone()))
     {
         Some(Ok(row)) => {
             assert_eq!(row.name, "test4");
             assert_eq!(row.age, 34);
         }
-        _ => assert!(false),
+        _ => panic!("unexpected batch result"),
     }
 
     match rows
@@ -195,7 +191,7 @@ async fn store_batch() {
             assert_eq!(row.name, "test5");
             assert_eq!(row.age, 35);
         }
-        _ => assert!(false),
+        _ => panic!("unexpected batch result"),
     }
 
     match rows
@@ -207,6 +203,6 @@ async fn store_batch() {
             assert_eq!(row.name, "test1");
             assert_eq!(row.age, 30);
         }
-        _ => assert!(false),
+        _ => panic!("unexpected batch result"),
     }
 }
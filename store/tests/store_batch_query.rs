@@ -0,0 +1,79 @@
+use actix_web::{test, web, App as WebApp};
+use serde_json::json;
+
+use vivalaakam_seattle_collection::Collections;
+use vivalaakam_seattle_collection_memory::StoreMemory;
+use vivalaakam_seattle_store::{routes, App};
+
+use crate::helpers::batch_request::{batch_request, CollectionAction};
+
+mod helpers;
+
+#[tokio::test]
+async fn store_batch_query() {
+    let collections = Collections::new(StoreMemory::new()).await;
+
+    for (name, age) in [("a", 10), ("b", 20), ("c", 30)] {
+        collections
+            .insert("Users".to_string(), json!({ "name": name, "age": age }))
+            .await
+            .unwrap();
+    }
+
+    collections
+        .insert("Orders".to_string(), json!({ "total": 5 }))
+        .await
+        .unwrap();
+
+    let secret_code = "secret_code".to_string();
+
+    let app = App::new(collections, secret_code.to_string());
+
+    let web_app = test::init_service(
+        WebApp::new()
+            .app_data(web::Data::new(app.clone()))
+            .configure(routes::config::<StoreMemory>),
+    )
+    .await;
+
+    let requests = vec![
+        CollectionAction::List {
+            collection: "Users".to_string(),
+            where_query: Some(json!({ "age": { "$gte": 20 } })),
+            order: Some("-age".to_string()),
+            limit: Some(1),
+            keys: Some(vec!["name".to_string()]),
+        },
+        CollectionAction::Count {
+            collection: "Users".to_string(),
+            where_query: None,
+        },
+        CollectionAction::Count {
+            collection: "Orders".to_string(),
+            where_query: Some(json!({ "total": { "$gt": 10 } })),
+        },
+        CollectionAction::List {
+            collection: "Users".to_string(),
+            where_query: Some(json!({ "name": { "$ref": "0.0.name" } })),
+            order: None,
+            limit: None,
+            keys: None,
+        },
+    ];
+
+    let rows = batch_request(&web_app, requests, &secret_code)
+        .await
+        .unwrap();
+
+    let users = rows.results[0].as_array().unwrap();
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0]["name"], "c");
+    assert!(users[0].get("age").is_none());
+
+    assert_eq!(rows.results[1]["count"], 3);
+    assert_eq!(rows.results[2]["count"], 0);
+
+    let users = rows.results[3].as_array().unwrap();
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0]["age"].as_f64(), Some(30.0));
+}
@@ -0,0 +1,131 @@
+use actix_web::{test, web, App as WebApp};
+use serde_json::json;
+
+use vivalaakam_seattle_collection::Collections;
+use vivalaakam_seattle_collection_memory::StoreMemory;
+use vivalaakam_seattle_store::{routes, App};
+
+use crate::helpers::batch_request::{batch_request, CollectionAction};
+
+mod helpers;
+
+#[tokio::test]
+async fn store_batch_reference() {
+    let collections = Collections::new(StoreMemory::new()).await;
+
+    let secret_code = "secret_code".to_string();
+
+    let app = App::new(collections, secret_code.to_string());
+
+    let web_app = test::init_service(
+        WebApp::new()
+            .app_data(web::Data::new(app.clone()))
+            .configure(routes::config::<StoreMemory>),
+    )
+    .await;
+
+    let requests = vec![
+        CollectionAction::Create {
+            collection: "Posts".to_string(),
+            data: json!({ "title": "post" }),
+        },
+        CollectionAction::Create {
+            collection: "Comments".to_string(),
+            data: json!({ "text": "first", "post": { "$ref": "0.id" } }),
+        },
+        CollectionAction::Create {
+            collection: "Comments".to_string(),
+            data: json!({ "text": "second", "post": { "$ref": "0.id" }, "reply": [{ "$ref": "1.id" }] }),
+        },
+        CollectionAction::Get {
+            collection: "Comments".to_string(),
+            identifier: "$2.id".to_string(),
+            keys: None,
+            exclude_keys: None,
+        },
+    ];
+
+    let rows = batch_request(&web_app, requests, &secret_code)
+        .await
+        .unwrap();
+
+    let post_id = &rows.results[0]["id"];
+
+    assert_eq!(&rows.results[1]["post"], post_id);
+    assert_eq!(&rows.results[2]["post"], post_id);
+    assert_eq!(rows.results[2]["reply"], json!([rows.results[1]["id"]]));
+    assert_eq!(rows.results[3]["text"], "second");
+
+    // bare strings in data are values, not references
+    let requests = vec![
+        CollectionAction::Create {
+            collection: "Posts".to_string(),
+            data: json!({ "title": "post" }),
+        },
+        CollectionAction::Create {
+            collection: "Notes".to_string(),
+            data: json!({ "price": "$1.50", "ref": "$0.id" }),
+        },
+    ];
+
+    let rows = batch_request(&web_app, requests, &secret_code)
+        .await
+        .unwrap();
+
+    assert_eq!(rows.results[1]["price"], "$1.50");
+    assert_eq!(rows.results[1]["ref"], "$0.id");
+
+    // references to failed or later actions are reported per action
+    let requests = vec![
+        CollectionAction::Get {
+            collection: "Posts".to_string(),
+            identifier: "missing".to_string(),
+            keys: None,
+            exclude_keys: None,
+        },
+        CollectionAction::Create {
+            collection: "Comments".to_string(),
+            data: json!({ "text": "orphan", "post": { "$ref": "0.id" } }),
+        },
+        CollectionAction::Create {
+            collection: "Comments".to_string(),
+            data: json!({ "text": "orphan", "post": { "$ref": "5.id" } }),
+        },
+        CollectionAction::Get {
+            collection: "Posts".to_string(),
+            identifier: "$0.missing".to_string(),
+            keys: None,
+            exclude_keys: None,
+        },
+        CollectionAction::Create {
+            collection: "Posts".to_string(),
+            data: json!({ "title": "counted", "views": 3 }),
+        },
+        CollectionAction::Get {
+            collection: "Posts".to_string(),
+            identifier: "$4.views".to_string(),
+            keys: None,
+            exclude_keys: None,
+        },
+    ];
+
+    let rows = batch_request(&web_app, requests, &secret_code)
+        .await
+        .unwrap();
+
+    let error = &rows.results[1]["InvalidReference"];
+    assert_eq!(error["reference"], "0.id");
+    assert!(error["error"]
+        .as_str()
+        .unwrap()
+        .starts_with("action 0 failed"));
+
+    let error = &rows.results[2]["InvalidReference"];
+    assert_eq!(error["reference"], "5.id");
+
+    let error = &rows.results[3]["InvalidReference"];
+    assert_eq!(error["reference"], "0.missing");
+
+    let error = &rows.results[5]["InvalidReference"];
+    assert_eq!(error["reference"], "4.views");
+}
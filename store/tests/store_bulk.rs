@@ -0,0 +1,110 @@
+use actix_http::StatusCode;
+use actix_web::dev::Service;
+use actix_web::{test, web, App as WebApp};
+use serde_json::{json, Value};
+
+use vivalaakam_seattle_collection::Collections;
+use vivalaakam_seattle_collection_memory::StoreMemory;
+use vivalaakam_seattle_store::{routes, App};
+
+use crate::helpers::collection_response::CollectionResponse;
+use crate::helpers::create_request::create_request;
+
+mod helpers;
+
+#[tokio::test]
+async fn store_bulk() {
+    let collections = Collections::new(StoreMemory::new()).await;
+
+    let secret_code = "secret_code".to_string();
+
+    let app = App::new(collections, secret_code.to_string());
+
+    let web_app = test::init_service(
+        WebApp::new()
+            .app_data(web::Data::new(app.clone()))
+            .configure(routes::config::<StoreMemory>),
+    )
+    .await;
+
+    let table_name = "Sessions".to_string();
+
+    for (name, age) in [("a", 10), ("b", 20), ("c", 30)] {
+        create_request::<_, CollectionResponse>(
+            &web_app,
+            &table_name,
+            json!({ "name": name, "age": age }),
+            &secret_code,
+        )
+        .await
+        .unwrap();
+    }
+
+    let req = test::TestRequest::put()
+        .uri("/api/collections/Sessions?where=%7B%22age%22%3A%7B%22%24gt%22%3A15%7D%7D")
+        .insert_header(("authorization", format!("Bearer {secret_code}")))
+        .set_json(json!({ "name": "expired" }))
+        .to_request();
+
+    let resp = web_app.call(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body, json!({ "count": 2 }));
+
+    // malformed bodies and filters are client errors
+    for (method, uri, body) in [
+        (
+            "PUT",
+            "/api/collections/Sessions?where=%7B%22age%22%3A10%7D",
+            "{",
+        ),
+        ("POST", "/api/collections/Sessions", "{"),
+        ("GET", "/api/collections/Sessions?where=%7B", ""),
+    ] {
+        let req = test::TestRequest::default()
+            .method(method.parse().unwrap())
+            .uri(uri)
+            .insert_header(("authorization", format!("Bearer {secret_code}")))
+            .set_payload(body)
+            .to_request();
+
+        let resp = web_app.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    // a bulk write without a filter is rejected
+    let req = test::TestRequest::delete()
+        .uri("/api/collections/Sessions")
+        .insert_header(("authorization", format!("Bearer {secret_code}")))
+        .to_request();
+
+    let resp = web_app.call(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    // neither does a filter on a misspelled field
+    let req = test::TestRequest::delete()
+        .uri("/api/collections/Sessions?where=%7B%22stauts%22%3A%22x%22%7D")
+        .insert_header(("authorization", format!("Bearer {secret_code}")))
+        .to_request();
+
+    let resp = web_app.call(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let req = test::TestRequest::delete()
+        .uri("/api/collections/Sessions?where=%7B%22name%22%3A%22expired%22%7D")
+        .insert_header(("authorization", format!("Bearer {secret_code}")))
+        .to_request();
+
+    let resp = web_app.call(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body: Value = test::read_body_json(resp).await;
+    assert_eq!(body, json!({ "count": 2 }));
+
+    let count = app
+        .get_collections()
+        .count(table_name.to_string(), json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(count, 1);
+}
@@ -6,9 +6,9 @@ use serde_json::json;
 use tracing::info;
 use tracing_subscriber::filter::LevelFilter;
 
-use store::{routes, App};
 use vivalaakam_seattle_collection::{Collection, Collections, Storage};
 use vivalaakam_seattle_collection_postgres::StorePostgresql;
+use vivalaakam_seattle_store::{routes, App};
 
 use crate::helpers::collection_response::CollectionResponse;
 use crate::helpers::create_request::create_request;
@@ -0,0 +1,62 @@
+use actix_web::{test, web, App as WebApp};
+use serde_json::json;
+
+use vivalaakam_seattle_collection::Collections;
+use vivalaakam_seattle_collection_memory::StoreMemory;
+use vivalaakam_seattle_store::{routes, App};
+
+use crate::helpers::collection_response::CollectionResponse;
+use crate::helpers::create_request::create_request;
+use crate::helpers::get_request::get_request;
+use crate::helpers::update_request::update_request;
+
+mod helpers;
+
+#[tokio::test]
+async fn store_memory() {
+    let collections = Collections::new(StoreMemory::new()).await;
+
+    let secret_code = "secret_code".to_string();
+
+    let app = App::new(collections, secret_code.to_string());
+
+    let web_app = test::init_service(
+        WebApp::new()
+            .app_data(web::Data::new(app.clone()))
+            .configure(routes::config::<StoreMemory>),
+    )
+    .await;
+
+    let table_name = "Collection1".to_string();
+
+    let row = create_request::<_, CollectionResponse>(
+        &web_app,
+        &table_name,
+        json!({"name": "test","age": 10}),
+        &secret_code,
+    )
+    .await;
+
+    assert!(row.is_ok());
+    let row = row.unwrap();
+    assert_eq!(row.name, "test");
+    assert_eq!(row.age, 10);
+
+    let row_update = update_request::<_, CollectionResponse>(
+        &web_app,
+        &table_name,
+        &row.id,
+        json!({"name": "test2","age": 11}),
+        &secret_code,
+    )
+    .await;
+
+    let row_check =
+        get_request::<_, CollectionResponse>(&web_app, &table_name, &row.id, &secret_code).await;
+
+    assert_eq!(row_update, row_check);
+
+    let row_check = row_check.unwrap();
+    assert_eq!(row_check.name, "test2");
+    assert_eq!(row_check.age, 11);
+}
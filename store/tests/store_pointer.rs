@@ -0,0 +1,86 @@
+use actix_web::{test, web, App as WebApp};
+use serde_json::{json, Value};
+
+use vivalaakam_seattle_collection::{Collections, Pointer};
+use vivalaakam_seattle_collection_memory::StoreMemory;
+use vivalaakam_seattle_store::{routes, App};
+
+use crate::helpers::create_request::create_request;
+use crate::helpers::get_query_request::get_query_request;
+use crate::helpers::query_request::query_request;
+
+mod helpers;
+
+#[tokio::test]
+async fn store_pointer() {
+    let collections = Collections::new(StoreMemory::new()).await;
+
+    let secret_code = "secret_code".to_string();
+
+    let app = App::new(collections, secret_code.to_string());
+
+    let web_app = test::init_service(
+        WebApp::new()
+            .app_data(web::Data::new(app.clone()))
+            .configure(routes::config::<StoreMemory>),
+    )
+    .await;
+
+    let companies = "Companies".to_string();
+    let users = "Users".to_string();
+    let posts = "Posts".to_string();
+
+    let company = create_request::<_, Value>(
+        &web_app,
+        &companies,
+        json!({ "name": "acme" }),
+        &secret_code,
+    )
+    .await
+    .unwrap();
+
+    let company = Pointer::new(&companies, company["id"].as_str().unwrap());
+
+    let author = create_request::<_, Value>(
+        &web_app,
+        &users,
+        json!({ "name": "john", "company": company.to_value() }),
+        &secret_code,
+    )
+    .await
+    .unwrap();
+
+    let author = Pointer::new(&users, author["id"].as_str().unwrap());
+
+    let post = create_request::<_, Value>(
+        &web_app,
+        &posts,
+        json!({ "title": "hello", "author": author.to_value() }),
+        &secret_code,
+    )
+    .await
+    .unwrap();
+
+    let post_id = post["id"].as_str().unwrap().to_string();
+
+    let post = get_query_request::<_, Value>(
+        &web_app,
+        &posts,
+        &post_id,
+        "include=author.company",
+        &secret_code,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(post["author"]["name"], "john");
+    assert_eq!(post["author"]["company"]["name"], "acme");
+
+    let rows = query_request::<_, Vec<Value>>(&web_app, &posts, "include=author", &secret_code)
+        .await
+        .unwrap();
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0]["author"]["name"], "john");
+    assert_eq!(rows[0]["author"]["company"], company.to_value());
+}
@@ -0,0 +1,184 @@
+use actix_http::{Method, StatusCode};
+use actix_web::{test, web, App as WebApp};
+use serde_json::json;
+
+use vivalaakam_seattle_collection::{Collections, SchemaPolicy};
+use vivalaakam_seattle_collection_memory::StoreMemory;
+use vivalaakam_seattle_store::{routes, App};
+
+use crate::helpers::collection_response::CollectionResponse;
+use crate::helpers::create_request::create_request;
+use crate::helpers::schema_request::schema_request;
+use crate::helpers::update_request::update_request;
+
+mod helpers;
+
+#[tokio::test]
+async fn store_policy() {
+    let collections = Collections::new(StoreMemory::new())
+        .await
+        .with_policy(SchemaPolicy::Strict);
+
+    let secret_code = "secret_code".to_string();
+    let master_code = "master_code".to_string();
+
+    let app = App::new(collections, secret_code.to_string()).with_master_code(master_code.clone());
+
+    let web_app = test::init_service(
+        WebApp::new()
+            .app_data(web::Data::new(app.clone()))
+            .configure(routes::config::<StoreMemory>),
+    )
+    .await;
+
+    let table_name = "Posts".to_string();
+
+    // strict: no implicit collections or fields
+
+    let result = create_request::<_, CollectionResponse>(
+        &web_app,
+        &table_name,
+        json!({"name": "test1", "age": 30}),
+        &secret_code,
+    )
+    .await;
+
+    assert!(result.is_err());
+
+    let (status, _) = schema_request(
+        &web_app,
+        Method::POST,
+        "",
+        Some(json!({
+            "name": table_name,
+            "fields": [
+                { "name": "name", "field_type": "String" },
+                { "name": "age", "field_type": "Number" }
+            ]
+        })),
+        &master_code,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+
+    let row = create_request::<_, CollectionResponse>(
+        &web_app,
+        &table_name,
+        json!({"name": "test1", "age": 30}),
+        &secret_code,
+    )
+    .await
+    .expect("declared fields must be accepted");
+
+    assert_eq!(row.name, "test1");
+
+    let result = update_request::<_, CollectionResponse>(
+        &web_app,
+        &table_name,
+        &row.id,
+        json!({"unknown": true}),
+        &secret_code,
+    )
+    .await;
+
+    assert!(result.is_err());
+
+    let (status, _) = schema_request(
+        &web_app,
+        Method::POST,
+        &format!("/{table_name}/fields"),
+        Some(json!({ "name": "unknown", "field_type": "Boolean" })),
+        &secret_code,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    // admin only: schema changes require the master code
+
+    let (status, _) = schema_request(
+        &web_app,
+        Method::PUT,
+        &format!("/{table_name}"),
+        Some(json!({"policy": "admin_only"})),
+        &secret_code,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, schema) = schema_request(
+        &web_app,
+        Method::PUT,
+        &format!("/{table_name}"),
+        Some(json!({"policy": "admin_only"})),
+        &master_code,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(schema["policy"], "admin_only");
+
+    let (status, _) = schema_request(
+        &web_app,
+        Method::POST,
+        &format!("/{table_name}/fields"),
+        Some(json!({ "name": "title", "field_type": "String" })),
+        &secret_code,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let result = create_request::<_, CollectionResponse>(
+        &web_app,
+        &table_name,
+        json!({"name": "test2", "age": 31, "title": "title"}),
+        &secret_code,
+    )
+    .await;
+
+    assert!(result.is_err());
+
+    let row = create_request::<_, CollectionResponse>(
+        &web_app,
+        &table_name,
+        json!({"name": "test2", "age": 31, "title": "title"}),
+        &master_code,
+    )
+    .await
+    .expect("master code may add fields");
+
+    assert_eq!(row.name, "test2");
+
+    let (status, schema) = schema_request(
+        &web_app,
+        Method::GET,
+        &format!("/{table_name}"),
+        None,
+        &secret_code,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(schema["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|field| field["name"] == "title"));
+
+    // back to the global policy
+
+    let (status, schema) = schema_request(
+        &web_app,
+        Method::PUT,
+        &format!("/{table_name}"),
+        Some(json!({"policy": null})),
+        &master_code,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(schema["policy"], json!(null));
+}
@@ -0,0 +1,121 @@
+use actix_http::StatusCode;
+use actix_web::dev::Service;
+use actix_web::http::header;
+use actix_web::{test, web, App as WebApp};
+use serde_json::json;
+
+use vivalaakam_seattle_collection::Collections;
+use vivalaakam_seattle_collection_memory::StoreMemory;
+use vivalaakam_seattle_store::{routes, App};
+
+#[tokio::test]
+async fn store_precondition() {
+    let collections = Collections::new(StoreMemory::new()).await;
+
+    let secret_code = "secret_code".to_string();
+
+    let app = App::new(collections, secret_code.to_string());
+
+    let web_app = test::init_service(
+        WebApp::new()
+            .app_data(web::Data::new(app.clone()))
+            .configure(routes::config::<StoreMemory>),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/api/collections/Posts")
+        .insert_header(("authorization", format!("Bearer {secret_code}")))
+        .set_json(json!({ "name": "test" }))
+        .to_request();
+
+    let resp = web_app.call(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let etag = resp.headers().get(header::ETAG).unwrap().clone();
+    let row: serde_json::Value = test::read_body_json(resp).await;
+    let uri = format!("/api/collections/Posts/{}", row["id"].as_str().unwrap());
+
+    std::thread::sleep(std::time::Duration::from_millis(5));
+
+    let req = test::TestRequest::put()
+        .uri(&uri)
+        .insert_header(("authorization", format!("Bearer {secret_code}")))
+        .insert_header((header::IF_MATCH, etag.clone()))
+        .set_json(json!({ "name": "first" }))
+        .to_request();
+
+    let resp = web_app.call(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_ne!(resp.headers().get(header::ETAG), Some(&etag));
+
+    // the etag is stale after the first update
+    let req = test::TestRequest::put()
+        .uri(&uri)
+        .insert_header(("authorization", format!("Bearer {secret_code}")))
+        .insert_header((header::IF_MATCH, etag.clone()))
+        .set_json(json!({ "name": "second" }))
+        .to_request();
+
+    let resp = web_app.call(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+
+    let req = test::TestRequest::delete()
+        .uri(&uri)
+        .insert_header(("authorization", format!("Bearer {secret_code}")))
+        .insert_header((header::IF_MATCH, etag))
+        .to_request();
+
+    let resp = web_app.call(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+
+    let req = test::TestRequest::get()
+        .uri(&uri)
+        .insert_header(("authorization", format!("Bearer {secret_code}")))
+        .to_request();
+
+    let resp = web_app.call(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(resp.headers().contains_key(header::ETAG));
+    let row: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(row["name"], "first");
+
+    // where guard
+    let req = test::TestRequest::put()
+        .uri(&format!("{uri}?where=%7B%22name%22%3A%22second%22%7D"))
+        .insert_header(("authorization", format!("Bearer {secret_code}")))
+        .set_json(json!({ "name": "third" }))
+        .to_request();
+
+    let resp = web_app.call(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::PRECONDITION_FAILED);
+
+    // a guard on an unknown field is rejected
+    let req = test::TestRequest::put()
+        .uri(&format!("{uri}?where=%7B%22nmae%22%3A%22first%22%7D"))
+        .insert_header(("authorization", format!("Bearer {secret_code}")))
+        .set_json(json!({ "name": "third" }))
+        .to_request();
+
+    let resp = web_app.call(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let req = test::TestRequest::put()
+        .uri(&format!("{uri}?where=%7B%22name%22%3A%22first%22%7D"))
+        .insert_header(("authorization", format!("Bearer {secret_code}")))
+        .set_json(json!({ "name": "third" }))
+        .to_request();
+
+    let resp = web_app.call(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let etag = resp.headers().get(header::ETAG).unwrap().clone();
+
+    let req = test::TestRequest::delete()
+        .uri(&uri)
+        .insert_header(("authorization", format!("Bearer {secret_code}")))
+        .insert_header((header::IF_MATCH, etag))
+        .to_request();
+
+    let resp = web_app.call(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+}
@@ -0,0 +1,106 @@
+use actix_web::{test, web, App as WebApp};
+use serde_json::{json, Value};
+
+use vivalaakam_seattle_collection::Collections;
+use vivalaakam_seattle_collection_memory::StoreMemory;
+use vivalaakam_seattle_store::{routes, App};
+
+use crate::helpers::batch_request::{batch_request, CollectionAction};
+use crate::helpers::create_request::create_request;
+use crate::helpers::get_query_request::get_query_request;
+use crate::helpers::query_request::query_request;
+
+mod helpers;
+
+fn keys(value: &Value) -> Vec<String> {
+    let mut keys = value
+        .as_object()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    keys.sort();
+    keys
+}
+
+#[tokio::test]
+async fn store_projection() {
+    let collections = Collections::new(StoreMemory::new()).await;
+
+    let secret_code = "secret_code".to_string();
+
+    let app = App::new(collections, secret_code.to_string());
+
+    let web_app = test::init_service(
+        WebApp::new()
+            .app_data(web::Data::new(app.clone()))
+            .configure(routes::config::<StoreMemory>),
+    )
+    .await;
+
+    let table_name = "CollectionProjection".to_string();
+
+    let row = create_request::<_, Value>(
+        &web_app,
+        &table_name,
+        json!({ "name": "test", "age": 10, "blob": { "large": [1, 2, 3] } }),
+        &secret_code,
+    )
+    .await
+    .unwrap();
+
+    let row_id = row["id"].as_str().unwrap().to_string();
+
+    // get with keys
+
+    let result =
+        get_query_request::<_, Value>(&web_app, &table_name, &row_id, "keys=name", &secret_code)
+            .await
+            .unwrap();
+
+    assert_eq!(keys(&result), vec!["id", "name"]);
+    assert_eq!(result["name"], "test");
+
+    // get with excludeKeys, id is always returned
+
+    let result = get_query_request::<_, Value>(
+        &web_app,
+        &table_name,
+        &row_id,
+        "excludeKeys=blob,id",
+        &secret_code,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        keys(&result),
+        vec!["age", "created_at", "id", "name", "updated_at"]
+    );
+
+    // list with keys
+
+    let result = query_request::<_, Vec<Value>>(&web_app, &table_name, "keys=age", &secret_code)
+        .await
+        .unwrap();
+
+    assert_eq!(result.len(), 1);
+    assert_eq!(keys(&result[0]), vec!["age", "id"]);
+
+    // batch get with keys
+
+    let result = batch_request(
+        &web_app,
+        vec![CollectionAction::Get {
+            collection: table_name.to_string(),
+            identifier: row_id.to_string(),
+            keys: Some(vec!["blob".to_string()]),
+            exclude_keys: None,
+        }],
+        &secret_code,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(keys(&result.results[0]), vec!["blob", "id"]);
+}
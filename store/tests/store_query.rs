@@ -0,0 +1,88 @@
+use actix_web::{test, web, App as WebApp};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use vivalaakam_seattle_collection::Collections;
+use vivalaakam_seattle_collection_memory::StoreMemory;
+use vivalaakam_seattle_store::{routes, App};
+
+use crate::helpers::collection_response::CollectionResponse;
+use crate::helpers::create_request::create_request;
+use crate::helpers::query_request::query_request;
+
+mod helpers;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CountResponse {
+    results: Option<Vec<CollectionResponse>>,
+    count: i64,
+}
+
+#[tokio::test]
+async fn store_query() {
+    let collections = Collections::new(StoreMemory::new()).await;
+
+    let secret_code = "secret_code".to_string();
+
+    let app = App::new(collections, secret_code.to_string());
+
+    let web_app = test::init_service(
+        WebApp::new()
+            .app_data(web::Data::new(app.clone()))
+            .configure(routes::config::<StoreMemory>),
+    )
+    .await;
+
+    let table_name = "Collection1".to_string();
+
+    for i in 0..5 {
+        let row = create_request::<_, CollectionResponse>(
+            &web_app,
+            &table_name,
+            json!({"name": format!("test_{i}"), "age": 10 + i}),
+            &secret_code,
+        )
+        .await;
+
+        assert!(row.is_ok());
+    }
+
+    // order, limit and skip
+
+    let rows = query_request::<_, Vec<CollectionResponse>>(
+        &web_app,
+        &table_name,
+        "order=-age&limit=2&skip=1",
+        &secret_code,
+    )
+    .await
+    .unwrap();
+
+    let ages = rows.iter().map(|row| row.age).collect::<Vec<_>>();
+
+    assert_eq!(ages, vec![13, 12]);
+
+    // count with page
+
+    let rows = query_request::<_, CountResponse>(
+        &web_app,
+        &table_name,
+        "where=%7B%22age%22%3A%7B%22%24gte%22%3A12%7D%7D&count=1&limit=2",
+        &secret_code,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(rows.count, 3);
+    assert_eq!(rows.results.map(|results| results.len()), Some(2));
+
+    // count only
+
+    let rows =
+        query_request::<_, CountResponse>(&web_app, &table_name, "count=1&limit=0", &secret_code)
+            .await
+            .unwrap();
+
+    assert_eq!(rows.count, 5);
+    assert!(rows.results.is_none());
+}
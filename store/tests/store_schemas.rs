@@ -0,0 +1,195 @@
+use actix_http::{Method, StatusCode};
+use actix_web::{test, web, App as WebApp};
+use serde_json::{json, Value};
+
+use vivalaakam_seattle_collection::Collections;
+use vivalaakam_seattle_collection_memory::StoreMemory;
+use vivalaakam_seattle_store::{routes, App};
+
+use crate::helpers::create_request::create_request;
+use crate::helpers::schema_request::schema_request;
+
+mod helpers;
+
+fn field_names(schema: &Value) -> Vec<String> {
+    schema["fields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|field| field["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn store_schemas() {
+    let collections = Collections::new(StoreMemory::new()).await;
+
+    let secret_code = "secret_code".to_string();
+    let master_code = "master_code".to_string();
+
+    let app = App::new(collections, secret_code.to_string()).with_master_code(master_code.clone());
+
+    let web_app = test::init_service(
+        WebApp::new()
+            .app_data(web::Data::new(app.clone()))
+            .configure(routes::config::<StoreMemory>),
+    )
+    .await;
+
+    let table_name = "Posts".to_string();
+
+    // create
+
+    let (status, schema) = schema_request(
+        &web_app,
+        Method::POST,
+        "",
+        Some(json!({
+            "name": table_name,
+            "fields": [
+                { "name": "title", "field_type": "String", "required": true },
+                { "name": "views", "field_type": "Number", "default": 0 }
+            ]
+        })),
+        &master_code,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(schema["name"], "Posts");
+    assert_eq!(
+        field_names(&schema),
+        vec!["title", "views", "id", "created_at", "updated_at"]
+    );
+
+    let (status, _) = schema_request(
+        &web_app,
+        Method::POST,
+        "",
+        Some(json!({ "name": table_name })),
+        &master_code,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // the cache serves the new schema to the collection routes
+
+    let row = create_request::<_, Value>(
+        &web_app,
+        &table_name,
+        json!({ "title": "hello" }),
+        &secret_code,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(row["views"], 0.0);
+
+    let (status, _) = schema_request(
+        &web_app,
+        Method::POST,
+        "",
+        Some(json!({
+            "name": "Invalid",
+            "fields": [{ "name": "count", "field_type": "Number", "default": "zero" }]
+        })),
+        &master_code,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // list and get
+
+    let (status, schemas) = schema_request(&web_app, Method::GET, "", None, &secret_code).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(schemas.as_array().unwrap().len(), 1);
+
+    let (status, schema) =
+        schema_request(&web_app, Method::GET, "/Posts", None, &secret_code).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(schema["fields"][0]["required"], true);
+
+    let (status, _) = schema_request(&web_app, Method::GET, "/Unknown", None, &secret_code).await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // add and remove fields
+
+    let (status, schema) = schema_request(
+        &web_app,
+        Method::POST,
+        "/Posts/fields",
+        Some(json!({ "name": "tags", "field_type": "Array" })),
+        &master_code,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(field_names(&schema).contains(&"tags".to_string()));
+
+    let (status, schema) = schema_request(
+        &web_app,
+        Method::DELETE,
+        "/Posts/fields/views",
+        None,
+        &master_code,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(!field_names(&schema).contains(&"views".to_string()));
+
+    let (status, _) = schema_request(
+        &web_app,
+        Method::DELETE,
+        "/Posts/fields/id",
+        None,
+        &master_code,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (_, schema) = schema_request(&web_app, Method::GET, "/Posts", None, &secret_code).await;
+
+    assert_eq!(
+        field_names(&schema),
+        vec!["title", "id", "created_at", "updated_at", "tags"]
+    );
+
+    // schema changes need the master code even under the open policy
+
+    for (method, path, data) in [
+        (Method::POST, "", Some(json!({ "name": "Other" }))),
+        (
+            Method::POST,
+            "/Posts/fields",
+            Some(json!({ "name": "rating", "field_type": "Number" })),
+        ),
+        (Method::DELETE, "/Posts/fields/tags", None),
+        (Method::PUT, "/Posts", Some(json!({ "policy": "strict" }))),
+        (Method::DELETE, "/Posts", None),
+    ] {
+        let (status, _) = schema_request(&web_app, method, path, data, &secret_code).await;
+
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    let (_, schema) = schema_request(&web_app, Method::GET, "/Posts", None, &secret_code).await;
+
+    assert!(field_names(&schema).contains(&"tags".to_string()));
+
+    // drop
+
+    let (status, _) = schema_request(&web_app, Method::DELETE, "/Posts", None, &master_code).await;
+
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = schema_request(&web_app, Method::GET, "/Posts", None, &secret_code).await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,86 @@
+use actix_web::{test, web, App as WebApp};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use vivalaakam_seattle_collection::Collections;
+use vivalaakam_seattle_collection_memory::StoreMemory;
+use vivalaakam_seattle_store::{routes, App};
+
+use crate::helpers::batch_request::{transaction_request, CollectionAction};
+use crate::helpers::query_request::query_request;
+
+mod helpers;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CountResponse {
+    count: i64,
+}
+
+#[tokio::test]
+async fn store_transaction() {
+    let collections = Collections::new(StoreMemory::new()).await;
+
+    let secret_code = "secret_code".to_string();
+
+    let app = App::new(collections, secret_code.to_string());
+
+    let web_app = test::init_service(
+        WebApp::new()
+            .app_data(web::Data::new(app.clone()))
+            .configure(routes::config::<StoreMemory>),
+    )
+    .await;
+
+    let table_name = "Orders".to_string();
+
+    let result = transaction_request(
+        &web_app,
+        vec![
+            CollectionAction::Create {
+                collection: table_name.to_string(),
+                data: json!({ "name": "order1" }),
+            },
+            CollectionAction::Update {
+                collection: table_name.to_string(),
+                identifier: "missing".to_string(),
+                data: json!({ "name": "order2" }),
+            },
+        ],
+        &secret_code,
+    )
+    .await;
+
+    assert!(result.is_err());
+
+    // the failed batch left nothing behind, not even the implicit collection
+    assert!(app
+        .get_collections()
+        .schema(table_name.to_string())
+        .is_err());
+
+    let result = transaction_request(
+        &web_app,
+        vec![
+            CollectionAction::Create {
+                collection: table_name.to_string(),
+                data: json!({ "name": "order1" }),
+            },
+            CollectionAction::Create {
+                collection: table_name.to_string(),
+                data: json!({ "name": "order2" }),
+            },
+        ],
+        &secret_code,
+    )
+    .await
+    .expect("transaction must be committed");
+
+    assert_eq!(result.results.len(), 2);
+
+    let rows =
+        query_request::<_, CountResponse>(&web_app, &table_name, "count=1&limit=0", &secret_code)
+            .await
+            .unwrap();
+
+    assert_eq!(rows.count, 2);
+}
@@ -0,0 +1,86 @@
+use actix_http::{Method, StatusCode};
+use actix_web::{test, web, App as WebApp};
+use serde_json::json;
+
+use vivalaakam_seattle_collection::Collections;
+use vivalaakam_seattle_collection_memory::StoreMemory;
+use vivalaakam_seattle_store::{routes, App};
+
+use crate::helpers::batch_request::{batch_request, CollectionAction};
+use crate::helpers::schema_request::schema_request;
+
+mod helpers;
+
+#[tokio::test]
+async fn store_upsert() {
+    let collections = Collections::new(StoreMemory::new()).await;
+
+    let secret_code = "secret_code".to_string();
+    let master_code = "master_code".to_string();
+
+    let app = App::new(collections, secret_code.to_string()).with_master_code(master_code.clone());
+
+    let web_app = test::init_service(
+        WebApp::new()
+            .app_data(web::Data::new(app.clone()))
+            .configure(routes::config::<StoreMemory>),
+    )
+    .await;
+
+    let table_name = "Products".to_string();
+
+    let (status, _) = schema_request(
+        &web_app,
+        Method::POST,
+        "",
+        Some(json!({
+            "name": table_name,
+            "fields": [{ "name": "sku", "field_type": "String", "unique": true }]
+        })),
+        &master_code,
+    )
+    .await;
+
+    assert_eq!(status, StatusCode::OK);
+
+    let requests = vec![
+        CollectionAction::Upsert {
+            collection: table_name.to_string(),
+            data: json!({ "sku": "A-1", "price": 10 }),
+            key: Some("sku".to_string()),
+        },
+        CollectionAction::Upsert {
+            collection: table_name.to_string(),
+            data: json!({ "sku": "A-1", "price": 12 }),
+            key: Some("sku".to_string()),
+        },
+        CollectionAction::Upsert {
+            collection: table_name.to_string(),
+            data: json!({ "sku": "B-1", "price": 5 }),
+            key: Some("sku".to_string()),
+        },
+        CollectionAction::Upsert {
+            collection: table_name.to_string(),
+            data: json!({ "sku": "C-1", "price": 5 }),
+            key: Some("price".to_string()),
+        },
+    ];
+
+    let rows = batch_request(&web_app, requests, &secret_code)
+        .await
+        .unwrap()
+        .results;
+
+    assert_eq!(rows[0]["id"], rows[1]["id"]);
+    assert_ne!(rows[0]["id"], rows[2]["id"]);
+    assert_eq!(rows[1]["price"].as_f64(), Some(12.0));
+    assert!(rows[3].get("InvalidQuery").is_some());
+
+    let count = app
+        .get_collections()
+        .count(table_name.to_string(), json!({}))
+        .await
+        .unwrap();
+
+    assert_eq!(count, 2);
+}